@@ -16,6 +16,8 @@ use serde::Deserialize;
 use tracing::{debug, instrument};
 use yaml_rust2::{Yaml, YamlLoader, yaml::Hash};
 
+use crate::version::{self, RequirementError};
+
 /// Parsed configuration file contents.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct Config {
@@ -38,8 +40,265 @@ pub struct Config {
     #[serde(default)]
     pub overrides: Vec<OverrideRule>,
 
+    /// Template path -> destination path renames applied to every stack.
+    #[serde(default)]
+    pub map: BTreeMap<PathBuf, PathBuf>,
+
     #[serde(default)]
     pub recipes: BTreeMap<String, RecipeDef>,
+
+    pub remote: Option<RemoteDef>,
+
+    /// Custom `.gitignore` fragments by name; these shadow built-in fragments.
+    #[serde(default)]
+    pub gitignore: BTreeMap<String, Vec<String>>,
+
+    pub update_check: Option<UpdateCheckDef>,
+
+    /// Policy for existing binary files; unset means the normal decision flow.
+    pub binary_action: Option<BinaryAction>,
+
+    /// Policy for an existing license file; unset means the normal decision flow.
+    pub license_conflict: Option<LicenseConflictAction>,
+
+    /// Treat existing lockfiles like any other file instead of keeping them.
+    #[serde(default)]
+    pub apply_lockfiles: bool,
+
+    /// Handling of nested git repositories in the destination.
+    #[serde(default)]
+    pub nested_repos: NestedRepoAction,
+
+    /// What `apply` does when the destination has uncommitted changes.
+    #[serde(default)]
+    pub dirty_dest: DirtyDestAction,
+
+    /// Branch name template for `apply --branch-per-apply`.
+    pub apply_branch: Option<String>,
+
+    /// Extra per-file transform steps, in order, after rendering and remapping.
+    #[serde(default)]
+    pub transforms: Vec<TransformDef>,
+
+    /// pinit versions this config needs, e.g. `">=0.5"`; checked before parsing.
+    pub requires_pinit: Option<String>,
+
+    /// Absolute cache directory replacing the platform default.
+    pub cache_dir: Option<PathBuf>,
+
+    /// Template, target, or recipe `pinit new` uses when none is named.
+    pub default_recipe: Option<String>,
+}
+
+/// A configured transform step (`[[transforms]]`).
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "step", rename_all = "lowercase")]
+pub enum TransformDef {
+    /// Normalize line endings in text files, optionally only those matching `pattern`.
+    Eol {
+        eol: EolStyle,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+    /// Set permission bits (octal string, e.g. `"755"`) on files matching `pattern`.
+    Mode { mode: String, pattern: String },
+}
+
+/// Line ending style for the `eol` transform.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum EolStyle {
+    Lf,
+    Crlf,
+}
+
+impl EolStyle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "lf" => Some(Self::Lf),
+            "crlf" => Some(Self::Crlf),
+            _ => None,
+        }
+    }
+}
+
+/// TOML goes through [`EolStyle::parse`] too, so both formats ignore case alike.
+impl TryFrom<String> for EolStyle {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        EolStyle::parse(&value).ok_or_else(|| format!("eol must be lf or crlf (got {value:?})"))
+    }
+}
+
+/// How to handle an existing destination file when either side is binary.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum BinaryAction {
+    Skip,
+    Overwrite,
+    /// Ask even when the run is otherwise non-interactive (needs a terminal).
+    Prompt,
+}
+
+impl BinaryAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "skip" => Some(BinaryAction::Skip),
+            "overwrite" => Some(BinaryAction::Overwrite),
+            "prompt" => Some(BinaryAction::Prompt),
+            _ => None,
+        }
+    }
+}
+
+/// TOML goes through [`BinaryAction::parse`] too, so both formats ignore case alike.
+impl TryFrom<String> for BinaryAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        BinaryAction::parse(&value).ok_or_else(|| {
+            format!("binary_action must be skip, overwrite, or prompt (got {value:?})")
+        })
+    }
+}
+
+/// How to handle an existing license file when the configured license differs.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum LicenseConflictAction {
+    /// Never touch an existing license file.
+    Keep,
+    Overwrite,
+    /// Overwrite only when the existing file is the same license (e.g. to refresh the year).
+    Matching,
+    /// Ask even when the run is otherwise non-interactive (needs a terminal).
+    Prompt,
+}
+
+impl LicenseConflictAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "keep" => Some(LicenseConflictAction::Keep),
+            "overwrite" => Some(LicenseConflictAction::Overwrite),
+            "matching" => Some(LicenseConflictAction::Matching),
+            "prompt" => Some(LicenseConflictAction::Prompt),
+            _ => None,
+        }
+    }
+}
+
+/// TOML goes through [`LicenseConflictAction::parse`] too, so both formats ignore case alike.
+impl TryFrom<String> for LicenseConflictAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        LicenseConflictAction::parse(&value).ok_or_else(|| {
+            format!("license_conflict must be keep, overwrite, matching, or prompt (got {value:?})")
+        })
+    }
+}
+
+/// What to do with nested git repositories (submodules, vendored clones) in the destination.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum NestedRepoAction {
+    /// Leave everything inside them alone.
+    #[default]
+    Skip,
+    /// Write into them, but without the outer repository's ignore rules.
+    Enter,
+}
+
+impl NestedRepoAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "skip" => Some(NestedRepoAction::Skip),
+            "enter" => Some(NestedRepoAction::Enter),
+            _ => None,
+        }
+    }
+}
+
+/// TOML goes through [`NestedRepoAction::parse`] too, so both formats ignore case alike.
+impl TryFrom<String> for NestedRepoAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        NestedRepoAction::parse(&value)
+            .ok_or_else(|| format!("nested_repos must be skip or enter (got {value:?})"))
+    }
+}
+
+/// What `pinit apply` does when the destination has uncommitted git changes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum DirtyDestAction {
+    /// Print a warning and carry on.
+    #[default]
+    Warn,
+    /// Refuse to apply.
+    Abort,
+    /// `git stash` the changes (including untracked files) first.
+    Stash,
+}
+
+impl DirtyDestAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "warn" => Some(DirtyDestAction::Warn),
+            "abort" => Some(DirtyDestAction::Abort),
+            "stash" => Some(DirtyDestAction::Stash),
+            _ => None,
+        }
+    }
+}
+
+/// TOML goes through [`DirtyDestAction::parse`] too, so both formats ignore case alike.
+impl TryFrom<String> for DirtyDestAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        DirtyDestAction::parse(&value)
+            .ok_or_else(|| format!("dirty_dest must be warn, abort, or stash (got {value:?})"))
+    }
+}
+
+/// Opt-in check for newer pinit releases.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct UpdateCheckDef {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Git repository whose `vX.Y.Z` tags are compared with the running version.
+    pub repo: Option<String>,
+}
+
+/// Remote repository creation for `pinit new --create-remote`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct RemoteDef {
+    /// Command run in the new project directory; `{{ name }}` variables are expanded.
+    #[serde(default)]
+    pub command: Vec<String>,
+
+    /// URL to register as the git remote after the command succeeds.
+    pub url: Option<String>,
+
+    /// Git remote name. Default: `origin`.
+    pub name: Option<String>,
+
+    /// Message for the initial commit made by `pinit new --push`.
+    pub commit_message: Option<String>,
+
+    /// Command run after `--push`, e.g. to enable branch protection.
+    #[serde(default)]
+    pub protect: Vec<String>,
+}
+
+impl RemoteDef {
+    pub fn remote_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("origin")
+    }
 }
 
 /// License configuration for optional SPDX rendering.
@@ -75,10 +334,10 @@ impl LicenseDef {
                         .or_insert_with(|| year.to_string());
                 }
                 if let Some(name) = d.name.as_deref() {
-                    args.entry("fullname".to_string())
-                        .or_insert_with(|| name.to_string());
-                    args.entry("copyright holders".to_string())
-                        .or_insert_with(|| name.to_string());
+                    for placeholder in crate::licensing::HOLDER_PLACEHOLDERS {
+                        args.entry(placeholder.to_string())
+                            .or_insert_with(|| name.to_string());
+                    }
                 }
                 args
             }
@@ -98,7 +357,8 @@ pub struct LicenseDetailed {
     /// Convenience: fills the SPDX `year` template variable.
     pub year: Option<String>,
 
-    /// Convenience: fills the SPDX `fullname` template variable.
+    /// Convenience: fills every copyright-holder template variable (`fullname`,
+    /// `copyright holders`, `owner`, `name of author`) that `args` leaves unset.
     pub name: Option<String>,
 
     /// SPDX template variables by name, e.g. `copyright holders`.
@@ -184,6 +444,12 @@ pub enum TemplateDef {
     Detailed {
         source: Option<String>,
         path: PathBuf,
+        /// Other names that resolve to this template.
+        #[serde(default)]
+        aliases: Vec<String>,
+        /// Why the template should no longer be used, e.g. "use rust-2024 instead".
+        #[serde(default)]
+        deprecated: Option<String>,
     },
 }
 
@@ -201,6 +467,20 @@ impl TemplateDef {
             TemplateDef::Detailed { source, .. } => source.as_deref(),
         }
     }
+
+    pub fn aliases(&self) -> &[String] {
+        match self {
+            TemplateDef::Path(_) => &[],
+            TemplateDef::Detailed { aliases, .. } => aliases.as_slice(),
+        }
+    }
+
+    pub fn deprecated(&self) -> Option<&str> {
+        match self {
+            TemplateDef::Path(_) => None,
+            TemplateDef::Detailed { deprecated, .. } => deprecated.as_deref(),
+        }
+    }
 }
 
 /// Action to take when an override rule matches.
@@ -223,16 +503,70 @@ pub struct OverrideRule {
     pub action: OverrideAction,
 }
 
+/// One entry in a target or recipe template list.
+///
+/// Either a bare template name, or a table that also places the template's
+/// files under `dest_prefix` inside the destination.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum TemplateRef {
+    Name(String),
+    Detailed {
+        #[serde(alias = "name")]
+        template: String,
+        #[serde(default)]
+        dest_prefix: Option<PathBuf>,
+    },
+}
+
+impl TemplateRef {
+    pub fn name(&self) -> &str {
+        match self {
+            TemplateRef::Name(name) => name,
+            TemplateRef::Detailed { template, .. } => template,
+        }
+    }
+
+    pub fn dest_prefix(&self) -> Option<&Path> {
+        match self {
+            TemplateRef::Name(_) => None,
+            TemplateRef::Detailed { dest_prefix, .. } => dest_prefix.as_deref(),
+        }
+    }
+}
+
+/// Renders as `name`, or `name -> prefix` when the entry has a `dest_prefix`.
+impl fmt::Display for TemplateRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.dest_prefix() {
+            Some(prefix) => write!(f, "{} -> {}", self.name(), prefix.display()),
+            None => f.write_str(self.name()),
+        }
+    }
+}
+
+impl From<&str> for TemplateRef {
+    fn from(name: &str) -> Self {
+        TemplateRef::Name(name.to_string())
+    }
+}
+
+impl From<String> for TemplateRef {
+    fn from(name: String) -> Self {
+        TemplateRef::Name(name)
+    }
+}
+
 /// Target definition that can be a simple template list or a detailed object.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum TargetDef {
-    Templates(Vec<String>),
+    Templates(Vec<TemplateRef>),
     Detailed(TargetDetailed),
 }
 
 impl TargetDef {
-    pub fn templates(&self) -> &[String] {
+    pub fn templates(&self) -> &[TemplateRef] {
         match self {
             TargetDef::Templates(items) => items.as_slice(),
             TargetDef::Detailed(def) => def.templates.as_slice(),
@@ -245,23 +579,55 @@ impl TargetDef {
             TargetDef::Detailed(def) => def.overrides.as_slice(),
         }
     }
+
+    pub fn map(&self) -> Option<&BTreeMap<PathBuf, PathBuf>> {
+        match self {
+            TargetDef::Templates(_) => None,
+            TargetDef::Detailed(def) => Some(&def.map),
+        }
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        match self {
+            TargetDef::Templates(_) => &[],
+            TargetDef::Detailed(def) => def.aliases.as_slice(),
+        }
+    }
+
+    pub fn deprecated(&self) -> Option<&str> {
+        match self {
+            TargetDef::Templates(_) => None,
+            TargetDef::Detailed(def) => def.deprecated.as_deref(),
+        }
+    }
 }
 
 /// Detailed target definition with template list and overrides.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct TargetDetailed {
     #[serde(default)]
-    pub templates: Vec<String>,
+    pub templates: Vec<TemplateRef>,
 
     #[serde(default)]
     pub overrides: Vec<OverrideRule>,
+
+    #[serde(default)]
+    pub map: BTreeMap<PathBuf, PathBuf>,
+
+    /// Other names that resolve to this target.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// Why the target should no longer be used.
+    #[serde(default)]
+    pub deprecated: Option<String>,
 }
 
 /// Recipe definition made of template names and/or file sets.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct RecipeDef {
     #[serde(default)]
-    pub templates: Vec<String>,
+    pub templates: Vec<TemplateRef>,
 
     #[serde(default)]
     pub files: Vec<FileSetDef>,
@@ -269,8 +635,23 @@ pub struct RecipeDef {
     #[serde(default)]
     pub overrides: Vec<OverrideRule>,
 
+    #[serde(default)]
+    pub map: BTreeMap<PathBuf, PathBuf>,
+
     #[serde(default)]
     pub hooks: HookSet,
+
+    /// `.gitignore` fragments composed into the project's `.gitignore`.
+    #[serde(default)]
+    pub gitignore: Vec<String>,
+
+    /// Other names that resolve to this recipe.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// Why the recipe should no longer be used.
+    #[serde(default)]
+    pub deprecated: Option<String>,
 }
 
 /// File set definition for inline recipes.
@@ -288,13 +669,23 @@ pub struct FileSetDef {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ResolvedRecipe {
     pub name: String,
-    pub templates: Vec<String>,
+    pub templates: Vec<TemplateRef>,
     pub files: Vec<FileSetDef>,
     pub overrides: Vec<OverrideRule>,
+    /// Global `map` entries with the stack's own entries layered on top.
+    pub map: BTreeMap<PathBuf, PathBuf>,
     pub hooks: HookSet,
+    pub gitignore: Vec<String>,
     pub kind: ResolvedKind,
 }
 
+/// A target's template list with nested targets inlined.
+struct ExpandedTarget {
+    templates: Vec<TemplateRef>,
+    overrides: Vec<OverrideRule>,
+    map: BTreeMap<PathBuf, PathBuf>,
+}
+
 /// What kind of config entry resolved to a template stack.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResolvedKind {
@@ -303,6 +694,20 @@ pub enum ResolvedKind {
     Template,
 }
 
+/// Non-fatal problem found by [`Config::lint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigWarning {
+    /// Key path of the offending entry (e.g. `targets.rust[1]`).
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
 /// Errors encountered while loading configuration.
 #[derive(Debug)]
 pub enum ConfigError {
@@ -326,6 +731,11 @@ pub enum ConfigError {
         path: PathBuf,
         message: String,
     },
+    /// `requires_pinit` is malformed or not met by this build.
+    Requirement {
+        path: PathBuf,
+        source: RequirementError,
+    },
 }
 
 impl fmt::Display for ConfigError {
@@ -343,6 +753,9 @@ impl fmt::Display for ConfigError {
             ConfigError::InvalidConfig { path, message } => {
                 write!(f, "{}: {}", path.display(), message)
             }
+            ConfigError::Requirement { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
         }
     }
 }
@@ -352,6 +765,7 @@ impl std::error::Error for ConfigError {
         match self {
             ConfigError::Io { source, .. } => Some(source),
             ConfigError::ParseToml { source, .. } => Some(source),
+            ConfigError::Requirement { source, .. } => Some(source),
             _ => None,
         }
     }
@@ -381,12 +795,26 @@ pub fn default_config_paths() -> Vec<PathBuf> {
     out
 }
 
+/// Directory for state worth keeping (run history, update checks), as opposed
+/// to the deletable cache: `XDG_STATE_HOME/pinit`, else `~/.local/state/pinit`.
+pub fn state_dir() -> Option<PathBuf> {
+    if let Some(xdg) = env::var_os("XDG_STATE_HOME").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(xdg).join("pinit"));
+    }
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state/pinit"))
+}
+
 /// Load configuration from disk, optionally overriding the discovery path.
 pub fn load_config(path_override: Option<&Path>) -> Result<(PathBuf, Config), ConfigError> {
     if let Some(path) = path_override {
         debug!(path = %path.display(), "config: load override");
         return load_config_at(path);
     }
+    if let Some(path) = env::var_os(CONFIG_ENV).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        debug!(path = %path.display(), "config: load from PINIT_CONFIG");
+        return load_config_at(&path);
+    }
 
     for path in default_config_paths() {
         if path.is_file() {
@@ -410,7 +838,16 @@ fn load_config_at(path: &Path) -> Result<(PathBuf, Config), ConfigError> {
         .unwrap_or_default()
         .to_ascii_lowercase();
 
-    let config = match ext.as_str() {
+    // A config written for a newer pinit may use keys or values this build
+    // cannot parse, so the version requirement is checked first.
+    if let Some(required) = declared_requirement(&ext, &content) {
+        version::check_pinit_version(&required).map_err(|source| ConfigError::Requirement {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    }
+
+    let mut config = match ext.as_str() {
         "toml" => parse_toml(path, &content)?,
         "yaml" | "yml" => parse_yaml(path, &content)?,
         _ => {
@@ -421,10 +858,68 @@ fn load_config_at(path: &Path) -> Result<(PathBuf, Config), ConfigError> {
             }
         }
     };
+    apply_env_overrides(&mut config);
     validate_config(path, &config)?;
     Ok((path.to_path_buf(), config))
 }
 
+/// Environment variable naming the config file when `--config` is not given.
+pub const CONFIG_ENV: &str = "PINIT_CONFIG";
+
+/// Environment variable that overrides the git ref of source `name`:
+/// `PINIT_SOURCE_<NAME>_REF`, with the name uppercased and every other
+/// character turned into `_` (`team-templates` -> `PINIT_SOURCE_TEAM_TEMPLATES_REF`).
+pub fn source_ref_env(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("PINIT_SOURCE_{name}_REF")
+}
+
+/// Layer environment overrides over the parsed file.
+fn apply_env_overrides(cfg: &mut Config) {
+    for source in &mut cfg.sources {
+        let key = source_ref_env(&source.name);
+        if let Ok(git_ref) = env::var(&key)
+            && !git_ref.trim().is_empty()
+        {
+            debug!(source = %source.name, env = %key, git_ref = %git_ref, "config: ref override");
+            source.git_ref = Some(git_ref.trim().to_string());
+        }
+    }
+}
+
+/// The top-level `requires_pinit` value, read without parsing the rest.
+fn declared_requirement(ext: &str, content: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Requirement {
+        requires_pinit: Option<String>,
+    }
+
+    let from_toml = || {
+        toml::from_str::<Requirement>(content)
+            .ok()
+            .and_then(|r| r.requires_pinit)
+    };
+    let from_yaml = || {
+        let docs = YamlLoader::load_from_str(content).ok()?;
+        let map = yaml_as_mapping(docs.first()?)?;
+        yaml_get_string(map, "requires_pinit")
+    };
+    match ext {
+        "toml" => from_toml(),
+        "yaml" | "yml" => from_yaml(),
+        _ => from_toml().or_else(from_yaml),
+    }
+}
+
 fn parse_toml(path: &Path, s: &str) -> Result<Config, ConfigError> {
     toml::from_str::<Config>(s).map_err(|e| ConfigError::ParseToml {
         path: path.to_path_buf(),
@@ -457,9 +952,72 @@ fn yaml_to_config(path: &Path, root: &Yaml) -> Result<Config, ConfigError> {
         });
     };
 
+    let binary_action = match yaml_get_string(map, "binary_action") {
+        Some(value) => {
+            Some(
+                BinaryAction::parse(&value).ok_or_else(|| ConfigError::InvalidConfig {
+                    path: path.to_path_buf(),
+                    message: format!(
+                        "binary_action must be skip, overwrite, or prompt (got {value:?})"
+                    ),
+                })?,
+            )
+        }
+        None => None,
+    };
+
+    let license_conflict = match yaml_get_string(map, "license_conflict") {
+        Some(value) => Some(LicenseConflictAction::parse(&value).ok_or_else(|| {
+            ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: format!(
+                    "license_conflict must be keep, overwrite, matching, or prompt (got {value:?})"
+                ),
+            }
+        })?),
+        None => None,
+    };
+
+    let nested_repos = match yaml_get_string(map, "nested_repos") {
+        Some(value) => {
+            NestedRepoAction::parse(&value).ok_or_else(|| ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: format!("nested_repos must be skip or enter (got {value:?})"),
+            })?
+        }
+        None => NestedRepoAction::default(),
+    };
+
+    let dirty_dest = match yaml_get_string(map, "dirty_dest") {
+        Some(value) => {
+            DirtyDestAction::parse(&value).ok_or_else(|| ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: format!("dirty_dest must be warn, abort, or stash (got {value:?})"),
+            })?
+        }
+        None => DirtyDestAction::default(),
+    };
+
+    let transforms = match yaml_get_seq(map, "transforms") {
+        Some(items) => yaml_to_transforms(path, items)?,
+        None => Vec::new(),
+    };
+
     let mut cfg = Config {
         base_template: yaml_get_string(map, "base_template"),
         license: yaml_get(map, "license").and_then(yaml_to_license),
+        binary_action,
+        license_conflict,
+        apply_lockfiles: yaml_get(map, "apply_lockfiles")
+            .and_then(yaml_as_bool)
+            .unwrap_or(false),
+        nested_repos,
+        dirty_dest,
+        apply_branch: yaml_get_string(map, "apply_branch"),
+        transforms,
+        requires_pinit: yaml_get_string(map, "requires_pinit"),
+        cache_dir: yaml_get_string(map, "cache_dir").map(PathBuf::from),
+        default_recipe: yaml_get_string(map, "default_recipe"),
         ..Config::default()
     };
 
@@ -510,6 +1068,8 @@ fn yaml_to_config(path: &Path, root: &Yaml) -> Result<Config, ConfigError> {
                     TemplateDef::Detailed {
                         source,
                         path: PathBuf::from(path_str),
+                        aliases: yaml_get_vec_of_strings(d, "aliases").unwrap_or_default(),
+                        deprecated: yaml_get_string(d, "deprecated"),
                     },
                 );
             }
@@ -521,22 +1081,30 @@ fn yaml_to_config(path: &Path, root: &Yaml) -> Result<Config, ConfigError> {
             let Some(name) = yaml_as_string(k) else {
                 continue;
             };
-            if let Some(items) = yaml_as_vec_of_strings(v) {
+            if let Some(items) = yaml_as_template_refs(v) {
                 cfg.targets.insert(name, TargetDef::Templates(items));
                 continue;
             }
             let Some(detail_map) = yaml_as_mapping(v) else {
                 continue;
             };
-            let templates = yaml_get_vec_of_strings(detail_map, "templates").unwrap_or_default();
+            let templates = yaml_get(detail_map, "templates")
+                .and_then(yaml_as_template_refs)
+                .unwrap_or_default();
             let overrides = yaml_get(detail_map, "overrides")
                 .and_then(yaml_to_override_rules)
                 .unwrap_or_default();
+            let map = yaml_get(detail_map, "map")
+                .and_then(yaml_to_path_map)
+                .unwrap_or_default();
             cfg.targets.insert(
                 name,
                 TargetDef::Detailed(TargetDetailed {
                     templates,
                     overrides,
+                    map,
+                    aliases: yaml_get_vec_of_strings(detail_map, "aliases").unwrap_or_default(),
+                    deprecated: yaml_get_string(detail_map, "deprecated"),
                 }),
             );
         }
@@ -546,10 +1114,43 @@ fn yaml_to_config(path: &Path, root: &Yaml) -> Result<Config, ConfigError> {
         cfg.overrides = overrides;
     }
 
+    if let Some(path_map) = yaml_get(map, "map").and_then(yaml_to_path_map) {
+        cfg.map = path_map;
+    }
+
     if let Some(hooks_root) = yaml_get(map, "hooks").and_then(yaml_as_mapping) {
         cfg.hooks = yaml_to_hook_set(path, hooks_root)?;
     }
 
+    if let Some(fragments) = yaml_get(map, "gitignore").and_then(yaml_as_mapping) {
+        for (k, v) in fragments {
+            let (Some(name), Some(patterns)) = (yaml_as_string(k), yaml_as_vec_of_strings(v))
+            else {
+                continue;
+            };
+            cfg.gitignore.insert(name, patterns);
+        }
+    }
+
+    if let Some(remote_map) = yaml_get(map, "remote").and_then(yaml_as_mapping) {
+        cfg.remote = Some(RemoteDef {
+            command: yaml_get_vec_of_strings(remote_map, "command").unwrap_or_default(),
+            url: yaml_get_string(remote_map, "url"),
+            name: yaml_get_string(remote_map, "name"),
+            commit_message: yaml_get_string(remote_map, "commit_message"),
+            protect: yaml_get_vec_of_strings(remote_map, "protect").unwrap_or_default(),
+        });
+    }
+
+    if let Some(update_map) = yaml_get(map, "update_check").and_then(yaml_as_mapping) {
+        cfg.update_check = Some(UpdateCheckDef {
+            enabled: yaml_get(update_map, "enabled")
+                .and_then(yaml_as_bool)
+                .unwrap_or(false),
+            repo: yaml_get_string(update_map, "repo"),
+        });
+    }
+
     if let Some(recipes_root) = yaml_get(map, "recipes").and_then(yaml_as_mapping) {
         for (k, v) in recipes_root {
             let Some(name) = yaml_as_string(k) else {
@@ -559,10 +1160,15 @@ fn yaml_to_config(path: &Path, root: &Yaml) -> Result<Config, ConfigError> {
                 continue;
             };
 
-            let templates = yaml_get_vec_of_strings(recipe_map, "templates").unwrap_or_default();
+            let templates = yaml_get(recipe_map, "templates")
+                .and_then(yaml_as_template_refs)
+                .unwrap_or_default();
             let overrides = yaml_get(recipe_map, "overrides")
                 .and_then(yaml_to_override_rules)
                 .unwrap_or_default();
+            let path_map = yaml_get(recipe_map, "map")
+                .and_then(yaml_to_path_map)
+                .unwrap_or_default();
             let hooks = match yaml_get(recipe_map, "hooks").and_then(yaml_as_mapping) {
                 Some(hooks_map) => yaml_to_hook_set(path, hooks_map)?,
                 None => HookSet::default(),
@@ -586,13 +1192,19 @@ fn yaml_to_config(path: &Path, root: &Yaml) -> Result<Config, ConfigError> {
                 }
             }
 
+            let gitignore = yaml_get_vec_of_strings(recipe_map, "gitignore").unwrap_or_default();
+
             cfg.recipes.insert(
                 name,
                 RecipeDef {
                     templates,
                     files,
                     overrides,
+                    map: path_map,
                     hooks,
+                    gitignore,
+                    aliases: yaml_get_vec_of_strings(recipe_map, "aliases").unwrap_or_default(),
+                    deprecated: yaml_get_string(recipe_map, "deprecated"),
                 },
             );
         }
@@ -657,6 +1269,79 @@ fn yaml_as_vec_of_strings(y: &Yaml) -> Option<Vec<String>> {
     }
 }
 
+/// A template list whose items are names or `{ template, dest_prefix }` mappings.
+fn yaml_as_template_refs(y: &Yaml) -> Option<Vec<TemplateRef>> {
+    let Yaml::Array(items) = y else {
+        return None;
+    };
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        if let Some(name) = yaml_as_string(item) {
+            out.push(TemplateRef::Name(name));
+            continue;
+        }
+        let map = yaml_as_mapping(item)?;
+        let template = yaml_get_string(map, "template").or_else(|| yaml_get_string(map, "name"))?;
+        out.push(TemplateRef::Detailed {
+            template,
+            dest_prefix: yaml_get_string(map, "dest_prefix").map(PathBuf::from),
+        });
+    }
+    Some(out)
+}
+
+fn yaml_to_transforms(path: &Path, items: &[Yaml]) -> Result<Vec<TransformDef>, ConfigError> {
+    let invalid = |message: String| ConfigError::InvalidConfig {
+        path: path.to_path_buf(),
+        message,
+    };
+    let mut out = Vec::with_capacity(items.len());
+    for (idx, item) in items.iter().enumerate() {
+        let Some(map) = yaml_as_mapping(item) else {
+            return Err(invalid(format!("transforms[{idx}] must be a mapping")));
+        };
+        let pattern = yaml_get_string(map, "pattern");
+        match yaml_get_string(map, "step").as_deref() {
+            Some("eol") => {
+                let value = yaml_get_string(map, "eol").unwrap_or_default();
+                let eol = EolStyle::parse(&value).ok_or_else(|| {
+                    invalid(format!(
+                        "transforms[{idx}].eol must be lf or crlf (got {value:?})"
+                    ))
+                })?;
+                out.push(TransformDef::Eol { eol, pattern });
+            }
+            Some("mode") => {
+                let (Some(mode), Some(pattern)) = (yaml_get_string(map, "mode"), pattern) else {
+                    return Err(invalid(format!(
+                        "transforms[{idx}] mode steps need mode and pattern"
+                    )));
+                };
+                out.push(TransformDef::Mode { mode, pattern });
+            }
+            other => {
+                return Err(invalid(format!(
+                    "transforms[{idx}].step must be eol or mode (got {:?})",
+                    other.unwrap_or("")
+                )));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn yaml_to_path_map(y: &Yaml) -> Option<BTreeMap<PathBuf, PathBuf>> {
+    let map = yaml_as_mapping(y)?;
+    let mut out = BTreeMap::new();
+    for (k, v) in map {
+        let (Some(from), Some(to)) = (yaml_as_string(k), yaml_as_string(v)) else {
+            continue;
+        };
+        out.insert(PathBuf::from(from), PathBuf::from(to));
+    }
+    Some(out)
+}
+
 fn yaml_as_bool(y: &Yaml) -> Option<bool> {
     match y {
         Yaml::Boolean(b) => Some(*b),
@@ -849,9 +1534,221 @@ fn validate_config(path: &Path, cfg: &Config) -> Result<(), ConfigError> {
         let label = format!("recipes.{name}.hooks");
         validate_hook_set(path, &label, &recipe.hooks)?;
     }
+    if let Some(remote) = &cfg.remote {
+        if remote.command.is_empty() && remote.url.is_none() {
+            return Err(ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: "remote must set command or url".to_string(),
+            });
+        }
+        if remote.name.as_deref().is_some_and(str::is_empty) {
+            return Err(ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: "remote.name must not be empty".to_string(),
+            });
+        }
+    }
+    validate_path_map(path, "map", &cfg.map)?;
+    for (idx, def) in cfg.transforms.iter().enumerate() {
+        if let TransformDef::Mode { mode, .. } = def
+            && crate::transform::parse_mode(mode).is_none()
+        {
+            return Err(ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: format!(
+                    "transforms[{idx}].mode must be an octal mode such as \"755\" (got {mode:?})"
+                ),
+            });
+        }
+    }
+    validate_aliases(path, cfg)?;
+    if let Some(cycle) = find_target_cycle(cfg) {
+        return Err(ConfigError::InvalidConfig {
+            path: path.to_path_buf(),
+            message: format!("targets reference each other in a cycle: {cycle}"),
+        });
+    }
+    for (name, target) in &cfg.targets {
+        validate_template_refs(path, &format!("targets.{name}"), target.templates())?;
+        if let Some(map) = target.map() {
+            validate_path_map(path, &format!("targets.{name}.map"), map)?;
+        }
+    }
+    for (name, recipe) in &cfg.recipes {
+        validate_template_refs(
+            path,
+            &format!("recipes.{name}.templates"),
+            &recipe.templates,
+        )?;
+        validate_path_map(path, &format!("recipes.{name}.map"), &recipe.map)?;
+    }
+    if let Some(update) = &cfg.update_check
+        && update.repo.as_deref().is_some_and(|r| r.trim().is_empty())
+    {
+        return Err(ConfigError::InvalidConfig {
+            path: path.to_path_buf(),
+            message: "update_check.repo must not be empty".to_string(),
+        });
+    }
+    if let Some(dir) = &cfg.cache_dir
+        && !dir.is_absolute()
+    {
+        return Err(ConfigError::InvalidConfig {
+            path: path.to_path_buf(),
+            message: format!("cache_dir must be an absolute path (got {dir:?})"),
+        });
+    }
+    if cfg
+        .apply_branch
+        .as_deref()
+        .is_some_and(|b| b.trim().is_empty())
+    {
+        return Err(ConfigError::InvalidConfig {
+            path: path.to_path_buf(),
+            message: "apply_branch must not be empty".to_string(),
+        });
+    }
+    if let Some(name) = &cfg.default_recipe
+        && cfg.resolve_recipe(name).is_none()
+    {
+        return Err(ConfigError::InvalidConfig {
+            path: path.to_path_buf(),
+            message: format!(
+                "default_recipe must name a template, target, or recipe (got {name:?})"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Aliases must be non-empty and name exactly one template, target, or recipe.
+fn validate_aliases(path: &Path, cfg: &Config) -> Result<(), ConfigError> {
+    let invalid = |message: String| ConfigError::InvalidConfig {
+        path: path.to_path_buf(),
+        message,
+    };
+    let mut owners: BTreeMap<&str, String> = BTreeMap::new();
+    let entries = cfg
+        .recipes
+        .iter()
+        .map(|(name, def)| (format!("recipes.{name}"), def.aliases.as_slice()))
+        .chain(
+            cfg.targets
+                .iter()
+                .map(|(name, def)| (format!("targets.{name}"), def.aliases())),
+        )
+        .chain(
+            cfg.templates
+                .iter()
+                .map(|(name, def)| (format!("templates.{name}"), def.aliases())),
+        );
+    for (owner, aliases) in entries {
+        for alias in aliases {
+            if alias.trim().is_empty() {
+                return Err(invalid(format!(
+                    "{owner}.aliases must not contain empty names"
+                )));
+            }
+            if cfg.recipes.contains_key(alias)
+                || cfg.targets.contains_key(alias)
+                || cfg.templates.contains_key(alias)
+            {
+                return Err(invalid(format!(
+                    "{owner}.aliases: '{alias}' is already the name of a template, target, or recipe"
+                )));
+            }
+            if let Some(first) = owners.insert(alias, owner.clone())
+                && first != owner
+            {
+                return Err(invalid(format!(
+                    "{owner}.aliases: '{alias}' is already an alias of {first}"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The first cycle of targets referencing targets, as `a -> b -> a`.
+fn find_target_cycle(cfg: &Config) -> Option<String> {
+    fn visit<'a>(cfg: &'a Config, name: &'a str, stack: &mut Vec<&'a str>) -> Option<String> {
+        if let Some(start) = stack.iter().position(|seen| *seen == name) {
+            let mut chain = stack[start..].to_vec();
+            chain.push(name);
+            return Some(chain.join(" -> "));
+        }
+        let target = cfg.targets.get(name)?;
+        stack.push(name);
+        for entry in target.templates() {
+            if cfg.is_target_ref(name, entry.name())
+                && let Some(cycle) = visit(cfg, entry.name(), stack)
+            {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        None
+    }
+
+    cfg.targets
+        .keys()
+        .find_map(|name| visit(cfg, name, &mut Vec::new()))
+}
+
+fn validate_template_refs(
+    path: &Path,
+    label: &str,
+    templates: &[TemplateRef],
+) -> Result<(), ConfigError> {
+    for (idx, template) in templates.iter().enumerate() {
+        let Some(prefix) = template.dest_prefix() else {
+            continue;
+        };
+        if !is_inside_relative(prefix) {
+            return Err(ConfigError::InvalidConfig {
+                path: path.to_path_buf(),
+                message: format!(
+                    "{label}[{idx}].dest_prefix must be a relative path inside the destination (got {})",
+                    prefix.display()
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn validate_path_map(
+    path: &Path,
+    label: &str,
+    map: &BTreeMap<PathBuf, PathBuf>,
+) -> Result<(), ConfigError> {
+    for (from, to) in map {
+        for p in [from, to] {
+            if !is_inside_relative(p) {
+                return Err(ConfigError::InvalidConfig {
+                    path: path.to_path_buf(),
+                    message: format!(
+                        "{label}: {} must be a relative path without '..'",
+                        p.display()
+                    ),
+                });
+            }
+        }
+    }
     Ok(())
 }
 
+/// Non-empty, relative, and never climbing out with `..`.
+fn is_inside_relative(p: &Path) -> bool {
+    !p.as_os_str().is_empty()
+        && p.components().all(|c| {
+            matches!(
+                c,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        })
+}
+
 fn validate_hook_set(path: &Path, label: &str, hooks: &HookSet) -> Result<(), ConfigError> {
     validate_hooks_list(
         path,
@@ -884,28 +1781,39 @@ fn validate_hooks_list(path: &Path, label: &str, hooks: &[HookDef]) -> Result<()
 impl Config {
     /// Resolve a recipe/target/template name into concrete templates and file sets.
     pub fn resolve_recipe(&self, name: &str) -> Option<ResolvedRecipe> {
+        let name = self.canonical_name(name);
         if let Some(def) = self.recipes.get(name) {
             let mut overrides = self.overrides.clone();
             overrides.extend(def.overrides.clone());
+            let mut map = self.map.clone();
+            map.extend(def.map.clone());
             return Some(ResolvedRecipe {
                 name: name.to_string(),
                 templates: def.templates.clone(),
                 files: def.files.clone(),
                 overrides,
+                map,
                 hooks: def.hooks.clone(),
+                gitignore: def.gitignore.clone(),
                 kind: ResolvedKind::Recipe,
             });
         }
 
-        if let Some(stack) = self.targets.get(name) {
-            let mut overrides = self.overrides.clone();
-            overrides.extend(stack.overrides().iter().cloned());
+        if self.targets.contains_key(name) {
+            let mut expanded = ExpandedTarget {
+                templates: Vec::new(),
+                overrides: self.overrides.clone(),
+                map: self.map.clone(),
+            };
+            self.expand_target(name, None, &mut Vec::new(), &mut expanded);
             return Some(ResolvedRecipe {
                 name: name.to_string(),
-                templates: stack.templates().to_vec(),
+                templates: expanded.templates,
                 files: Vec::new(),
-                overrides,
+                overrides: expanded.overrides,
+                map: expanded.map,
                 hooks: HookSet::default(),
+                gitignore: Vec::new(),
                 kind: ResolvedKind::Target,
             });
         }
@@ -915,22 +1823,283 @@ impl Config {
             if let Some(base_template) = self.base_template.as_deref()
                 && base_template != name
             {
-                templates.push(base_template.to_string());
+                templates.push(TemplateRef::from(base_template));
             }
-            templates.push(name.to_string());
+            templates.push(TemplateRef::from(name));
             let overrides = self.overrides.clone();
             return Some(ResolvedRecipe {
                 name: name.to_string(),
                 templates,
                 files: Vec::new(),
                 overrides,
+                map: self.map.clone(),
                 hooks: HookSet::default(),
+                gitignore: Vec::new(),
                 kind: ResolvedKind::Template,
             });
         }
 
         None
     }
+
+    /// The template, target, or recipe name that `name` is an alias of.
+    ///
+    /// Defined names win over aliases; unknown names come back unchanged.
+    pub fn canonical_name<'a>(&'a self, name: &'a str) -> &'a str {
+        if self.recipes.contains_key(name)
+            || self.targets.contains_key(name)
+            || self.templates.contains_key(name)
+        {
+            return name;
+        }
+        self.aliases()
+            .find(|(alias, _)| *alias == name)
+            .map_or(name, |(_, target)| target)
+    }
+
+    /// Every `(alias, name)` pair, recipes first, then targets, then templates.
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &str)> {
+        let recipes = self
+            .recipes
+            .iter()
+            .flat_map(|(name, def)| def.aliases.iter().map(move |a| (a.as_str(), name.as_str())));
+        let targets = self.targets.iter().flat_map(|(name, def)| {
+            def.aliases()
+                .iter()
+                .map(move |a| (a.as_str(), name.as_str()))
+        });
+        let templates = self.templates.iter().flat_map(|(name, def)| {
+            def.aliases()
+                .iter()
+                .map(move |a| (a.as_str(), name.as_str()))
+        });
+        recipes.chain(targets).chain(templates)
+    }
+
+    /// Deprecation notices for a resolved stack: the recipe or target itself,
+    /// then each deprecated template it applies.
+    pub fn deprecations(&self, resolved: &ResolvedRecipe) -> Vec<ConfigWarning> {
+        let name = resolved.name.as_str();
+        let stack = match resolved.kind {
+            ResolvedKind::Recipe => self
+                .recipes
+                .get(name)
+                .and_then(|def| def.deprecated.as_deref())
+                .map(|note| ("recipe", name, note)),
+            ResolvedKind::Target => self
+                .targets
+                .get(name)
+                .and_then(TargetDef::deprecated)
+                .map(|note| ("target", name, note)),
+            ResolvedKind::Template => None,
+        };
+        let mut seen = std::collections::BTreeSet::new();
+        let templates = resolved
+            .templates
+            .iter()
+            .map(TemplateRef::name)
+            .filter(|template| seen.insert(*template))
+            .filter_map(|template| {
+                let note = self.templates.get(template)?.deprecated()?;
+                Some(("template", template, note))
+            });
+        stack
+            .into_iter()
+            .chain(templates)
+            .map(|(kind, entry, note)| ConfigWarning {
+                location: format!("{kind}s.{entry}"),
+                message: format!("{kind} '{entry}' is deprecated: {note}"),
+            })
+            .collect()
+    }
+
+    /// Whether `entry` in target `owner`'s template list names another target.
+    ///
+    /// A template of the same name wins, and a target never refers to itself,
+    /// so existing lists such as `rust = ["rust"]` keep their meaning.
+    fn is_target_ref(&self, owner: &str, entry: &str) -> bool {
+        entry != owner && !self.templates.contains_key(entry) && self.targets.contains_key(entry)
+    }
+
+    /// Append target `name` to `out`, inlining entries that name other targets.
+    ///
+    /// Nested overrides and maps land before the referencing target's own, so
+    /// the outer target wins. Loading rejects cycles; `stack` only guards
+    /// configs built in code.
+    fn expand_target<'a>(
+        &'a self,
+        name: &'a str,
+        prefix: Option<&Path>,
+        stack: &mut Vec<&'a str>,
+        out: &mut ExpandedTarget,
+    ) {
+        let Some(def) = self.targets.get(name) else {
+            return;
+        };
+        if stack.contains(&name) {
+            return;
+        }
+        stack.push(name);
+        for entry in def.templates() {
+            let entry_prefix = match (prefix, entry.dest_prefix()) {
+                (Some(outer), Some(inner)) => Some(outer.join(inner)),
+                (outer, inner) => outer.or(inner).map(Path::to_path_buf),
+            };
+            if self.is_target_ref(name, entry.name()) {
+                self.expand_target(entry.name(), entry_prefix.as_deref(), stack, out);
+            } else if prefix.is_none() {
+                out.templates.push(entry.clone());
+            } else {
+                out.templates.push(TemplateRef::Detailed {
+                    template: entry.name().to_string(),
+                    dest_prefix: entry_prefix,
+                });
+            }
+        }
+        out.overrides.extend(def.overrides().iter().cloned());
+        if let Some(map) = def.map() {
+            out.map.extend(map.clone());
+        }
+        stack.pop();
+    }
+
+    /// Check cross-references between sources, templates, targets, and recipes.
+    ///
+    /// Loading only validates shape; this reports names that will fail (or never
+    /// be used) at apply time. Warnings are ordered by config section.
+    pub fn lint(&self) -> Vec<ConfigWarning> {
+        let mut out = Vec::new();
+        let mut warn = |location: String, message: String| {
+            out.push(ConfigWarning { location, message });
+        };
+
+        let mut seen_sources = BTreeMap::new();
+        for (idx, source) in self.sources.iter().enumerate() {
+            let location = format!("sources[{idx}]");
+            if let Some(first) = seen_sources.insert(source.name.as_str(), idx) {
+                warn(
+                    location.clone(),
+                    format!(
+                        "duplicate source name '{}' (sources[{first}] is used)",
+                        source.name
+                    ),
+                );
+                seen_sources.insert(source.name.as_str(), first);
+            }
+            if source.path.is_none() && source.repo.is_none() {
+                warn(
+                    location.clone(),
+                    format!("source '{}' sets neither path nor repo", source.name),
+                );
+            }
+            let used = self
+                .templates
+                .values()
+                .any(|def| def.source() == Some(source.name.as_str()));
+            if !used {
+                warn(
+                    location,
+                    format!("source '{}' is not used by any template", source.name),
+                );
+            }
+        }
+
+        if let Some(base) = &self.base_template
+            && !self.templates.contains_key(base)
+        {
+            warn(
+                "base_template".to_string(),
+                format!("undefined template '{base}'"),
+            );
+        }
+
+        for (name, def) in &self.templates {
+            let location = format!("templates.{name}");
+            match def.source() {
+                Some(source) if !seen_sources.contains_key(source) => warn(
+                    format!("{location}.source"),
+                    format!("undefined source '{source}'"),
+                ),
+                None if def.path().is_relative() => warn(
+                    location.clone(),
+                    "relative path requires a source".to_string(),
+                ),
+                _ => {}
+            }
+            if self.targets.contains_key(name) || self.recipes.contains_key(name) {
+                let by = if self.recipes.contains_key(name) {
+                    "recipe"
+                } else {
+                    "target"
+                };
+                warn(
+                    location,
+                    format!("template '{name}' is shadowed by a {by} of the same name"),
+                );
+            }
+        }
+
+        for (name, def) in &self.targets {
+            let location = format!("targets.{name}");
+            for (idx, template) in def.templates().iter().map(TemplateRef::name).enumerate() {
+                if !self.templates.contains_key(template) && !self.is_target_ref(name, template) {
+                    warn(
+                        format!("{location}[{idx}]"),
+                        format!("undefined template '{template}'"),
+                    );
+                }
+            }
+            if self.recipes.contains_key(name) {
+                warn(
+                    location,
+                    format!("target '{name}' is shadowed by a recipe of the same name"),
+                );
+            }
+        }
+
+        for (name, def) in &self.recipes {
+            for (idx, template) in def.templates.iter().map(TemplateRef::name).enumerate() {
+                if !self.templates.contains_key(template) {
+                    warn(
+                        format!("recipes.{name}.templates[{idx}]"),
+                        format!("undefined template '{template}'"),
+                    );
+                }
+            }
+            for (idx, fragment) in def.gitignore.iter().enumerate() {
+                if !crate::gitignore::is_known_fragment(fragment, &self.gitignore) {
+                    warn(
+                        format!("recipes.{name}.gitignore[{idx}]"),
+                        format!("unknown gitignore fragment '{fragment}'"),
+                    );
+                }
+            }
+        }
+
+        // Every template can be applied by name, so "unused" only means something
+        // once the config composes templates into targets or recipes.
+        if !self.targets.is_empty() || !self.recipes.is_empty() {
+            for name in self.templates.keys() {
+                let referenced = self.base_template.as_deref() == Some(name.as_str())
+                    || self
+                        .targets
+                        .values()
+                        .any(|t| t.templates().iter().any(|r| r.name() == name))
+                    || self
+                        .recipes
+                        .values()
+                        .any(|r| r.templates.iter().any(|r| r.name() == name));
+                if !referenced {
+                    warn(
+                        format!("templates.{name}"),
+                        format!("template '{name}' is not used by any target or recipe"),
+                    );
+                }
+            }
+        }
+
+        out
+    }
 }
 
 #[cfg(test)]
@@ -966,7 +2135,10 @@ rust = ["common", "rust"]
         let resolved = cfg.resolve_recipe("rust").unwrap();
         assert_eq!(
             resolved.templates,
-            vec!["common".to_string(), "rust".to_string()]
+            vec![
+                TemplateRef::Name("common".to_string()),
+                TemplateRef::Name("rust".to_string())
+            ]
         );
         assert!(resolved.overrides.is_empty());
     }
@@ -991,7 +2163,10 @@ targets:
         let resolved = cfg.resolve_recipe("rust").unwrap();
         assert_eq!(
             resolved.templates,
-            vec!["common".to_string(), "rust".to_string()]
+            vec![
+                TemplateRef::Name("common".to_string()),
+                TemplateRef::Name("rust".to_string())
+            ]
         );
         assert_eq!(cfg.license.as_ref().unwrap().spdx(), "MIT");
         assert!(resolved.overrides.is_empty());
@@ -1295,11 +2470,114 @@ templates:
         assert!(!cfg.templates.contains_key("bad"));
         assert_eq!(
             cfg.targets.get("rust").unwrap().templates(),
-            &["common".to_string(), "rust".to_string()]
+            &[
+                TemplateRef::Name("common".to_string()),
+                TemplateRef::Name("rust".to_string())
+            ]
         );
         assert!(!cfg.targets.contains_key("bad"));
         assert!(cfg.recipes.contains_key("r1"));
         assert!(!cfg.recipes.contains_key("bad"));
         assert_eq!(cfg.recipes["r1"].files.len(), 1);
     }
+
+    #[test]
+    fn lint_clean_config_has_no_warnings() {
+        let cfg: Config = toml::from_str(
+            r#"
+base_template = "common"
+
+[[sources]]
+name = "local"
+path = "/tmp/templates"
+
+[templates]
+common = { source = "local", path = "common" }
+rust = "/tmp/rust"
+
+[targets]
+rust-stack = ["common", "rust"]
+"#,
+        )
+        .unwrap();
+        assert!(cfg.lint().is_empty());
+    }
+
+    #[test]
+    fn lint_reports_undefined_unused_and_shadowed_names() {
+        let cfg: Config = toml::from_str(
+            r#"
+base_template = "missing"
+
+[[sources]]
+name = "local"
+path = "/tmp/templates"
+
+[[sources]]
+name = "unused"
+repo = "org/repo"
+
+[templates]
+common = { source = "nope", path = "common" }
+relative = "relative"
+lonely = "/tmp/lonely"
+stack = "/tmp/stack"
+
+[targets]
+stack = ["common", "ghost"]
+
+[recipes.stack]
+templates = ["relative"]
+
+[recipes.other]
+templates = ["phantom"]
+"#,
+        )
+        .unwrap();
+
+        let warnings: Vec<String> = cfg.lint().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "sources[0]: source 'local' is not used by any template",
+                "sources[1]: source 'unused' is not used by any template",
+                "base_template: undefined template 'missing'",
+                "templates.common.source: undefined source 'nope'",
+                "templates.relative: relative path requires a source",
+                "templates.stack: template 'stack' is shadowed by a recipe of the same name",
+                "targets.stack[1]: undefined template 'ghost'",
+                "targets.stack: target 'stack' is shadowed by a recipe of the same name",
+                "recipes.other.templates[0]: undefined template 'phantom'",
+                "templates.lonely: template 'lonely' is not used by any target or recipe",
+                "templates.stack: template 'stack' is not used by any target or recipe",
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_reports_duplicate_and_empty_sources() {
+        let cfg: Config = toml::from_str(
+            r#"
+[[sources]]
+name = "a"
+path = "/tmp/a"
+
+[[sources]]
+name = "a"
+
+[templates]
+t = { source = "a", path = "t" }
+"#,
+        )
+        .unwrap();
+
+        let warnings: Vec<String> = cfg.lint().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "sources[1]: duplicate source name 'a' (sources[0] is used)",
+                "sources[1]: source 'a' sets neither path nor repo",
+            ]
+        );
+    }
 }
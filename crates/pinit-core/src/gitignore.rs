@@ -0,0 +1,149 @@
+#![forbid(unsafe_code)]
+
+//! `.gitignore` fragment composition.
+//!
+//! Recipes list fragment names (`rust`, `macos`, `vscode`, ...). Each name is
+//! looked up in the config's `gitignore` table first and then in the built-in
+//! catalog; the fragments are concatenated in order with duplicate patterns
+//! dropped.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::merge;
+
+/// Built-in fragments, kept deliberately small: only the patterns nearly every
+/// project of that kind wants.
+const BUILTIN_FRAGMENTS: &[(&str, &str)] = &[
+    ("rust", "/target/\n**/*.rs.bk\n"),
+    (
+        "node",
+        "node_modules/\nnpm-debug.log*\nyarn-debug.log*\nyarn-error.log*\n.pnpm-debug.log*\n",
+    ),
+    (
+        "python",
+        "__pycache__/\n*.py[cod]\n.venv/\n.pytest_cache/\n*.egg-info/\n",
+    ),
+    ("go", "*.test\n*.out\n/vendor/\n"),
+    ("macos", ".DS_Store\n.AppleDouble\n.LSOverride\n._*\n"),
+    ("windows", "Thumbs.db\nehthumbs.db\nDesktop.ini\n"),
+    ("linux", "*~\n.directory\n.Trash-*\n"),
+    (
+        "vscode",
+        ".vscode/*\n!.vscode/settings.json\n!.vscode/extensions.json\n",
+    ),
+    ("jetbrains", ".idea/\n*.iml\n"),
+    ("vim", "[._]*.s[a-v][a-z]\n[._]*.sw[a-p]\nSession.vim\n"),
+    ("env", ".env\n.env.*\n!.env.example\n"),
+];
+
+/// Errors encountered while composing fragments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitignoreError {
+    UnknownFragment(String),
+}
+
+impl fmt::Display for GitignoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitignoreError::UnknownFragment(name) => {
+                write!(f, "unknown gitignore fragment: {name}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GitignoreError {}
+
+/// Names of the built-in fragments, in catalog order.
+pub fn builtin_fragment_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_FRAGMENTS.iter().map(|(name, _)| *name)
+}
+
+/// Built-in fragment text by name.
+pub fn builtin_fragment(name: &str) -> Option<&'static str> {
+    BUILTIN_FRAGMENTS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, text)| *text)
+}
+
+/// True if `name` resolves via `custom` or the built-in catalog.
+pub fn is_known_fragment(name: &str, custom: &BTreeMap<String, Vec<String>>) -> bool {
+    custom.contains_key(name) || builtin_fragment(name).is_some()
+}
+
+/// Compose fragments into one `.gitignore` body.
+///
+/// Each fragment gets a `# <name>` header. Patterns already emitted by an
+/// earlier fragment are dropped, and a fragment that contributes nothing new is
+/// omitted entirely.
+pub fn compose(
+    names: &[String],
+    custom: &BTreeMap<String, Vec<String>>,
+) -> Result<String, GitignoreError> {
+    let mut out = String::new();
+    for name in names {
+        let body = match custom.get(name) {
+            Some(patterns) => {
+                let mut body = patterns.join("\n");
+                body.push('\n');
+                body
+            }
+            None => builtin_fragment(name)
+                .ok_or_else(|| GitignoreError::UnknownFragment(name.clone()))?
+                .to_string(),
+        };
+        let fragment = format!("# {name}\n{body}");
+        if let Some(merged) = merge::merge_ignore_text(&out, &fragment) {
+            out = merged;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composes_in_order_and_drops_duplicates() {
+        let mut custom = BTreeMap::new();
+        custom.insert(
+            "local".to_string(),
+            vec![".DS_Store".to_string(), "scratch/".to_string()],
+        );
+
+        let out = compose(
+            &["rust".to_string(), "macos".to_string(), "local".to_string()],
+            &custom,
+        )
+        .unwrap();
+
+        assert!(out.starts_with("# rust\n/target/\n"));
+        assert!(out.contains("\n\n# macos\n.DS_Store\n"));
+        assert!(out.ends_with("\n\n# local\nscratch/\n"));
+        assert_eq!(out.matches(".DS_Store").count(), 1);
+    }
+
+    #[test]
+    fn custom_fragment_shadows_builtin_and_unknown_errors() {
+        let mut custom = BTreeMap::new();
+        custom.insert("rust".to_string(), vec!["target/".to_string()]);
+        assert_eq!(
+            compose(&["rust".to_string()], &custom).unwrap(),
+            "# rust\ntarget/\n"
+        );
+
+        let err = compose(&["cobol".to_string()], &BTreeMap::new()).unwrap_err();
+        assert_eq!(err.to_string(), "unknown gitignore fragment: cobol");
+    }
+
+    #[test]
+    fn fragment_adding_nothing_is_omitted() {
+        let mut custom = BTreeMap::new();
+        custom.insert("dup".to_string(), vec!["/target/".to_string()]);
+        let out = compose(&["rust".to_string(), "dup".to_string()], &custom).unwrap();
+        assert!(!out.contains("# dup"));
+    }
+}
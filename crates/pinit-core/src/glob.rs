@@ -0,0 +1,84 @@
+#![forbid(unsafe_code)]
+
+//! Path globs shared by override rules and transform steps.
+
+/// Match a `/`-separated relative path against a glob.
+///
+/// `*` and `?` match within one segment; a `**` segment matches any number of
+/// segments (including none). Leading slashes on either side are ignored.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.replace('\\', "/");
+    let pattern = pattern.trim_start_matches('/').to_string();
+    let path = path.trim_start_matches('/').to_string();
+    let pat_segments: Vec<&str> = pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.split('/').collect();
+    glob_match_segments(&pat_segments, &path_segments)
+}
+
+fn glob_match_segments(patterns: &[&str], paths: &[&str]) -> bool {
+    if patterns.is_empty() {
+        return paths.is_empty();
+    }
+    if patterns[0] == "**" {
+        for idx in 0..=paths.len() {
+            if glob_match_segments(&patterns[1..], &paths[idx..]) {
+                return true;
+            }
+        }
+        return false;
+    }
+    if paths.is_empty() {
+        return false;
+    }
+    if !glob_match_segment(patterns[0], paths[0]) {
+        return false;
+    }
+    glob_match_segments(&patterns[1..], &paths[1..])
+}
+
+fn glob_match_segment(pattern: &str, text: &str) -> bool {
+    let pat = pattern.as_bytes();
+    let txt = text.as_bytes();
+    let mut p = 0usize;
+    let mut t = 0usize;
+    let mut star_idx: Option<usize> = None;
+    let mut match_idx = 0usize;
+
+    while t < txt.len() {
+        if p < pat.len() && (pat[p] == b'?' || pat[p] == txt[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pat.len() && pat[p] == b'*' {
+            star_idx = Some(p);
+            match_idx = t;
+            p += 1;
+        } else if let Some(star) = star_idx {
+            p = star + 1;
+            match_idx += 1;
+            t = match_idx;
+        } else {
+            return false;
+        }
+    }
+
+    while p < pat.len() && pat[p] == b'*' {
+        p += 1;
+    }
+    p == pat.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_segments_and_double_star() {
+        assert!(glob_match("*.sh", "build.sh"));
+        assert!(!glob_match("*.sh", "scripts/build.sh"));
+        assert!(glob_match("scripts/*.sh", "scripts/build.sh"));
+        assert!(glob_match("**/*.sh", "a/b/build.sh"));
+        assert!(glob_match("**/*.sh", "build.sh"));
+        assert!(glob_match("/docs/??.md", "docs/ab.md"));
+        assert!(!glob_match("docs/??.md", "docs/abc.md"));
+    }
+}
@@ -6,19 +6,34 @@
 //! directory, and a strategy for resolving conflicts when destination files already exist.
 
 pub mod config;
+pub mod gitignore;
+pub mod glob;
 pub mod licensing;
+pub mod manifest;
 mod merge;
 pub mod resolve;
+pub mod time;
+pub mod timing;
+pub mod transform;
+pub mod vars;
+pub mod version;
 
+pub use merge::merge_driver_names;
+
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsStr;
 use std::fmt;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use tracing::{debug, instrument, trace};
 
+use crate::timing::{Phase, Timings};
+
 /// Action to take when the destination file already exists.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ExistingFileAction {
@@ -39,6 +54,9 @@ impl ExistingFileAction {
 }
 
 /// Context describing an existing destination file and its candidate replacements.
+///
+/// Fields may be added; outside this crate, start from [`ExistingFileDecisionContext::new`].
+#[non_exhaustive]
 pub struct ExistingFileDecisionContext<'a> {
     /// Template name if the caller provided one.
     pub template_name: Option<&'a str>,
@@ -54,6 +72,108 @@ pub struct ExistingFileDecisionContext<'a> {
     pub dest_bytes: &'a [u8],
     /// Merged bytes, if a merge driver could produce them.
     pub merge_bytes: Option<&'a [u8]>,
+    /// True when either side looks binary (see [`is_binary`]).
+    pub is_binary: bool,
+    /// Template size minus destination size, in bytes.
+    pub size_delta: i64,
+    /// `merge` attribute the destination's `.gitattributes` declares for this path.
+    pub merge_attr: Option<&'a MergeAttribute>,
+    /// Set when the file is a rendered license (see [`apply_license_file`]).
+    pub license: Option<&'a LicenseConflict>,
+}
+
+impl<'a> ExistingFileDecisionContext<'a> {
+    /// Context for replacing `dest_bytes` with `src_bytes`, with `is_binary` and
+    /// `size_delta` derived from them and no template, merge, or license details.
+    pub fn new(
+        rel_path: &'a Path,
+        dest_path: &'a Path,
+        src_bytes: &'a [u8],
+        dest_bytes: &'a [u8],
+    ) -> Self {
+        Self {
+            template_name: None,
+            template_index: None,
+            rel_path,
+            dest_path,
+            src_bytes,
+            dest_bytes,
+            merge_bytes: None,
+            is_binary: is_binary(src_bytes) || is_binary(dest_bytes),
+            size_delta: size_delta(src_bytes, dest_bytes),
+            merge_attr: None,
+            license: None,
+        }
+    }
+}
+
+/// The two sides of an existing license file conflict.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LicenseConflict {
+    /// SPDX id being rendered.
+    pub spdx: String,
+    /// SPDX id detected in the existing file, if recognizable (see [`licensing::detect_spdx`]).
+    pub existing_spdx: Option<String>,
+}
+
+/// A `merge` attribute from the destination repository's `.gitattributes`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeAttribute {
+    /// `merge=ours`: keep the destination file.
+    Ours,
+    /// `merge=theirs`: take the template file.
+    Theirs,
+    /// `merge=union`: keep lines from both sides; pinit merges line-wise.
+    Union,
+    /// `merge=binary` or `-merge`: never merge the contents.
+    Binary,
+    /// `merge` set without a value: git's text merge, so pinit's usual driver applies.
+    Text,
+    /// Any other (custom) driver name; pinit has no equivalent.
+    Driver(String),
+}
+
+impl MergeAttribute {
+    /// Parse the value column of `git check-attr merge`; `None` for `unspecified`.
+    fn from_check_attr(value: &str) -> Option<Self> {
+        match value {
+            "unspecified" | "" => None,
+            "set" | "text" => Some(Self::Text),
+            "unset" | "binary" => Some(Self::Binary),
+            "ours" => Some(Self::Ours),
+            "theirs" => Some(Self::Theirs),
+            "union" => Some(Self::Union),
+            other => Some(Self::Driver(other.to_string())),
+        }
+    }
+}
+
+/// Heuristic binary check, the same one git uses: a NUL byte in the first 8000 bytes.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|&b| b == 0)
+}
+
+/// Well-known lockfile names. Existing copies are kept by default (see [`is_lockfile`]).
+pub const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+];
+
+/// Whether `rel` names a well-known lockfile, in any directory.
+pub fn is_lockfile(rel: &Path) -> bool {
+    rel.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| LOCKFILES.contains(&name))
+}
+
+fn size_delta(src: &[u8], dest: &[u8]) -> i64 {
+    src.len() as i64 - dest.len() as i64
 }
 
 /// Decide what to do when a destination file already exists.
@@ -80,10 +200,103 @@ pub struct ApplyOptions {
     pub template_name: Option<String>,
     /// Template index for stack-aware decisions.
     pub template_index: Option<usize>,
+    /// Maximum directory depth below the template root (`None` = unlimited).
+    pub max_depth: Option<usize>,
+    /// Maximum number of template files to walk (`None` = unlimited).
+    pub max_files: Option<usize>,
+    /// Values substituted for `{{ name }}` references in text files.
+    pub variables: vars::Variables,
+    /// Place template files under this subdirectory of the destination.
+    ///
+    /// Ignore checks, override patterns, and report entries all see the
+    /// prefixed path, since that is where the file ends up.
+    pub dest_prefix: Option<PathBuf>,
+    /// Template path -> destination path renames, applied before `dest_prefix`.
+    ///
+    /// A key naming a directory moves that whole subtree.
+    pub path_map: BTreeMap<PathBuf, PathBuf>,
+    /// Extra transform steps, run after variable rendering and path remapping.
+    pub transforms: transform::Pipeline,
+    /// Only update files that already exist; missing files are skipped, not created.
+    pub only_existing: bool,
+    /// Handling of nested git repositories (a directory with its own `.git`) in the destination.
+    ///
+    /// The destination repository's ignore rules and attributes never apply past
+    /// such a boundary, whichever action is chosen.
+    pub nested_repos: config::NestedRepoAction,
+    /// Count the files inside pruned (ignored) directories for the report.
+    ///
+    /// Costs a walk of each pruned directory; off by default.
+    pub count_pruned_files: bool,
+    /// Dry runs record what they would write here and read it back before the disk.
+    ///
+    /// Share one overlay across the templates of a stack so later members see the
+    /// files earlier ones would create or change. Ignored unless `dry_run` is set.
+    pub dry_run_overlay: Option<DryRunOverlay>,
+}
+
+/// Destination contents a dry run would have written, keyed by destination path.
+///
+/// Clones share the same contents.
+#[derive(Clone, Debug, Default)]
+pub struct DryRunOverlay(Arc<Mutex<HashMap<PathBuf, Vec<u8>>>>);
+
+impl DryRunOverlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, path: &Path) -> Option<Vec<u8>> {
+        self.0.lock().ok()?.get(path).cloned()
+    }
+
+    fn insert(&self, path: &Path, bytes: Vec<u8>) {
+        if let Ok(mut files) = self.0.lock() {
+            files.insert(path.to_path_buf(), bytes);
+        }
+    }
+}
+
+impl ApplyOptions {
+    /// The overlay in effect: only dry runs read from or record into it.
+    fn overlay(&self) -> Option<&DryRunOverlay> {
+        self.dry_run_overlay.as_ref().filter(|_| self.dry_run)
+    }
+
+    /// `dest_path` as this run sees it: the dry-run overlay first, then the disk.
+    /// `None` when the file does not exist.
+    fn read_dest(&self, dest_path: &Path) -> Result<Option<Vec<u8>>, ApplyError> {
+        if let Some(bytes) = self.overlay().and_then(|o| o.get(dest_path)) {
+            return Ok(Some(bytes));
+        }
+        if !dest_path.exists() {
+            return Ok(None);
+        }
+        fs::read(dest_path).map(Some).map_err(|e| ApplyError::Io {
+            path: dest_path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// The full per-file pipeline: render variables, remap paths, then `transforms`.
+    fn pipeline(&self) -> transform::Pipeline {
+        let mut pipeline = transform::Pipeline::new();
+        if !self.variables.is_empty() {
+            pipeline.push(transform::RenderVariables(self.variables.clone()));
+        }
+        if !self.path_map.is_empty() || self.dest_prefix.is_some() {
+            pipeline.push(transform::RemapPaths {
+                map: self.path_map.clone(),
+                dest_prefix: self.dest_prefix.clone(),
+            });
+        }
+        pipeline.extend(&self.transforms);
+        pipeline
+    }
 }
 
 /// Summary of work performed during template application.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ApplyReport {
     /// Files created because they did not exist in the destination.
     pub created_files: usize,
@@ -91,8 +304,270 @@ pub struct ApplyReport {
     pub updated_files: usize,
     /// Files skipped due to identical contents or a skip decision.
     pub skipped_files: usize,
-    /// Paths ignored by destination gitignore rules.
+    /// Ignored paths of any kind: `ignored_files + pruned_dirs`.
     pub ignored_paths: usize,
+    /// Template files left out by an ignore rule, a built-in exclusion, or a nested repo.
+    pub ignored_files: usize,
+    /// Template directories left out as a whole, without descending into them.
+    pub pruned_dirs: usize,
+    /// Files inside pruned directories; only counted with [`ApplyOptions::count_pruned_files`].
+    pub pruned_dir_files: usize,
+    /// Per-path outcomes, in the order they were decided.
+    pub entries: Vec<ReportEntry>,
+    /// Time spent walking template directories and merging (the walk and merge phases).
+    pub timings: Timings,
+    /// Non-fatal problems noticed along the way, in the order they came up.
+    pub warnings: Vec<Warning>,
+}
+
+impl ApplyReport {
+    /// Fold another report (for example, from the next template in a stack) into this one.
+    pub fn absorb(&mut self, other: ApplyReport) {
+        self.created_files += other.created_files;
+        self.updated_files += other.updated_files;
+        self.skipped_files += other.skipped_files;
+        self.ignored_paths += other.ignored_paths;
+        self.ignored_files += other.ignored_files;
+        self.pruned_dirs += other.pruned_dirs;
+        self.pruned_dir_files += other.pruned_dir_files;
+        self.entries.extend(other.entries);
+        self.timings.absorb(&other.timings);
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Total bytes written to created and updated files (would be written, in a dry run).
+    pub fn bytes_written(&self) -> u64 {
+        self.entries.iter().filter_map(|e| e.bytes).sum()
+    }
+
+    /// The `n` largest written files, biggest first.
+    pub fn largest_files(&self, n: usize) -> Vec<&ReportEntry> {
+        let mut written: Vec<&ReportEntry> =
+            self.entries.iter().filter(|e| e.bytes.is_some()).collect();
+        written.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+        written.truncate(n);
+        written
+    }
+
+    fn record(&mut self, path: &Path, outcome: EntryOutcome) {
+        self.record_entry(path, outcome, None);
+    }
+
+    /// Record a created or updated file along with the size of what was written.
+    fn record_written(&mut self, path: &Path, outcome: EntryOutcome, bytes: u64) {
+        self.record_entry(path, outcome, Some(bytes));
+    }
+
+    /// Record an updated file, with the action that changed it.
+    fn record_updated(&mut self, path: &Path, action: ExistingFileAction, bytes: u64) {
+        self.record_written(path, EntryOutcome::Updated, bytes);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.action = Some(action);
+        }
+    }
+
+    fn record_entry(&mut self, path: &Path, outcome: EntryOutcome, bytes: Option<u64>) {
+        match outcome {
+            EntryOutcome::Created => self.created_files += 1,
+            EntryOutcome::Updated => self.updated_files += 1,
+            EntryOutcome::Skipped => self.skipped_files += 1,
+            EntryOutcome::Ignored(_) => {
+                self.ignored_paths += 1;
+                self.ignored_files += 1;
+            }
+        }
+        self.entries.push(ReportEntry {
+            path: path.to_path_buf(),
+            outcome,
+            pruned: None,
+            bytes,
+            template: None,
+            action: None,
+        });
+    }
+
+    /// Record an ignored template path; directories are pruned as a whole.
+    ///
+    /// `files` is the number of files below a pruned directory, when counted.
+    fn record_ignored(
+        &mut self,
+        path: &Path,
+        reason: IgnoreReason,
+        is_dir: bool,
+        files: Option<usize>,
+    ) {
+        if !is_dir {
+            self.record(path, EntryOutcome::Ignored(reason));
+            return;
+        }
+        self.ignored_paths += 1;
+        self.pruned_dirs += 1;
+        self.pruned_dir_files += files.unwrap_or(0);
+        self.entries.push(ReportEntry {
+            path: path.to_path_buf(),
+            outcome: EntryOutcome::Ignored(reason),
+            pruned: Some(PrunedDir { files }),
+            bytes: None,
+            template: None,
+            action: None,
+        });
+    }
+
+    fn single(path: &Path, outcome: EntryOutcome) -> Self {
+        let mut report = ApplyReport::default();
+        report.record(path, outcome);
+        report
+    }
+
+    fn single_written(path: &Path, outcome: EntryOutcome, bytes: u64) -> Self {
+        let mut report = ApplyReport::default();
+        report.record_written(path, outcome, bytes);
+        report
+    }
+
+    fn single_updated(path: &Path, action: ExistingFileAction, bytes: u64) -> Self {
+        let mut report = ApplyReport::default();
+        report.record_updated(path, action, bytes);
+        report
+    }
+}
+
+/// A non-fatal problem, collected during a run and reported at its end.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    /// Destination-relative path the warning is about, if any.
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            path: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}: {}", path.display(), self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// What a [`Warning`] is about; `as_str` is the stable name used in JSON output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A merge used the line-wise fallback because no format-aware driver exists.
+    MergeFallback,
+    /// A deprecated template, target, or recipe was used.
+    Deprecated,
+    /// The destination had uncommitted changes (`dirty_dest = "warn"`).
+    DirtyDest,
+    /// A hook with `allow_failure` failed.
+    HookFailed,
+    /// A configured or `--override` pattern matched none of the applied files.
+    UnmatchedOverride,
+}
+
+impl WarningKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WarningKind::MergeFallback => "merge_fallback",
+            WarningKind::Deprecated => "deprecated",
+            WarningKind::DirtyDest => "dirty_dest",
+            WarningKind::HookFailed => "hook_failed",
+            WarningKind::UnmatchedOverride => "unmatched_override",
+        }
+    }
+}
+
+/// What happened to one template path.
+///
+/// Fields may be added; outside this crate, start from [`ReportEntry::new`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReportEntry {
+    /// Path relative to the destination root (directories for pruned subtrees).
+    pub path: PathBuf,
+    pub outcome: EntryOutcome,
+    /// Set when `path` is an ignored directory that was pruned without descending.
+    pub pruned: Option<PrunedDir>,
+    /// Size of the contents written for created and updated files (also in a dry run).
+    pub bytes: Option<u64>,
+    /// Template that produced the path ([`ApplyOptions::template_name`]); `None` for generated files.
+    pub template: Option<String>,
+    /// How an updated file was changed: [`ExistingFileAction::Merge`] or [`ExistingFileAction::Overwrite`].
+    pub action: Option<ExistingFileAction>,
+}
+
+impl ReportEntry {
+    /// An entry for `path` with no pruning, size, template, or action details.
+    pub fn new(path: impl Into<PathBuf>, outcome: EntryOutcome) -> Self {
+        Self {
+            path: path.into(),
+            outcome,
+            pruned: None,
+            bytes: None,
+            template: None,
+            action: None,
+        }
+    }
+}
+
+/// Details of a pruned template directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrunedDir {
+    /// Files below the directory, if [`ApplyOptions::count_pruned_files`] was set.
+    pub files: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryOutcome {
+    Created,
+    Updated,
+    Skipped,
+    Ignored(IgnoreReason),
+}
+
+/// Why a path was left out of the apply.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IgnoreReason {
+    /// Built-in exclusions: `.git/`, `.DS_Store`, template manifests and policies.
+    Always,
+    /// Inside a nested git repository of the destination, rooted at this path.
+    NestedRepo(PathBuf),
+    /// A gitignore rule in the destination repository matched.
+    Git(IgnoreRule),
+}
+
+/// The gitignore rule that matched a path, as reported by `git check-ignore --verbose`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IgnoreRule {
+    /// File the pattern came from (`.gitignore`, `.git/info/exclude`, a global excludes file).
+    pub source: String,
+    /// 1-based line number within `source`; 0 when git's output could not be parsed.
+    pub line: usize,
+    pub pattern: String,
+}
+
+impl fmt::Display for IgnoreRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return f.write_str(&self.source);
+        }
+        write!(f, "{}:{}: {}", self.source, self.line, self.pattern)
+    }
 }
 
 /// Errors that can occur when applying a template directory.
@@ -102,11 +577,24 @@ pub enum ApplyError {
     TemplateDirNotDir(PathBuf),
     DestDirNotDir(PathBuf),
     SymlinkNotSupported(PathBuf),
+    MaxDepthExceeded {
+        path: PathBuf,
+        max_depth: usize,
+    },
+    MaxFilesExceeded {
+        template_dir: PathBuf,
+        max_files: usize,
+    },
     GitIgnoreFailed {
         cmd: String,
         status: i32,
         stderr: String,
     },
+    TransformFailed {
+        path: PathBuf,
+        step: String,
+        message: String,
+    },
     Io {
         path: PathBuf,
         source: io::Error,
@@ -128,6 +616,23 @@ impl fmt::Display for ApplyError {
             ApplyError::SymlinkNotSupported(path) => {
                 write!(f, "symlinks are not supported (yet): {}", path.display())
             }
+            ApplyError::MaxDepthExceeded { path, max_depth } => {
+                write!(
+                    f,
+                    "template is nested deeper than {max_depth} directories at {}; is this the right template directory?",
+                    path.display()
+                )
+            }
+            ApplyError::MaxFilesExceeded {
+                template_dir,
+                max_files,
+            } => {
+                write!(
+                    f,
+                    "template has more than {max_files} files: {}; is this the right template directory?",
+                    template_dir.display()
+                )
+            }
             ApplyError::GitIgnoreFailed {
                 cmd,
                 status,
@@ -138,6 +643,17 @@ impl fmt::Display for ApplyError {
                     "git ignore check failed ({status}) running {cmd}: {stderr}"
                 )
             }
+            ApplyError::TransformFailed {
+                path,
+                step,
+                message,
+            } => {
+                write!(
+                    f,
+                    "{step} transform failed for {}: {message}",
+                    path.display()
+                )
+            }
             ApplyError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
         }
     }
@@ -174,6 +690,7 @@ pub fn apply_template_dir(
 ) -> Result<ApplyReport, ApplyError> {
     let template_dir = template_dir.as_ref();
     let dest_dir = dest_dir.as_ref();
+    let start = Instant::now();
 
     let template_meta = fs::symlink_metadata(template_dir).map_err(|e| {
         if e.kind() == io::ErrorKind::NotFound {
@@ -192,34 +709,97 @@ pub fn apply_template_dir(
         return Err(ApplyError::TemplateDirNotDir(template_dir.to_path_buf()));
     }
 
-    if let Ok(dest_meta) = fs::symlink_metadata(dest_dir) {
-        if dest_meta.file_type().is_symlink() {
-            return Err(ApplyError::SymlinkNotSupported(dest_dir.to_path_buf()));
-        }
-        if !dest_meta.is_dir() {
-            return Err(ApplyError::DestDirNotDir(dest_dir.to_path_buf()));
+    let dest_exists = match fs::symlink_metadata(dest_dir) {
+        Ok(dest_meta) => {
+            if dest_meta.file_type().is_symlink() {
+                return Err(ApplyError::SymlinkNotSupported(dest_dir.to_path_buf()));
+            }
+            if !dest_meta.is_dir() {
+                return Err(ApplyError::DestDirNotDir(dest_dir.to_path_buf()));
+            }
+            true
         }
-    } else if !options.dry_run {
+        Err(_) => false,
+    };
+
+    let git_ignore = GitIgnore::detect(dest_dir)?;
+    let pipeline = options.pipeline();
+    if !pipeline.is_empty() {
+        debug!(steps = ?pipeline.step_names(), "transform pipeline");
+    }
+    let mut report = ApplyReport::default();
+    let mut files = Vec::new();
+    let walk = Walk {
+        root: template_dir,
+        dest_root: dest_dir,
+        options: &options,
+        pipeline: &pipeline,
+        git_ignore: &git_ignore,
+    };
+    collect_template_files(&walk, template_dir, 0, false, &mut report, &mut files)?;
+
+    if !dest_exists && !options.dry_run {
         fs::create_dir_all(dest_dir).map_err(|e| ApplyError::Io {
             path: dest_dir.to_path_buf(),
             source: e,
         })?;
     }
 
-    let git_ignore = GitIgnore::detect(dest_dir)?;
-    let mut report = ApplyReport::default();
-    apply_dir_recursive(
-        template_dir,
-        template_dir,
-        dest_dir,
-        &options,
-        &git_ignore,
-        decider,
-        &mut report,
-    )?;
+    let merge_attrs = match &git_ignore {
+        Some(g) => {
+            let queries: Vec<String> = files
+                .iter()
+                .filter(|f| !f.in_nested_repo)
+                .map(|f| format_git_rel(&f.rel, false))
+                .collect();
+            g.merge_attributes(&queries)?
+        }
+        None => HashMap::new(),
+    };
+
+    // Time spent waiting on the decider (an interactive prompt) is not walking.
+    let mut decider = TimedDecider {
+        inner: decider,
+        waited: Duration::ZERO,
+    };
+    for file in &files {
+        let merge_attr = merge_attrs.get(&format_git_rel(&file.rel, false));
+        apply_template_file(
+            file,
+            dest_dir,
+            &options,
+            &pipeline,
+            merge_attr,
+            &mut decider,
+            &mut report,
+        )?;
+    }
+    let walked = start
+        .elapsed()
+        .saturating_sub(decider.waited)
+        .saturating_sub(report.timings.get(Phase::Merge));
+    report.timings.add(Phase::Walk, walked);
+    for entry in &mut report.entries {
+        entry.template = options.template_name.clone();
+    }
     Ok(report)
 }
 
+/// Decider wrapper that keeps track of how long decisions took.
+struct TimedDecider<'a> {
+    inner: &'a mut dyn ExistingFileDecider,
+    waited: Duration,
+}
+
+impl ExistingFileDecider for TimedDecider<'_> {
+    fn decide(&mut self, ctx: ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        let start = Instant::now();
+        let action = self.inner.decide(ctx);
+        self.waited += start.elapsed();
+        action
+    }
+}
+
 /// Apply a generated file into the destination directory.
 ///
 /// Generated files bypass merge drivers; if the destination exists the decider
@@ -241,18 +821,90 @@ pub fn apply_generated_file(
     options: ApplyOptions,
     decider: &mut dyn ExistingFileDecider,
 ) -> Result<ApplyReport, ApplyError> {
-    let dest_dir = dest_dir.as_ref();
-    let rel_path = rel_path.as_ref();
+    apply_generated(
+        dest_dir.as_ref(),
+        rel_path.as_ref(),
+        contents,
+        &options,
+        decider,
+        GeneratedKind::Plain,
+    )
+}
 
+/// Apply a rendered license file.
+///
+/// Like [`apply_generated_file`], but an existing file is reported to the
+/// decider with [`ExistingFileDecisionContext::license`] set, including the
+/// SPDX id detected in it, so license conflicts can follow their own policy.
+#[instrument(skip(options, decider, contents), fields(dest_dir = %dest_dir.as_ref().display(), rel_path = %rel_path.as_ref().display(), spdx = %spdx, dry_run = options.dry_run))]
+pub fn apply_license_file(
+    dest_dir: impl AsRef<Path>,
+    rel_path: impl AsRef<Path>,
+    contents: &[u8],
+    spdx: &str,
+    options: ApplyOptions,
+    decider: &mut dyn ExistingFileDecider,
+) -> Result<ApplyReport, ApplyError> {
+    apply_generated(
+        dest_dir.as_ref(),
+        rel_path.as_ref(),
+        contents,
+        &options,
+        decider,
+        GeneratedKind::License(spdx),
+    )
+}
+
+/// Apply a generated file, offering the same merge drivers as template files.
+///
+/// Used for generated content that is meant to combine with what is already
+/// there, such as a composed `.gitignore`.
+#[instrument(skip(options, decider, contents), fields(dest_dir = %dest_dir.as_ref().display(), rel_path = %rel_path.as_ref().display(), dry_run = options.dry_run))]
+pub fn merge_generated_file(
+    dest_dir: impl AsRef<Path>,
+    rel_path: impl AsRef<Path>,
+    contents: &[u8],
+    options: ApplyOptions,
+    decider: &mut dyn ExistingFileDecider,
+) -> Result<ApplyReport, ApplyError> {
+    apply_generated(
+        dest_dir.as_ref(),
+        rel_path.as_ref(),
+        contents,
+        &options,
+        decider,
+        GeneratedKind::Mergeable,
+    )
+}
+
+/// What a generated file is, which decides how an existing copy is handled.
+#[derive(Clone, Copy, Debug)]
+enum GeneratedKind<'a> {
+    /// Overwrite or skip only.
+    Plain,
+    /// Merge drivers apply, as for template files.
+    Mergeable,
+    /// A rendered license with this SPDX id; overwrite or skip only.
+    License(&'a str),
+}
+
+fn apply_generated(
+    dest_dir: &Path,
+    rel_path: &Path,
+    contents: &[u8],
+    options: &ApplyOptions,
+    decider: &mut dyn ExistingFileDecider,
+    kind: GeneratedKind<'_>,
+) -> Result<ApplyReport, ApplyError> {
     if rel_path.as_os_str() == OsStr::new("") {
         return Ok(ApplyReport::default());
     }
     if should_always_ignore(rel_path) {
         trace!(path = %rel_path.display(), "ignored (always)");
-        return Ok(ApplyReport {
-            ignored_paths: 1,
-            ..ApplyReport::default()
-        });
+        return Ok(ApplyReport::single(
+            rel_path,
+            EntryOutcome::Ignored(IgnoreReason::Always),
+        ));
     }
 
     if let Ok(dest_meta) = fs::symlink_metadata(dest_dir) {
@@ -270,33 +922,47 @@ pub fn apply_generated_file(
     }
 
     let git_ignore = GitIgnore::detect(dest_dir)?;
+    let mut merge_attr = None;
     if let Some(g) = &git_ignore {
         let query = format_git_rel(rel_path, false);
-        if g.ignored_set(std::slice::from_ref(&query))?
-            .contains(&query)
-        {
-            trace!(path = %query, "ignored (git)");
-            return Ok(ApplyReport {
-                ignored_paths: 1,
-                ..ApplyReport::default()
-            });
+        if let Some(rule) = g.ignored_set(std::slice::from_ref(&query))?.remove(&query) {
+            trace!(path = %query, source = %rule.source, line = rule.line, pattern = %rule.pattern, "ignored (git)");
+            return Ok(ApplyReport::single(
+                rel_path,
+                EntryOutcome::Ignored(IgnoreReason::Git(rule)),
+            ));
+        }
+        if fs::symlink_metadata(dest_dir.join(rel_path)).is_ok() {
+            merge_attr = g
+                .merge_attributes(std::slice::from_ref(&query))?
+                .remove(&query);
         }
     }
 
     let dest_path = dest_dir.join(rel_path);
-    if dest_path.exists() {
-        let dest_bytes = fs::read(&dest_path).map_err(|e| ApplyError::Io {
-            path: dest_path.clone(),
-            source: e,
-        })?;
+    if let Some(dest_bytes) = options.read_dest(&dest_path)? {
         if dest_bytes == contents {
             trace!(path = %rel_path.display(), "skip (identical)");
-            return Ok(ApplyReport {
-                skipped_files: 1,
-                ..ApplyReport::default()
-            });
+            return Ok(ApplyReport::single(rel_path, EntryOutcome::Skipped));
         }
 
+        let merge_bytes = match kind {
+            GeneratedKind::Mergeable => {
+                merge_with_attr(rel_path, merge_attr.as_ref(), &dest_bytes, contents)
+            }
+            GeneratedKind::Plain | GeneratedKind::License(_) => None,
+        };
+        let license = match kind {
+            GeneratedKind::License(spdx) => {
+                let existing_spdx = licensing::detect_spdx(&String::from_utf8_lossy(&dest_bytes));
+                debug!(path = %rel_path.display(), spdx = %spdx, existing = ?existing_spdx, "existing license file");
+                Some(LicenseConflict {
+                    spdx: spdx.to_string(),
+                    existing_spdx,
+                })
+            }
+            GeneratedKind::Plain | GeneratedKind::Mergeable => None,
+        };
         let action = decider.decide(ExistingFileDecisionContext {
             template_name: options.template_name.as_deref(),
             template_index: options.template_index,
@@ -304,29 +970,36 @@ pub fn apply_generated_file(
             dest_path: &dest_path,
             src_bytes: contents,
             dest_bytes: &dest_bytes,
-            merge_bytes: None,
+            merge_bytes: merge_bytes.as_deref(),
+            is_binary: is_binary(contents) || is_binary(&dest_bytes),
+            size_delta: size_delta(contents, &dest_bytes),
+            merge_attr: merge_attr.as_ref(),
+            license: license.as_ref(),
         });
 
         trace!(path = %rel_path.display(), action = action.as_str(), "existing file decision (generated)");
 
-        match action {
-            ExistingFileAction::Skip | ExistingFileAction::Merge => {
+        let output_bytes = match (action, merge_bytes) {
+            (ExistingFileAction::Overwrite, _) => contents.to_vec(),
+            (ExistingFileAction::Merge, Some(merged)) => merged,
+            (ExistingFileAction::Skip, _) | (ExistingFileAction::Merge, None) => {
                 if action == ExistingFileAction::Merge {
                     debug!(path = %rel_path.display(), "merge unavailable for generated file; skipping");
                 }
-                return Ok(ApplyReport {
-                    skipped_files: 1,
-                    ..ApplyReport::default()
-                });
+                return Ok(ApplyReport::single(rel_path, EntryOutcome::Skipped));
             }
-            ExistingFileAction::Overwrite => {}
+        };
+        if output_bytes == dest_bytes {
+            trace!(path = %rel_path.display(), action = action.as_str(), "no changes after action");
+            return Ok(ApplyReport::single(rel_path, EntryOutcome::Skipped));
         }
 
         if options.dry_run {
-            return Ok(ApplyReport {
-                updated_files: 1,
-                ..ApplyReport::default()
-            });
+            let bytes = output_bytes.len() as u64;
+            if let Some(overlay) = options.overlay() {
+                overlay.insert(&dest_path, output_bytes);
+            }
+            return Ok(ApplyReport::single_updated(rel_path, action, bytes));
         }
 
         let existing_perms = fs::metadata(&dest_path)
@@ -341,7 +1014,7 @@ pub fn apply_generated_file(
                 source: e,
             })?;
         }
-        fs::write(&dest_path, contents).map_err(|e| ApplyError::Io {
+        fs::write(&dest_path, &output_bytes).map_err(|e| ApplyError::Io {
             path: dest_path.clone(),
             source: e,
         })?;
@@ -349,17 +1022,27 @@ pub fn apply_generated_file(
             path: dest_path.clone(),
             source: e,
         })?;
-        return Ok(ApplyReport {
-            updated_files: 1,
-            ..ApplyReport::default()
-        });
+        return Ok(ApplyReport::single_updated(
+            rel_path,
+            action,
+            output_bytes.len() as u64,
+        ));
+    }
+
+    if options.only_existing {
+        trace!(path = %rel_path.display(), "skip (missing; only updating existing files)");
+        return Ok(ApplyReport::single(rel_path, EntryOutcome::Skipped));
     }
 
     if options.dry_run {
-        return Ok(ApplyReport {
-            created_files: 1,
-            ..ApplyReport::default()
-        });
+        if let Some(overlay) = options.overlay() {
+            overlay.insert(&dest_path, contents.to_vec());
+        }
+        return Ok(ApplyReport::single_written(
+            rel_path,
+            EntryOutcome::Created,
+            contents.len() as u64,
+        ));
     }
 
     if let Some(parent) = dest_path.parent() {
@@ -372,21 +1055,53 @@ pub fn apply_generated_file(
         path: dest_path.clone(),
         source: e,
     })?;
-    Ok(ApplyReport {
-        created_files: 1,
-        ..ApplyReport::default()
-    })
+    Ok(ApplyReport::single_written(
+        rel_path,
+        EntryOutcome::Created,
+        contents.len() as u64,
+    ))
+}
+
+/// A template file selected for application, in walk order.
+struct TemplateFile {
+    path: PathBuf,
+    rel: PathBuf,
+    /// Lands inside a nested repository entered with [`config::NestedRepoAction::Enter`].
+    in_nested_repo: bool,
+}
+
+/// Fixed inputs of a template walk.
+struct Walk<'a> {
+    root: &'a Path,
+    dest_root: &'a Path,
+    options: &'a ApplyOptions,
+    pipeline: &'a transform::Pipeline,
+    git_ignore: &'a Option<GitIgnore>,
 }
 
-fn apply_dir_recursive(
-    root: &Path,
+/// Walk the template tree and collect the files to apply.
+///
+/// Ignored directories are pruned before descending, so large ignored trees
+/// (`node_modules/`, `target/`) cost one ignore check rather than a full walk.
+/// Size limits are enforced here, before anything is written.
+///
+/// `in_nested_repo` is set below a nested destination repository that is being
+/// entered; the destination's ignore rules are not consulted there.
+fn collect_template_files(
+    walk: &Walk<'_>,
     current: &Path,
-    dest_root: &Path,
-    options: &ApplyOptions,
-    git_ignore: &Option<GitIgnore>,
-    decider: &mut dyn ExistingFileDecider,
+    depth: usize,
+    in_nested_repo: bool,
     report: &mut ApplyReport,
+    files: &mut Vec<TemplateFile>,
 ) -> Result<(), ApplyError> {
+    let Walk {
+        root,
+        dest_root,
+        options,
+        pipeline,
+        git_ignore,
+    } = *walk;
     let mut entries: Vec<_> = fs::read_dir(current)
         .map_err(|e| ApplyError::Io {
             path: current.to_path_buf(),
@@ -400,27 +1115,7 @@ fn apply_dir_recursive(
 
     entries.sort_by_key(|e| e.file_name());
 
-    // Precompute ignore matches for this directory level so we don't spawn one `git` process per path.
-    let mut queries: Vec<String> = Vec::with_capacity(entries.len());
-    for entry in &entries {
-        let path = entry.path();
-        let rel = path.strip_prefix(root).unwrap_or(&path);
-        if rel.as_os_str() == OsStr::new("") {
-            continue;
-        }
-        let meta = fs::symlink_metadata(&path).map_err(|e| ApplyError::Io {
-            path: path.clone(),
-            source: e,
-        })?;
-        let q = format_git_rel(rel, meta.is_dir());
-        queries.push(q);
-    }
-
-    let ignored = match git_ignore {
-        Some(g) => g.ignored_set(&queries)?,
-        None => std::collections::HashSet::new(),
-    };
-
+    let mut candidates = Vec::with_capacity(entries.len());
     for entry in entries {
         let path = entry.path();
         let meta = fs::symlink_metadata(&path).map_err(|e| ApplyError::Io {
@@ -431,27 +1126,82 @@ fn apply_dir_recursive(
             return Err(ApplyError::SymlinkNotSupported(path));
         }
 
-        let rel = path.strip_prefix(root).unwrap_or(&path);
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
         if rel.as_os_str() == OsStr::new("") {
             continue;
         }
 
-        if should_always_ignore(rel) {
+        // Always-ignored paths never reach git and are never descended into.
+        if should_always_ignore(&rel) {
             trace!(path = %rel.display(), "ignored (always)");
-            report.ignored_paths += 1;
+            let files = pruned_file_count(options, &path, meta.is_dir());
+            report.record_ignored(&rel, IgnoreReason::Always, meta.is_dir(), files);
             continue;
         }
 
-        let is_dir = meta.is_dir();
-        let query = format_git_rel(rel, is_dir);
-        if ignored.contains(&query) {
-            trace!(path = %query, "ignored (git)");
-            report.ignored_paths += 1;
+        let mapped = pipeline.map_path(rel.clone());
+        let remapped = mapped != rel;
+        if remapped {
+            trace!(from = %rel.display(), to = %mapped.display(), "remapped");
+        }
+        let rel = mapped;
+        // A rename must not land a file somewhere the original name would never go.
+        if remapped && should_always_ignore(&rel) {
+            trace!(path = %rel.display(), "ignored (always, after remap)");
+            let files = pruned_file_count(options, &path, meta.is_dir());
+            report.record_ignored(&rel, IgnoreReason::Always, meta.is_dir(), files);
             continue;
         }
 
-        if is_dir {
-            apply_dir_recursive(root, &path, dest_root, options, git_ignore, decider, report)?;
+        // Directories can start a nested repo; remapped files can land in one directly.
+        let mut nested = in_nested_repo;
+        if !nested
+            && (meta.is_dir() || remapped)
+            && let Some(repo) = nested_repo_root(dest_root, &rel, meta.is_dir())
+        {
+            if options.nested_repos == config::NestedRepoAction::Skip {
+                trace!(path = %rel.display(), repo = %repo.display(), "ignored (nested repo)");
+                let files = pruned_file_count(options, &path, meta.is_dir());
+                report.record_ignored(&rel, IgnoreReason::NestedRepo(repo), meta.is_dir(), files);
+                continue;
+            }
+            trace!(path = %rel.display(), repo = %repo.display(), "entering nested repo");
+            nested = true;
+        }
+
+        let query = format_git_rel(&rel, meta.is_dir());
+        candidates.push((path, rel, meta, query, nested));
+    }
+
+    // Check the whole directory level at once so we don't spawn one `git` process per path.
+    // Paths in nested repos are left out: the outer repo's rules stop at the boundary.
+    let mut ignored = match git_ignore {
+        Some(g) => {
+            let queries: Vec<String> = candidates
+                .iter()
+                .filter(|c| !c.4)
+                .map(|c| c.3.clone())
+                .collect();
+            g.ignored_set(&queries)?
+        }
+        None => HashMap::new(),
+    };
+
+    for (path, rel, meta, query, nested) in candidates {
+        if let Some(rule) = ignored.remove(&query) {
+            trace!(path = %query, source = %rule.source, line = rule.line, pattern = %rule.pattern, "ignored (git)");
+            let files = pruned_file_count(options, &path, meta.is_dir());
+            report.record_ignored(&rel, IgnoreReason::Git(rule), meta.is_dir(), files);
+            continue;
+        }
+
+        if meta.is_dir() {
+            if let Some(max_depth) = options.max_depth
+                && depth + 1 > max_depth
+            {
+                return Err(ApplyError::MaxDepthExceeded { path, max_depth });
+            }
+            collect_template_files(walk, &path, depth + 1, nested, report, files)?;
             continue;
         }
 
@@ -459,106 +1209,286 @@ fn apply_dir_recursive(
             continue;
         }
 
-        let dest_path = dest_root.join(rel);
-        if dest_path.exists() {
-            let src_bytes = fs::read(&path).map_err(|e| ApplyError::Io {
-                path: path.clone(),
-                source: e,
+        if let Some(max_files) = options.max_files
+            && files.len() >= max_files
+        {
+            return Err(ApplyError::MaxFilesExceeded {
+                template_dir: root.to_path_buf(),
+                max_files,
+            });
+        }
+        files.push(TemplateFile {
+            path,
+            rel,
+            in_nested_repo: nested,
+        });
+    }
+
+    Ok(())
+}
+
+fn apply_template_file(
+    file: &TemplateFile,
+    dest_root: &Path,
+    options: &ApplyOptions,
+    pipeline: &transform::Pipeline,
+    merge_attr: Option<&MergeAttribute>,
+    decider: &mut dyn ExistingFileDecider,
+    report: &mut ApplyReport,
+) -> Result<(), ApplyError> {
+    let path = &file.path;
+    let rel = file.rel.as_path();
+    let dest_path = dest_root.join(rel);
+    // Files are only read up front when a step edits them; otherwise a plain copy will do.
+    let transformed = if pipeline.edits_files() {
+        let raw = fs::read(path).map_err(|e| ApplyError::Io {
+            path: path.clone(),
+            source: e,
+        })?;
+        let mut data = transform::FileData {
+            rel: rel.to_path_buf(),
+            contents: raw,
+            mode: None,
+        };
+        pipeline
+            .apply(&mut data)
+            .map_err(|(step, message)| ApplyError::TransformFailed {
+                path: rel.to_path_buf(),
+                step,
+                message,
             })?;
-            let dest_bytes = fs::read(&dest_path).map_err(|e| ApplyError::Io {
-                path: dest_path.clone(),
+        Some(data)
+    } else {
+        None
+    };
+    let mode = transformed.as_ref().and_then(|data| data.mode);
+    if let Some(dest_bytes) = options.read_dest(&dest_path)? {
+        let src_bytes = match transformed {
+            Some(data) => data.contents,
+            None => fs::read(path).map_err(|e| ApplyError::Io {
+                path: path.clone(),
                 source: e,
-            })?;
+            })?,
+        };
 
-            if src_bytes == dest_bytes {
-                trace!(path = %rel.display(), "skip (identical)");
-                report.skipped_files += 1;
-                continue;
-            }
+        if src_bytes == dest_bytes {
+            trace!(path = %rel.display(), "skip (identical)");
+            report.record(rel, EntryOutcome::Skipped);
+            return Ok(());
+        }
 
-            let merge_bytes = merge::merge_file(rel, &dest_bytes, &src_bytes);
-            let action = decider.decide(ExistingFileDecisionContext {
-                template_name: options.template_name.as_deref(),
-                template_index: options.template_index,
-                rel_path: rel,
-                dest_path: &dest_path,
-                src_bytes: &src_bytes,
-                dest_bytes: &dest_bytes,
-                merge_bytes: merge_bytes.as_deref(),
-            });
+        let merge_bytes = report.timings.time(Phase::Merge, || {
+            merge_with_attr(rel, merge_attr, &dest_bytes, &src_bytes)
+        });
+        let action = decider.decide(ExistingFileDecisionContext {
+            template_name: options.template_name.as_deref(),
+            template_index: options.template_index,
+            rel_path: rel,
+            dest_path: &dest_path,
+            src_bytes: &src_bytes,
+            dest_bytes: &dest_bytes,
+            merge_bytes: merge_bytes.as_deref(),
+            is_binary: is_binary(&src_bytes) || is_binary(&dest_bytes),
+            size_delta: size_delta(&src_bytes, &dest_bytes),
+            merge_attr,
+            license: None,
+        });
 
-            trace!(path = %rel.display(), action = action.as_str(), "existing file decision");
+        trace!(path = %rel.display(), action = action.as_str(), "existing file decision");
 
-            let output_bytes = match action {
-                ExistingFileAction::Skip => {
-                    report.skipped_files += 1;
-                    continue;
-                }
-                ExistingFileAction::Overwrite => src_bytes,
-                ExistingFileAction::Merge => {
-                    let Some(merged) = merge_bytes else {
-                        debug!(path = %rel.display(), "merge unavailable; skipping");
-                        report.skipped_files += 1;
-                        continue;
-                    };
-                    merged
+        let output_bytes = match action {
+            ExistingFileAction::Skip => {
+                report.record(rel, EntryOutcome::Skipped);
+                return Ok(());
+            }
+            ExistingFileAction::Overwrite => src_bytes,
+            ExistingFileAction::Merge => {
+                let Some(merged) = merge_bytes else {
+                    debug!(path = %rel.display(), "merge unavailable; skipping");
+                    report.record(rel, EntryOutcome::Skipped);
+                    return Ok(());
+                };
+                // `merge=union` asks for the line-wise merge; otherwise it is a fallback.
+                if !matches!(merge_attr, Some(MergeAttribute::Union))
+                    && merge::driver(rel).is_none()
+                {
+                    report.warnings.push(
+                        Warning::new(
+                            WarningKind::MergeFallback,
+                            "no merge driver for this file type; merged line by line",
+                        )
+                        .with_path(rel),
+                    );
                 }
-            };
-
-            if output_bytes == dest_bytes {
-                trace!(path = %rel.display(), action = action.as_str(), "no changes after action");
-                report.skipped_files += 1;
-                continue;
+                merged
             }
+        };
 
-            report.updated_files += 1;
-            if options.dry_run {
-                continue;
+        if output_bytes == dest_bytes {
+            trace!(path = %rel.display(), action = action.as_str(), "no changes after action");
+            report.record(rel, EntryOutcome::Skipped);
+            return Ok(());
+        }
+
+        report.record_updated(rel, action, output_bytes.len() as u64);
+        if options.dry_run {
+            if let Some(overlay) = options.overlay() {
+                overlay.insert(&dest_path, output_bytes);
             }
+            return Ok(());
+        }
 
-            let existing_perms =
-                fs::metadata(&dest_path)
-                    .map(|m| m.permissions())
-                    .map_err(|e| ApplyError::Io {
-                        path: dest_path.clone(),
-                        source: e,
-                    })?;
-            fs::write(&dest_path, &output_bytes).map_err(|e| ApplyError::Io {
+        let existing_perms = fs::metadata(&dest_path)
+            .map(|m| m.permissions())
+            .map_err(|e| ApplyError::Io {
                 path: dest_path.clone(),
                 source: e,
             })?;
-            fs::set_permissions(&dest_path, existing_perms).map_err(|e| ApplyError::Io {
-                path: dest_path.clone(),
+        fs::write(&dest_path, &output_bytes).map_err(|e| ApplyError::Io {
+            path: dest_path.clone(),
+            source: e,
+        })?;
+        fs::set_permissions(&dest_path, existing_perms).map_err(|e| ApplyError::Io {
+            path: dest_path.clone(),
+            source: e,
+        })?;
+        set_mode(&dest_path, mode)?;
+
+        return Ok(());
+    }
+
+    if options.only_existing {
+        trace!(path = %rel.display(), "skip (missing; only updating existing files)");
+        report.record(rel, EntryOutcome::Skipped);
+        return Ok(());
+    }
+
+    let bytes = match &transformed {
+        Some(data) => data.contents.len() as u64,
+        None => fs::metadata(path)
+            .map_err(|e| ApplyError::Io {
+                path: path.clone(),
+                source: e,
+            })?
+            .len(),
+    };
+    if !options.dry_run {
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| ApplyError::Io {
+                path: parent.to_path_buf(),
                 source: e,
             })?;
-
-            continue;
         }
-
-        if !options.dry_run {
-            if let Some(parent) = dest_path.parent() {
-                fs::create_dir_all(parent).map_err(|e| ApplyError::Io {
-                    path: parent.to_path_buf(),
-                    source: e,
-                })?;
-            }
-            trace!(src = %path.display(), dest = %dest_path.display(), "copy");
-            fs::copy(&path, &dest_path).map_err(|e| ApplyError::Io {
+        trace!(src = %path.display(), dest = %dest_path.display(), "copy");
+        fs::copy(path, &dest_path).map_err(|e| ApplyError::Io {
+            path: dest_path.clone(),
+            source: e,
+        })?;
+        // Copy first so permissions carry over, then replace the contents.
+        if let Some(data) = transformed {
+            trace!(path = %rel.display(), "write transformed contents");
+            fs::write(&dest_path, data.contents).map_err(|e| ApplyError::Io {
                 path: dest_path.clone(),
                 source: e,
             })?;
         }
-        report.created_files += 1;
+        set_mode(&dest_path, mode)?;
+    } else if let Some(overlay) = options.overlay() {
+        let contents = match transformed {
+            Some(data) => data.contents,
+            None => fs::read(path).map_err(|e| ApplyError::Io {
+                path: path.clone(),
+                source: e,
+            })?,
+        };
+        overlay.insert(&dest_path, contents);
     }
+    report.record_written(rel, EntryOutcome::Created, bytes);
+    Ok(())
+}
+
+/// Pick the merge driver, letting a `.gitattributes` `merge=` value override the
+/// extension-based choice.
+fn merge_with_attr(
+    rel: &Path,
+    attr: Option<&MergeAttribute>,
+    dest_bytes: &[u8],
+    src_bytes: &[u8],
+) -> Option<Vec<u8>> {
+    if let Some(attr) = attr {
+        trace!(path = %rel.display(), merge_attr = ?attr, "gitattributes merge");
+    }
+    match attr {
+        Some(MergeAttribute::Binary) => None,
+        Some(MergeAttribute::Union) => merge::merge_lines(dest_bytes, src_bytes),
+        _ => merge::merge_file(rel, dest_bytes, src_bytes),
+    }
+}
 
+/// Apply a mode chosen by a transform step; no-op when unset or off Unix.
+fn set_mode(path: &Path, mode: Option<u32>) -> Result<(), ApplyError> {
+    let Some(mode) = mode else {
+        return Ok(());
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        trace!(path = %path.display(), mode = format!("{mode:o}"), "set mode");
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).map_err(|e| {
+            ApplyError::Io {
+                path: path.to_path_buf(),
+                source: e,
+            }
+        })?;
+    }
+    #[cfg(not(unix))]
+    debug!(path = %path.display(), mode = format!("{mode:o}"), "mode transform ignored on this platform");
     Ok(())
 }
 
+/// Files below a pruned template directory, when the caller asked for them.
+fn pruned_file_count(options: &ApplyOptions, path: &Path, is_dir: bool) -> Option<usize> {
+    (is_dir && options.count_pruned_files).then(|| count_files(path))
+}
+
+/// Regular files below `dir`; unreadable entries are not counted.
+fn count_files(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => count_files(&entry.path()),
+            Ok(t) if t.is_file() => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The nested repository `rel` lands in, if any: the closest ancestor (or `rel`
+/// itself, for a directory) below `dest_root` that has its own `.git`.
+///
+/// Submodules and linked worktrees have a `.git` file rather than a directory;
+/// both count.
+fn nested_repo_root(dest_root: &Path, rel: &Path, is_dir: bool) -> Option<PathBuf> {
+    let start = if is_dir { Some(rel) } else { rel.parent() };
+    start?
+        .ancestors()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .find(|dir| dest_root.join(dir).join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
 fn should_always_ignore(rel: &Path) -> bool {
     if rel.file_name() == Some(OsStr::new(".DS_Store")) {
         return true;
     }
-    matches!(rel.components().next(), Some(std::path::Component::Normal(s)) if s == OsStr::new(".git"))
+    if manifest::is_manifest_path(rel) {
+        return true;
+    }
+    rel.components()
+        .any(|c| matches!(c, std::path::Component::Normal(s) if s == OsStr::new(".git")))
 }
 
 fn format_git_rel(rel: &Path, is_dir: bool) -> String {
@@ -601,17 +1531,80 @@ impl GitIgnore {
             return Ok(None);
         }
         debug!(dest_root = %dest_root.display(), "gitignore: enabled");
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            log_exclude_sources(dest_root);
+        }
         Ok(Some(Self {
             cwd: dest_root.to_path_buf(),
         }))
     }
 
-    fn ignored_set(
+    /// `merge` attributes for `rel_paths`; paths with no attribute are left out.
+    ///
+    /// Attributes only steer decisions, so a failing `git check-attr` is logged
+    /// and treated as "no attributes" rather than aborting the apply.
+    fn merge_attributes(
         &self,
         rel_paths: &[String],
-    ) -> Result<std::collections::HashSet<String>, ApplyError> {
+    ) -> Result<HashMap<String, MergeAttribute>, ApplyError> {
+        let mut attrs = HashMap::new();
+        if rel_paths.is_empty() {
+            return Ok(attrs);
+        }
+
+        trace!(count = rel_paths.len(), "gitattributes: check");
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(&self.cwd)
+            .args(["check-attr", "-z", "--stdin", "merge"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| ApplyError::Io {
+                path: PathBuf::from("git"),
+                source: e,
+            })?;
+        {
+            let mut stdin = child.stdin.take().expect("stdin piped");
+            use std::io::Write;
+            let mut input = Vec::new();
+            for p in rel_paths {
+                input.extend_from_slice(p.as_bytes());
+                input.push(0);
+            }
+            stdin.write_all(&input).map_err(|e| ApplyError::Io {
+                path: PathBuf::from("git stdin"),
+                source: e,
+            })?;
+        }
+        let out = child.wait_with_output().map_err(|e| ApplyError::Io {
+            path: PathBuf::from("git"),
+            source: e,
+        })?;
+        if !out.status.success() {
+            debug!(
+                stderr = %String::from_utf8_lossy(&out.stderr).trim(),
+                "gitattributes: check-attr failed; ignoring attributes"
+            );
+            return Ok(attrs);
+        }
+
+        // `-z` output is `<path> NUL <attribute> NUL <value> NUL` per path.
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let fields: Vec<&str> = stdout.split('\0').collect();
+        for triple in fields.chunks_exact(3) {
+            if let Some(attr) = MergeAttribute::from_check_attr(triple[2]) {
+                attrs.insert(triple[0].to_string(), attr);
+            }
+        }
+        Ok(attrs)
+    }
+
+    /// Map each ignored path in `rel_paths` to the rule that ignored it.
+    fn ignored_set(&self, rel_paths: &[String]) -> Result<HashMap<String, IgnoreRule>, ApplyError> {
         if rel_paths.is_empty() {
-            return Ok(std::collections::HashSet::new());
+            return Ok(HashMap::new());
         }
 
         trace!(count = rel_paths.len(), "gitignore: check");
@@ -660,21 +1653,80 @@ impl GitIgnore {
             });
         }
 
-        let mut ignored = std::collections::HashSet::new();
+        let mut ignored = HashMap::new();
         let stdout = String::from_utf8_lossy(&out.stdout);
         for line in stdout.lines() {
             let Some((left, path)) = line.split_once('\t') else {
                 continue;
             };
-            if left.starts_with("::") {
-                continue;
-            };
-            ignored.insert(path.to_string());
+            if let Some(rule) = parse_ignore_rule(left) {
+                ignored.insert(path.to_string(), rule);
+            }
         }
         Ok(ignored)
     }
 }
 
+/// Parse the `<source>:<line>:<pattern>` half of a `check-ignore --verbose` line.
+///
+/// Returns `None` for non-matching paths (`::`) and for negated patterns, which
+/// match a path in order to re-include it. Any other match means the path is ignored,
+/// even if the source cannot be parsed; the rule is then only as precise as it can be.
+fn parse_ignore_rule(left: &str) -> Option<IgnoreRule> {
+    if left == "::" {
+        return None;
+    }
+    // Both the source (a Windows drive, an odd file name) and the pattern may contain ':',
+    // so look for the first `:<line>:` instead of splitting.
+    let parsed = left.match_indices(':').find_map(|(at, _)| {
+        let rest = &left[at + 1..];
+        let (line, pattern) = rest.split_once(':')?;
+        let line = line.parse().ok()?;
+        Some((&left[..at], line, pattern))
+    });
+    let Some((source, line, pattern)) = parsed else {
+        return Some(IgnoreRule {
+            source: left.to_string(),
+            line: 0,
+            pattern: String::new(),
+        });
+    };
+    if pattern.starts_with('!') {
+        return None;
+    }
+    Some(IgnoreRule {
+        source: source.to_string(),
+        line,
+        pattern: pattern.to_string(),
+    })
+}
+
+/// Log the non-`.gitignore` exclude sources git will consult for `dest_root`.
+///
+/// `git check-ignore` already reads `$GIT_DIR/info/exclude` and `core.excludesFile`
+/// (defaulting to `$XDG_CONFIG_HOME/git/ignore`); this only makes them visible in `-vv` runs.
+fn log_exclude_sources(dest_root: &Path) {
+    let git_stdout = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(dest_root)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let info_exclude = git_stdout(&["rev-parse", "--git-path", "info/exclude"]);
+    let excludes_file = git_stdout(&["config", "--path", "core.excludesFile"]);
+    debug!(
+        info_exclude = info_exclude.as_deref().unwrap_or("-"),
+        excludes_file = excludes_file.as_deref().unwrap_or("(git default)"),
+        "gitignore: exclude sources"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -709,6 +1761,35 @@ mod tests {
         let _ = fs::remove_dir_all(temp);
     }
 
+    #[test]
+    fn parse_ignore_rule_handles_sources_and_negation() {
+        assert_eq!(
+            parse_ignore_rule(".gitignore:3:*.log"),
+            Some(IgnoreRule {
+                source: ".gitignore".to_string(),
+                line: 3,
+                pattern: "*.log".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_ignore_rule("sub/.gitignore:1:a:b").map(|r| r.pattern),
+            Some("a:b".to_string())
+        );
+        assert_eq!(parse_ignore_rule("::"), None);
+        assert_eq!(parse_ignore_rule(".gitignore:2:!keep.log"), None);
+
+        // Matches whose source has a ':' or cannot be parsed still count as ignored.
+        assert_eq!(
+            parse_ignore_rule("C:\\Users\\ada\\ignore:4:target/"),
+            Some(IgnoreRule {
+                source: "C:\\Users\\ada\\ignore".to_string(),
+                line: 4,
+                pattern: "target/".to_string(),
+            })
+        );
+        assert!(parse_ignore_rule("odd source").is_some());
+    }
+
     #[test]
     fn format_git_rel_adds_trailing_slash_for_dirs() {
         assert_eq!(format_git_rel(Path::new("a/b"), true), "a/b/");
@@ -1,6 +1,8 @@
 #![forbid(unsafe_code)]
 
 use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use tracing::debug;
 
@@ -10,6 +12,34 @@ pub struct RenderedLicense {
     pub text: String,
 }
 
+/// SPDX template variables that name the copyright holder, across license texts.
+pub const HOLDER_PLACEHOLDERS: &[&str] =
+    &["fullname", "copyright holders", "owner", "name of author"];
+
+/// Metadata about an SPDX license and the placeholders its text expects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LicenseInfo {
+    pub spdx: String,
+    pub name: String,
+    pub osi_approved: bool,
+    pub fsf_libre: bool,
+    pub deprecated: bool,
+    /// Template variables named in the text (`<year>`, `<<var;name=...>>`), in order of first use.
+    pub placeholders: Vec<String>,
+}
+
+impl LicenseInfo {
+    pub fn requires_year(&self) -> bool {
+        self.placeholders.iter().any(|p| p == "year")
+    }
+
+    pub fn requires_fullname(&self) -> bool {
+        self.placeholders
+            .iter()
+            .any(|p| HOLDER_PLACEHOLDERS.contains(&p.as_str()))
+    }
+}
+
 #[derive(Debug)]
 pub enum LicenseError {
     UnknownSpdxId { spdx: String },
@@ -37,24 +67,334 @@ pub fn render_spdx_license(
     spdx: &str,
     template_args: &BTreeMap<String, String>,
 ) -> Result<RenderedLicense, LicenseError> {
-    use std::str::FromStr;
-
-    let parsed: &dyn license::License =
-        <&dyn license::License>::from_str(spdx).map_err(|_| LicenseError::UnknownSpdxId {
-            spdx: spdx.to_string(),
-        })?;
-
+    let parsed = parse_spdx(spdx)?;
     let raw = parsed.text();
     let mut args = template_args.clone();
     maybe_insert_current_year(raw, &mut args);
-    let expanded = expand_spdx_template(spdx, raw, &args)?;
-    let expanded = replace_angle_placeholders(&expanded, &args);
+    render_text(spdx, raw, &args)
+}
+
+fn render_text(
+    spdx: &str,
+    raw: &str,
+    args: &BTreeMap<String, String>,
+) -> Result<RenderedLicense, LicenseError> {
+    let expanded = expand_spdx_template(spdx, raw, args)?;
+    let expanded = replace_angle_placeholders(&expanded, args);
     Ok(RenderedLicense {
         spdx: spdx.to_string(),
         text: expanded,
     })
 }
 
+/// On-disk cache of SPDX license texts, keyed by id.
+///
+/// Raw texts live at `<dir>/<id>.txt`, refreshed from the bundled license
+/// data and used for ids it does not know, so a populated cache keeps working
+/// offline if texts ever come from the network. Rendered texts live at `<dir>/rendered/<id>-<hash>.txt`, keyed
+/// by the raw text and the template arguments, so repeated renders skip
+/// template expansion. Cache I/O failures fall back to rendering from scratch.
+#[derive(Clone, Debug)]
+pub struct LicenseCache {
+    dir: PathBuf,
+}
+
+impl LicenseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache inside pinit's cache directory (`<cache>/pinit/licenses`).
+    pub fn in_cache_dir(cache_dir: &Path) -> Self {
+        Self::new(cache_dir.join("licenses"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Raw SPDX template text for `spdx`, from the bundled data or the cache.
+    pub fn raw_text(&self, spdx: &str) -> Result<String, LicenseError> {
+        let text = self.read_raw_text(spdx)?;
+        if parse_spdx(spdx).is_ok() {
+            let path = self.raw_path(spdx)?;
+            if fs::read_to_string(&path).ok().as_deref() != Some(text.as_str()) {
+                write_cache_file(&path, &text);
+            }
+        }
+        Ok(text)
+    }
+
+    /// Raw SPDX template text for `spdx`, like [`Self::raw_text`] but without
+    /// writing to the cache.
+    pub fn read_raw_text(&self, spdx: &str) -> Result<String, LicenseError> {
+        match parse_spdx(spdx) {
+            Ok(parsed) => Ok(parsed.text().to_string()),
+            Err(e) => {
+                let path = self.raw_path(spdx)?;
+                let text = fs::read_to_string(&path).map_err(|_| e)?;
+                debug!(spdx = %spdx, path = %path.display(), "license text from cache");
+                Ok(text)
+            }
+        }
+    }
+
+    /// Like [`license_info`], falling back to the cached text for ids the
+    /// bundled data does not know; those are named by their id, with every
+    /// flag `false`.
+    pub fn info(&self, spdx: &str) -> Result<LicenseInfo, LicenseError> {
+        license_info(spdx).or_else(|e| {
+            let raw = self.read_raw_text(spdx).map_err(|_| e)?;
+            Ok(LicenseInfo {
+                spdx: spdx.to_string(),
+                name: spdx.to_string(),
+                osi_approved: false,
+                fsf_libre: false,
+                deprecated: false,
+                placeholders: template_placeholders(&raw),
+            })
+        })
+    }
+
+    /// Like [`render_spdx_license`], reusing a cached rendering when possible.
+    pub fn render(
+        &self,
+        spdx: &str,
+        template_args: &BTreeMap<String, String>,
+    ) -> Result<RenderedLicense, LicenseError> {
+        let raw = self.raw_text(spdx)?;
+        let mut args = template_args.clone();
+        maybe_insert_current_year(&raw, &mut args);
+
+        let path = self.rendered_path(spdx, &raw, &args)?;
+        if let Ok(text) = fs::read_to_string(&path) {
+            debug!(spdx = %spdx, path = %path.display(), "rendered license cached");
+            return Ok(RenderedLicense {
+                spdx: spdx.to_string(),
+                text,
+            });
+        }
+        let rendered = render_text(spdx, &raw, &args)?;
+        write_cache_file(&path, &rendered.text);
+        Ok(rendered)
+    }
+
+    /// Like [`Self::render`], but only reads the cache, never writes it.
+    pub fn render_read_only(
+        &self,
+        spdx: &str,
+        template_args: &BTreeMap<String, String>,
+    ) -> Result<RenderedLicense, LicenseError> {
+        let raw = self.read_raw_text(spdx)?;
+        let mut args = template_args.clone();
+        maybe_insert_current_year(&raw, &mut args);
+
+        let path = self.rendered_path(spdx, &raw, &args)?;
+        if let Ok(text) = fs::read_to_string(&path) {
+            debug!(spdx = %spdx, path = %path.display(), "rendered license cached");
+            return Ok(RenderedLicense {
+                spdx: spdx.to_string(),
+                text,
+            });
+        }
+        render_text(spdx, &raw, &args)
+    }
+
+    fn raw_path(&self, spdx: &str) -> Result<PathBuf, LicenseError> {
+        Ok(self.dir.join(format!("{}.txt", cache_name(spdx)?)))
+    }
+
+    fn rendered_path(
+        &self,
+        spdx: &str,
+        raw: &str,
+        args: &BTreeMap<String, String>,
+    ) -> Result<PathBuf, LicenseError> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(raw.as_bytes());
+        for (name, value) in args {
+            // Length prefixes keep `a=bc` and `ab=c` apart.
+            for part in [name, value] {
+                hasher.update(&(part.len() as u64).to_le_bytes());
+                hasher.update(part.as_bytes());
+            }
+        }
+        let hash = hasher.finalize().to_hex();
+        Ok(self
+            .dir
+            .join("rendered")
+            .join(format!("{}-{}.txt", cache_name(spdx)?, &hash[..16])))
+    }
+}
+
+/// `spdx` as a file name; ids are letters, digits, `.`, `-`, and `+`.
+fn cache_name(spdx: &str) -> Result<&str, LicenseError> {
+    let valid = !spdx.is_empty()
+        && !spdx.starts_with('.')
+        && spdx
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'+'));
+    if valid {
+        Ok(spdx)
+    } else {
+        Err(LicenseError::UnknownSpdxId {
+            spdx: spdx.to_string(),
+        })
+    }
+}
+
+fn write_cache_file(path: &Path, text: &str) {
+    let result = (|| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename so a concurrent reader never sees a partial file.
+        let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, path)
+    })();
+    if let Err(e) = result {
+        debug!(error = %e, path = %path.display(), "license cache write failed");
+    }
+}
+
+/// Look up `spdx` and describe it without rendering.
+pub fn license_info(spdx: &str) -> Result<LicenseInfo, LicenseError> {
+    let parsed = parse_spdx(spdx)?;
+    Ok(LicenseInfo {
+        spdx: parsed.id().to_string(),
+        name: parsed.name().to_string(),
+        osi_approved: parsed.is_osi_approved(),
+        fsf_libre: parsed.is_fsf_libre(),
+        deprecated: parsed.is_deprecated(),
+        placeholders: template_placeholders(parsed.text()),
+    })
+}
+
+/// Best-effort SPDX id of an existing license file.
+///
+/// An `SPDX-License-Identifier:` tag wins; otherwise the text is matched against
+/// distinctive phrases of common licenses. Returns `None` when unsure.
+pub fn detect_spdx(text: &str) -> Option<String> {
+    for line in text.lines().take(20) {
+        if let Some((_, id)) = line.split_once("SPDX-License-Identifier:") {
+            let id = id.trim().trim_end_matches("*/").trim();
+            if !id.is_empty() {
+                return Some(id.to_string());
+            }
+        }
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let normalized = words.join(" ");
+    let has = |phrase: &str| normalized.contains(phrase);
+    // License bodies cite each other (the GPL mentions the AGPL), so families
+    // are told apart by their title.
+    let title = words[..words.len().min(16)].join(" ");
+    let titled = |phrase: &str| title.contains(phrase);
+
+    let id = if titled("gnu affero general public license") && titled("version 3") {
+        "AGPL-3.0-only"
+    } else if titled("gnu lesser general public license") {
+        if titled("version 3") {
+            "LGPL-3.0-only"
+        } else {
+            "LGPL-2.1-only"
+        }
+    } else if titled("gnu general public license") {
+        if titled("version 3") {
+            "GPL-3.0-only"
+        } else if titled("version 2") {
+            "GPL-2.0-only"
+        } else {
+            return None;
+        }
+    } else if titled("apache license") && titled("version 2.0") {
+        "Apache-2.0"
+    } else if titled("mozilla public license version 2.0") {
+        "MPL-2.0"
+    } else if titled("boost software license version 1.0") {
+        "BSL-1.0"
+    } else if has("this is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else if has("permission is hereby granted free of charge to any person obtaining a copy") {
+        "MIT"
+    } else if has("redistribution and use in source and binary forms with or without modification")
+    {
+        if has("neither the name of") {
+            "BSD-3-Clause"
+        } else {
+            "BSD-2-Clause"
+        }
+    } else if has("permission to use copy modify and or distribute this software for any purpose") {
+        if has("provided that the above copyright notice and this permission notice appear") {
+            "ISC"
+        } else {
+            "0BSD"
+        }
+    } else {
+        return None;
+    };
+    Some(id.to_string())
+}
+
+fn parse_spdx(spdx: &str) -> Result<&'static dyn license::License, LicenseError> {
+    use std::str::FromStr;
+
+    <&dyn license::License>::from_str(spdx).map_err(|_| LicenseError::UnknownSpdxId {
+        spdx: spdx.to_string(),
+    })
+}
+
+/// Variable names used by `<<var;name=...>>` directives and `<name>` placeholders.
+///
+/// Angle-bracketed URLs (`<https://fsf.org/>`) are text, not placeholders.
+fn template_placeholders(template: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut push = |name: &str| {
+        if !name.is_empty() && !out.iter().any(|n| n == name) {
+            out.push(name.to_string());
+        }
+    };
+
+    let mut idx = 0usize;
+    while let Some(open_rel) = template[idx..].find('<') {
+        let open = idx + open_rel;
+        if template[open..].starts_with("<<") {
+            let Some(close_rel) = template[open + 2..].find(">>") else {
+                break;
+            };
+            let close = open + 2 + close_rel;
+            let directive = template[open + 2..close].trim();
+            if directive.to_ascii_lowercase().starts_with("var;") {
+                for part in split_semicolons(directive) {
+                    if let Some((key, value)) = part.split_once('=')
+                        && key.trim() == "name"
+                    {
+                        push(&unquote(value.trim()));
+                    }
+                }
+            }
+            idx = close + 2;
+            continue;
+        }
+        let Some(close_rel) = template[open + 1..].find('>') else {
+            break;
+        };
+        let close = open + 1 + close_rel;
+        let key = template[open + 1..close].trim();
+        if !key.contains([':', '/', '<', '\n']) {
+            push(key);
+        }
+        idx = close + 1;
+    }
+    out
+}
+
 fn expand_spdx_template(
     spdx: &str,
     template: &str,
@@ -259,27 +599,9 @@ fn template_supports_year(template: &str) -> bool {
 }
 
 fn current_year_string() -> String {
-    let now = std::time::SystemTime::now();
-    let duration = now
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default();
-    let days = (duration.as_secs() / 86_400) as i64;
-    let year = civil_from_days(days).0;
-    year.to_string()
-}
-
-fn civil_from_days(days: i64) -> (i32, u32, u32) {
-    let z = days + 719_468;
-    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
-    let doe = z - era * 146_097;
-    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
-    let y = yoe + era * 400;
-    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
-    let mp = (5 * doy + 2) / 153;
-    let d = doy - (153 * mp + 2) / 5 + 1;
-    let m = mp + if mp < 10 { 3 } else { -9 };
-    let year = y + if m <= 2 { 1 } else { 0 };
-    (year as i32, m as u32, d as u32)
+    crate::time::utc_date(std::time::SystemTime::now())
+        .0
+        .to_string()
 }
 
 #[cfg(test)]
@@ -328,6 +650,116 @@ mod tests {
         assert!(matches!(err, LicenseError::UnterminatedDirective { .. }));
     }
 
+    #[test]
+    fn license_info_lists_placeholders_but_not_urls() {
+        let info = license_info("GPL-3.0-only").unwrap();
+        assert_eq!(info.name, "GNU General Public License v3.0 only");
+        assert!(info.osi_approved);
+        assert!(!info.deprecated);
+        assert!(info.placeholders.iter().any(|p| p == "name of author"));
+        assert!(!info.placeholders.iter().any(|p| p.contains("gnu.org")));
+        assert!(info.requires_year());
+        assert!(info.requires_fullname());
+
+        let info = license_info("Apache-2.0").unwrap();
+        assert!(info.placeholders.is_empty());
+        assert!(!info.requires_fullname());
+        assert!(license_info("GPL-3.0").unwrap().deprecated);
+    }
+
+    #[test]
+    fn detect_spdx_recognizes_rendered_licenses_and_tags() {
+        for id in [
+            "MIT",
+            "Apache-2.0",
+            "GPL-3.0-only",
+            "AGPL-3.0-only",
+            "LGPL-3.0-only",
+            "MPL-2.0",
+            "BSD-2-Clause",
+            "BSD-3-Clause",
+            "ISC",
+            "0BSD",
+            "Unlicense",
+            "BSL-1.0",
+        ] {
+            let mut args = BTreeMap::new();
+            args.insert("copyright holders".to_string(), "Jo".to_string());
+            args.insert("owner".to_string(), "Jo".to_string());
+            let rendered = render_spdx_license(id, &args).unwrap();
+            assert_eq!(detect_spdx(&rendered.text).as_deref(), Some(id), "{id}");
+        }
+        assert_eq!(
+            detect_spdx("// SPDX-License-Identifier: MIT OR Apache-2.0\n").as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(detect_spdx("All rights reserved.\n"), None);
+    }
+
+    fn temp_cache(name: &str) -> LicenseCache {
+        let dir =
+            std::env::temp_dir().join(format!("pinit-license-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        LicenseCache::new(dir)
+    }
+
+    #[test]
+    fn cache_stores_raw_and_rendered_texts() {
+        let cache = temp_cache("render");
+        let mut args = BTreeMap::new();
+        args.insert("year".to_string(), "2025".to_string());
+        args.insert("copyright holders".to_string(), "Clay".to_string());
+
+        let first = cache.render("MIT", &args).unwrap();
+        assert_eq!(first, render_spdx_license("MIT", &args).unwrap());
+        assert!(cache.dir().join("MIT.txt").is_file());
+        let rendered: Vec<_> = fs::read_dir(cache.dir().join("rendered"))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert_eq!(rendered.len(), 1);
+
+        // A second render with the same arguments comes straight from the cache.
+        fs::write(&rendered[0], "cached\n").unwrap();
+        assert_eq!(cache.render("MIT", &args).unwrap().text, "cached\n");
+        args.insert("year".to_string(), "2026".to_string());
+        assert!(cache.render("MIT", &args).unwrap().text.contains("2026"));
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn cached_raw_text_works_without_bundled_data() {
+        let cache = temp_cache("offline");
+        fs::create_dir_all(cache.dir()).unwrap();
+        fs::write(
+            cache.dir().join("Example-1.0.txt"),
+            "Example License <year> <owner>\n",
+        )
+        .unwrap();
+        let mut args = BTreeMap::new();
+        args.insert("year".to_string(), "2025".to_string());
+        args.insert("owner".to_string(), "Jo".to_string());
+        let rendered = cache.render("Example-1.0", &args).unwrap();
+        assert_eq!(rendered.text, "Example License 2025 Jo\n");
+
+        assert!(matches!(
+            cache.render("../MIT", &args),
+            Err(LicenseError::UnknownSpdxId { .. })
+        ));
+        assert!(matches!(
+            cache.render("Not-A-License", &args),
+            Err(LicenseError::UnknownSpdxId { .. })
+        ));
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn template_placeholders_reads_var_directives() {
+        let tpl =
+            "<<var;name=\"copyright\";original=\"(c)\">> <year> <<beginOptional>>x<<endOptional>>";
+        assert_eq!(template_placeholders(tpl), vec!["copyright", "year"]);
+    }
+
     #[test]
     fn split_semicolons_respects_quotes() {
         let parts = split_semicolons(r#"var;name="a;b";original='c;d';x=y"#);
@@ -0,0 +1,305 @@
+#![forbid(unsafe_code)]
+
+//! Template manifests.
+//!
+//! A template directory may ship a `pinit-template.toml` at its root describing
+//! what it is and which variables it understands. The manifest is metadata only:
+//! it is never copied into the destination.
+//!
+//! A template may also ship a `pinit-policy.toml` with default actions for its
+//! own files (see [`TemplatePolicy`]). Like the manifest, it is never copied.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::config::OverrideRule;
+use crate::version::{self, RequirementError};
+
+/// File name of the template manifest, relative to the template root.
+pub const MANIFEST_FILE: &str = "pinit-template.toml";
+
+/// File name of the template policy, relative to the template root.
+pub const POLICY_FILE: &str = "pinit-policy.toml";
+
+/// Parsed template manifest contents.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TemplateManifest {
+    /// Short human description shown by `pinit list`.
+    pub description: Option<String>,
+
+    /// Variables the template understands, by name.
+    #[serde(default)]
+    pub variables: BTreeMap<String, VariableDef>,
+
+    /// Assertions checked by `pinit template test`.
+    pub test: Option<TemplateTest>,
+
+    /// pinit versions the template needs, e.g. `">=0.5"`.
+    pub requires_pinit: Option<String>,
+}
+
+/// Test declared by a template manifest.
+///
+/// The template is applied into an empty directory with `variables`, then each
+/// assertion is checked against the result.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TemplateTest {
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+
+    /// Paths (relative to the output) that must exist.
+    #[serde(default)]
+    pub exists: Vec<PathBuf>,
+
+    /// Files whose contents must match a regex.
+    #[serde(default)]
+    pub matches: Vec<ContentMatch>,
+
+    /// Command that must exit successfully, run in the output directory.
+    #[serde(default)]
+    pub command: Vec<String>,
+}
+
+/// A file that must contain a match for `regex` (multi-line mode).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct ContentMatch {
+    pub path: PathBuf,
+    pub regex: String,
+}
+
+/// Variable declared by a template manifest.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct VariableDef {
+    pub description: Option<String>,
+
+    /// Value used when the caller does not provide one.
+    pub default: Option<String>,
+
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Per-glob default actions a template declares for its own files.
+///
+/// Rules use the same shape as config `overrides` and match destination paths.
+/// They rank below every user-supplied rule: config overrides and `--override`
+/// patterns always win.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TemplatePolicy {
+    #[serde(default)]
+    pub overrides: Vec<OverrideRule>,
+}
+
+/// Errors encountered while reading a template manifest or policy.
+#[derive(Debug)]
+pub enum ManifestError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// The manifest's `requires_pinit` is malformed or not met by this build.
+    Requirement {
+        path: PathBuf,
+        source: RequirementError,
+    },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ManifestError::Parse { path, source } => write!(f, "{}: {}", path.display(), source),
+            ManifestError::Requirement { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ManifestError::Io { source, .. } => Some(source),
+            ManifestError::Parse { source, .. } => Some(source),
+            ManifestError::Requirement { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Load the manifest from a template directory, if it has one.
+pub fn load_manifest(template_dir: &Path) -> Result<Option<TemplateManifest>, ManifestError> {
+    let path = template_dir.join(MANIFEST_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ManifestError::Io { path, source: e }),
+    };
+    debug!(path = %path.display(), "manifest: load");
+    requirement_met(&path, &content)?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| ManifestError::Parse { path, source: e })
+}
+
+/// Fail if the template's manifest declares a `requires_pinit` this build does
+/// not meet. The rest of the manifest is not parsed.
+pub fn check_requirement(template_dir: &Path) -> Result<(), ManifestError> {
+    let path = template_dir.join(MANIFEST_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => requirement_met(&path, &content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(ManifestError::Io { path, source: e }),
+    }
+}
+
+fn requirement_met(path: &Path, content: &str) -> Result<(), ManifestError> {
+    #[derive(Deserialize)]
+    struct Requirement {
+        requires_pinit: Option<String>,
+    }
+
+    // A manifest for a newer pinit may not parse here, so only this key is read.
+    let Some(required) = toml::from_str::<Requirement>(content)
+        .ok()
+        .and_then(|r| r.requires_pinit)
+    else {
+        return Ok(());
+    };
+    version::check_pinit_version(&required).map_err(|source| ManifestError::Requirement {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Load the policy from a template directory, if it has one.
+pub fn load_policy(template_dir: &Path) -> Result<Option<TemplatePolicy>, ManifestError> {
+    let path = template_dir.join(POLICY_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ManifestError::Io { path, source: e }),
+    };
+    debug!(path = %path.display(), "policy: load");
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| ManifestError::Parse { path, source: e })
+}
+
+/// Return true if `rel` (relative to a template root) is the manifest or policy file.
+pub fn is_manifest_path(rel: &Path) -> bool {
+    rel == Path::new(MANIFEST_FILE) || rel == Path::new(POLICY_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_description_and_variables() {
+        let manifest: TemplateManifest = toml::from_str(
+            r#"
+description = "Rust library baseline"
+
+[variables.project_name]
+description = "Crate name"
+required = true
+
+[variables.edition]
+default = "2024"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.description.as_deref(),
+            Some("Rust library baseline")
+        );
+        assert!(manifest.variables["project_name"].required);
+        assert_eq!(
+            manifest.variables["edition"].default.as_deref(),
+            Some("2024")
+        );
+    }
+
+    #[test]
+    fn parses_test_section() {
+        let manifest: TemplateManifest = toml::from_str(
+            r#"
+[test]
+variables = { project_name = "demo" }
+exists = ["Cargo.toml"]
+command = ["cargo", "check"]
+
+[[test.matches]]
+path = "Cargo.toml"
+regex = '^name = "demo"$'
+"#,
+        )
+        .unwrap();
+
+        let test = manifest.test.unwrap();
+        assert_eq!(test.variables["project_name"], "demo");
+        assert_eq!(test.exists, vec![PathBuf::from("Cargo.toml")]);
+        assert_eq!(test.matches[0].regex, "^name = \"demo\"$");
+        assert_eq!(test.command, vec!["cargo", "check"]);
+    }
+
+    #[test]
+    fn parses_policy_rules() {
+        use crate::config::OverrideAction;
+
+        let policy: TemplatePolicy = toml::from_str(
+            r#"
+[[overrides]]
+pattern = ".github/workflows/release.yml"
+action = "overwrite"
+
+[[overrides]]
+path = "Cargo.toml"
+action = "merge"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.overrides.len(), 2);
+        assert_eq!(policy.overrides[0].action, OverrideAction::Overwrite);
+        assert_eq!(policy.overrides[1].pattern, "Cargo.toml");
+    }
+
+    #[test]
+    fn requirement_is_checked_before_the_rest_of_the_manifest() {
+        let path = Path::new(MANIFEST_FILE);
+        requirement_met(path, "requires_pinit = \">=0.1\"\n").unwrap();
+        // Unknown shapes from a newer pinit still report the version first.
+        let err = requirement_met(path, "requires_pinit = \">=999\"\ntest = 3\n").unwrap_err();
+        assert!(matches!(
+            err,
+            ManifestError::Requirement {
+                source: RequirementError::Unsatisfied { .. },
+                ..
+            }
+        ));
+        assert!(
+            err.to_string()
+                .contains("requires pinit >=999, but this is pinit")
+        );
+        requirement_met(path, "description = \"no requirement\"\n").unwrap();
+    }
+
+    #[test]
+    fn manifest_path_only_matches_root() {
+        assert!(is_manifest_path(Path::new(MANIFEST_FILE)));
+        assert!(is_manifest_path(Path::new(POLICY_FILE)));
+        assert!(!is_manifest_path(Path::new("nested/pinit-template.toml")));
+    }
+}
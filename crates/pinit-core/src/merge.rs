@@ -6,16 +6,31 @@ use std::path::Path;
 use tracing::debug;
 use yaml_rust2::{Yaml, YamlEmitter, YamlLoader};
 
+/// A format-aware merge: destination and template bytes in, merged bytes out.
+pub(crate) type MergeDriver = fn(&[u8], &[u8]) -> Option<Vec<u8>>;
+
 pub fn merge_file(rel_path: &Path, dest_bytes: &[u8], src_bytes: &[u8]) -> Option<Vec<u8>> {
+    match driver(rel_path) {
+        Some(merge) => merge(dest_bytes, src_bytes),
+        None => merge_lines(dest_bytes, src_bytes),
+    }
+}
+
+/// The merge driver for `rel_path`; `None` means [`merge_file`] falls back to a
+/// line-wise union.
+pub(crate) fn driver(rel_path: &Path) -> Option<MergeDriver> {
     let file_name = rel_path
         .file_name()
         .and_then(|s| s.to_str())
         .unwrap_or_default();
     if file_name == ".envrc" {
-        return merge_envrc(dest_bytes, src_bytes);
+        return Some(merge_envrc);
     }
     if file_name == ".env" || file_name.starts_with(".env.") {
-        return merge_env(dest_bytes, src_bytes);
+        return Some(merge_env);
+    }
+    if is_ignore_file(file_name) {
+        return Some(merge_ignore);
     }
 
     let ext = rel_path
@@ -23,27 +38,42 @@ pub fn merge_file(rel_path: &Path, dest_bytes: &[u8], src_bytes: &[u8]) -> Optio
         .and_then(|s| s.to_str())
         .unwrap_or_default()
         .to_ascii_lowercase();
-    match ext.as_str() {
-        "toml" => merge_toml(dest_bytes, src_bytes),
-        "yml" | "yaml" => merge_yaml(dest_bytes, src_bytes),
-        "rs" => merge_rust(dest_bytes, src_bytes),
-        "php" => merge_php(dest_bytes, src_bytes),
-        "py" => merge_python(dest_bytes, src_bytes),
-        "js" | "mjs" | "cjs" => merge_javascript(dest_bytes, src_bytes),
-        "ts" => merge_typescript(dest_bytes, src_bytes),
-        "tsx" => merge_tsx(dest_bytes, src_bytes),
-        "css" => merge_css(dest_bytes, src_bytes),
-        "md" | "markdown" => merge_markdown(dest_bytes, src_bytes),
-        "lua" => merge_lua(dest_bytes, src_bytes),
-        "sh" | "bash" => merge_bash(dest_bytes, src_bytes),
-        "zsh" => merge_zsh(dest_bytes, src_bytes),
-        "rb" => merge_ruby(dest_bytes, src_bytes),
-        "html" | "htm" => merge_html(dest_bytes, src_bytes),
-        _ => merge_lines(dest_bytes, src_bytes),
-    }
+    EXTENSION_DRIVERS
+        .iter()
+        .find(|(_, exts, _)| exts.contains(&ext.as_str()))
+        .map(|(_, _, merge)| *merge)
+}
+
+/// Drivers picked by file extension: name, extensions, driver.
+const EXTENSION_DRIVERS: &[(&str, &[&str], MergeDriver)] = &[
+    ("toml", &["toml"], merge_toml),
+    ("yaml", &["yml", "yaml"], merge_yaml),
+    ("rust", &["rs"], merge_rust),
+    ("php", &["php"], merge_php),
+    ("python", &["py"], merge_python),
+    ("javascript", &["js", "mjs", "cjs"], merge_javascript),
+    ("typescript", &["ts"], merge_typescript),
+    ("tsx", &["tsx"], merge_tsx),
+    ("css", &["css"], merge_css),
+    ("markdown", &["md", "markdown"], merge_markdown),
+    ("lua", &["lua"], merge_lua),
+    ("bash", &["sh", "bash"], merge_bash),
+    ("zsh", &["zsh"], merge_zsh),
+    ("ruby", &["rb"], merge_ruby),
+    ("html", &["html", "htm"], merge_html),
+];
+
+/// Names of the format-aware merge drivers compiled into this build, for `pinit version`.
+///
+/// Files matching none of them are merged line by line.
+pub fn merge_driver_names() -> Vec<&'static str> {
+    let mut names = vec!["envrc", "env", "ignore"];
+    names.extend(EXTENSION_DRIVERS.iter().map(|(name, _, _)| *name));
+    names
 }
 
-fn merge_lines(dest_bytes: &[u8], src_bytes: &[u8]) -> Option<Vec<u8>> {
+/// Line-wise union: the destination, then template lines it does not already have.
+pub(crate) fn merge_lines(dest_bytes: &[u8], src_bytes: &[u8]) -> Option<Vec<u8>> {
     let dest = std::str::from_utf8(dest_bytes).ok()?;
     let src = std::str::from_utf8(src_bytes).ok()?;
 
@@ -74,6 +104,89 @@ fn merge_lines(dest_bytes: &[u8], src_bytes: &[u8]) -> Option<Vec<u8>> {
     Some(out.into_bytes())
 }
 
+/// `.gitignore`, `.dockerignore`, `.npmignore`, and friends.
+fn is_ignore_file(file_name: &str) -> bool {
+    file_name.starts_with('.') && file_name.ends_with("ignore")
+}
+
+fn merge_ignore(dest_bytes: &[u8], src_bytes: &[u8]) -> Option<Vec<u8>> {
+    let dest = std::str::from_utf8(dest_bytes).ok()?;
+    let src = std::str::from_utf8(src_bytes).ok()?;
+    match merge_ignore_text(dest, src) {
+        Some(out) => Some(out.into_bytes()),
+        None => Some(dest_bytes.to_vec()),
+    }
+}
+
+/// Append patterns from `src` that `dest` does not already have.
+///
+/// Patterns are compared after trimming unescaped trailing whitespace, which
+/// git ignores too. Comments directly above a new pattern come along with it;
+/// comments and blank lines are never deduplicated on their own. Returns `None`
+/// when nothing new was found.
+pub(crate) fn merge_ignore_text(dest: &str, src: &str) -> Option<String> {
+    let mut have: HashSet<&str> = dest.lines().filter_map(ignore_pattern).collect();
+
+    let mut appended = String::new();
+    let mut pending_comments: Vec<&str> = Vec::new();
+    for line in src.lines() {
+        if line.trim().is_empty() {
+            pending_comments.clear();
+            continue;
+        }
+        let Some(pattern) = ignore_pattern(line) else {
+            pending_comments.push(line);
+            continue;
+        };
+        if !have.insert(pattern) {
+            continue;
+        }
+        if !pending_comments.is_empty() {
+            if !appended.is_empty() {
+                appended.push('\n');
+            }
+            for comment in pending_comments.drain(..) {
+                appended.push_str(comment);
+                appended.push('\n');
+            }
+        }
+        appended.push_str(pattern);
+        appended.push('\n');
+    }
+
+    if appended.is_empty() {
+        return None;
+    }
+
+    let mut out = String::with_capacity(dest.len() + appended.len() + 2);
+    out.push_str(dest);
+    if !out.is_empty() {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+    }
+    out.push_str(&appended);
+    Some(out)
+}
+
+fn ignore_pattern(line: &str) -> Option<&str> {
+    if line.starts_with('#') {
+        return None;
+    }
+    let mut end = line.len();
+    while end > 0 && line[..end].ends_with([' ', '\t', '\r']) {
+        if line[..end - 1].ends_with('\\') {
+            break;
+        }
+        end -= 1;
+    }
+    let pattern = &line[..end];
+    (!pattern.trim_start().is_empty()).then_some(pattern)
+}
+
 fn merge_env(dest_bytes: &[u8], src_bytes: &[u8]) -> Option<Vec<u8>> {
     let dest = std::str::from_utf8(dest_bytes).ok()?;
     let src = std::str::from_utf8(src_bytes).ok()?;
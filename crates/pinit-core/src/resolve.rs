@@ -11,8 +11,11 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 
-use crate::config::{Config, GitProtocol, TemplateDef};
+use crate::config::{Config, GitProtocol, Source, TemplateDef};
+use crate::timing::{Phase, Timings};
 
 use tracing::{debug, instrument};
 
@@ -22,6 +25,7 @@ pub enum ResolveError {
     NoHomeDir,
     UnknownTemplate(String),
     UnknownSource(String),
+    NotGitTemplate(String),
     TemplatePathNotDir(PathBuf),
     SourcePathMissing {
         source: String,
@@ -29,6 +33,10 @@ pub enum ResolveError {
     SourceRepoMissing {
         source: String,
     },
+    UnknownRef {
+        repo: String,
+        git_ref: String,
+    },
     GitCommandFailed {
         cmd: String,
         status: i32,
@@ -46,6 +54,9 @@ impl fmt::Display for ResolveError {
             ResolveError::NoHomeDir => write!(f, "could not determine a cache directory"),
             ResolveError::UnknownTemplate(name) => write!(f, "unknown template: {name}"),
             ResolveError::UnknownSource(name) => write!(f, "unknown template source: {name}"),
+            ResolveError::NotGitTemplate(name) => {
+                write!(f, "template '{name}' does not come from a git source")
+            }
             ResolveError::TemplatePathNotDir(path) => {
                 write!(f, "template path is not a directory: {}", path.display())
             }
@@ -55,6 +66,9 @@ impl fmt::Display for ResolveError {
             ResolveError::SourceRepoMissing { source } => {
                 write!(f, "source '{source}' is missing 'repo'")
             }
+            ResolveError::UnknownRef { repo, git_ref } => {
+                write!(f, "unknown ref '{git_ref}' in {repo}")
+            }
             ResolveError::GitCommandFailed {
                 cmd,
                 status,
@@ -80,6 +94,8 @@ impl std::error::Error for ResolveError {
 #[derive(Clone, Debug)]
 pub struct TemplateResolver {
     cache_dir: PathBuf,
+    /// Fetch and checkout time, shared by clones of this resolver.
+    timings: Arc<Mutex<Timings>>,
 }
 
 /// Resolved template entry with its name and local directory.
@@ -88,19 +104,107 @@ pub struct ResolvedTemplate {
     pub name: String,
     pub dir: PathBuf,
     pub index: usize,
+    /// Subdirectory of the destination the template's files land under.
+    pub dest_prefix: Option<PathBuf>,
+}
+
+/// Where a template name points, determined without cloning or fetching.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemplateLocation {
+    /// Template directory on the local filesystem.
+    Local { dir: PathBuf },
+    /// Template inside a git source; `commit` is set when a cached checkout exists.
+    Git {
+        repo: String,
+        git_ref: String,
+        dir: PathBuf,
+        commit: Option<String>,
+    },
+}
+
+/// Where a configured source lives, determined without cloning or fetching.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SourceLocation {
+    /// Source root on the local filesystem.
+    Local { root: PathBuf },
+    /// Git repository; the cache fields are set when a checkout exists.
+    Git {
+        /// Repository URL after shorthand expansion (`owner/name` -> full URL).
+        repo: String,
+        git_ref: String,
+        subdir: Option<PathBuf>,
+        /// Cached checkout directory (may not exist yet).
+        checkout: PathBuf,
+        /// Commit the cached checkout is at.
+        commit: Option<String>,
+        /// When the checkout was last cloned or fetched.
+        fetched_at: Option<SystemTime>,
+    },
 }
 
+impl SourceLocation {
+    /// Directory templates from this source are resolved against.
+    pub fn root(&self) -> PathBuf {
+        match self {
+            SourceLocation::Local { root } => root.clone(),
+            SourceLocation::Git {
+                checkout, subdir, ..
+            } => match subdir {
+                Some(subdir) => checkout.join(subdir),
+                None => checkout.clone(),
+            },
+        }
+    }
+}
+
+impl TemplateLocation {
+    /// Local directory for the template (may not exist yet for uncached git sources).
+    pub fn dir(&self) -> &Path {
+        match self {
+            TemplateLocation::Local { dir } => dir,
+            TemplateLocation::Git { dir, .. } => dir,
+        }
+    }
+
+    /// True when the template directory can be read without fetching.
+    pub fn is_available(&self) -> bool {
+        match self {
+            TemplateLocation::Local { dir } => dir.is_dir(),
+            TemplateLocation::Git { commit, dir, .. } => commit.is_some() && dir.is_dir(),
+        }
+    }
+}
+
+/// Environment variable that replaces the default cache directory.
+pub const CACHE_DIR_ENV: &str = "PINIT_CACHE_DIR";
+
 impl TemplateResolver {
+    /// Use `PINIT_CACHE_DIR` if set, else `pinit` under the platform cache directory.
     pub fn with_default_cache() -> Result<Self, ResolveError> {
+        if let Some(dir) = std::env::var_os(CACHE_DIR_ENV).filter(|d| !d.is_empty()) {
+            return Ok(Self::new(PathBuf::from(dir)));
+        }
         let base = directories::BaseDirs::new().ok_or(ResolveError::NoHomeDir)?;
-        Ok(Self {
-            cache_dir: base.cache_dir().join("pinit"),
-        })
+        Ok(Self::new(base.cache_dir().join("pinit")))
+    }
+
+    /// Use `PINIT_CACHE_DIR` if set, then the config's `cache_dir`, then the
+    /// platform default.
+    pub fn for_config(cfg: &Config) -> Result<Self, ResolveError> {
+        match &cfg.cache_dir {
+            Some(dir) if std::env::var_os(CACHE_DIR_ENV).is_none_or(|d| d.is_empty()) => {
+                Ok(Self::new(dir.clone()))
+            }
+            _ => Self::with_default_cache(),
+        }
     }
 
     /// Create a resolver using an explicit cache directory.
     pub fn new(cache_dir: PathBuf) -> Self {
-        Self { cache_dir }
+        Self {
+            cache_dir,
+            timings: Arc::default(),
+        }
     }
 
     /// Return the cache directory path.
@@ -108,6 +212,20 @@ impl TemplateResolver {
         &self.cache_dir
     }
 
+    /// Time spent fetching and checking out git sources so far.
+    pub fn timings(&self) -> Timings {
+        self.timings.lock().map(|t| t.clone()).unwrap_or_default()
+    }
+
+    fn timed<T>(&self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let out = f();
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.add(phase, start.elapsed());
+        }
+        out
+    }
+
     /// Resolve a recipe name to a list of template directories.
     pub fn resolve_recipe_template_dirs(
         &self,
@@ -134,9 +252,14 @@ impl TemplateResolver {
             "resolve recipe"
         );
         let mut out = Vec::new();
-        for (index, name) in resolved.templates.into_iter().enumerate() {
-            let dir = self.resolve_template_dir(cfg, &name)?;
-            out.push(ResolvedTemplate { name, dir, index });
+        for (index, entry) in resolved.templates.into_iter().enumerate() {
+            let dir = self.resolve_template_dir(cfg, entry.name())?;
+            out.push(ResolvedTemplate {
+                name: entry.name().to_string(),
+                dir,
+                index,
+                dest_prefix: entry.dest_prefix().map(Path::to_path_buf),
+            });
         }
         Ok(out)
     }
@@ -153,19 +276,146 @@ impl TemplateResolver {
             .get(template_name)
             .ok_or_else(|| ResolveError::UnknownTemplate(template_name.to_string()))?;
 
-        let path = self.resolve_template_def(cfg, template_name, def)?;
+        let path = self.resolve_template_def(cfg, template_name, def, None)?;
         ensure_is_dir(&path)?;
         Ok(path)
     }
 
+    /// Resolve a git-sourced template at `git_ref` instead of its configured ref.
+    ///
+    /// Each ref gets its own cached checkout, so two revisions can be resolved
+    /// side by side. Templates from local sources return
+    /// [`ResolveError::NotGitTemplate`].
+    pub fn resolve_template_dir_at(
+        &self,
+        cfg: &Config,
+        template_name: &str,
+        git_ref: &str,
+    ) -> Result<PathBuf, ResolveError> {
+        let def = cfg
+            .templates
+            .get(template_name)
+            .ok_or_else(|| ResolveError::UnknownTemplate(template_name.to_string()))?;
+
+        let path = self.resolve_template_def(cfg, template_name, def, Some(git_ref))?;
+        ensure_is_dir(&path)?;
+        Ok(path)
+    }
+
+    /// Describe where a template resolves from without touching the network.
+    pub fn locate_template(
+        &self,
+        cfg: &Config,
+        template_name: &str,
+    ) -> Result<TemplateLocation, ResolveError> {
+        let def = cfg
+            .templates
+            .get(template_name)
+            .ok_or_else(|| ResolveError::UnknownTemplate(template_name.to_string()))?;
+
+        let path = def.path();
+        if path.is_absolute() {
+            return Ok(TemplateLocation::Local {
+                dir: path.to_path_buf(),
+            });
+        }
+
+        let Some(source_name) = def.source() else {
+            return Err(ResolveError::UnknownSource(format!(
+                "template '{template_name}' uses a relative path but has no source"
+            )));
+        };
+        let source = cfg
+            .sources
+            .iter()
+            .find(|s| s.name == source_name)
+            .ok_or_else(|| ResolveError::UnknownSource(source_name.to_string()))?;
+
+        let location = self.locate_source(source)?;
+        let dir = location.root().join(path);
+        Ok(match location {
+            SourceLocation::Local { .. } => TemplateLocation::Local { dir },
+            SourceLocation::Git {
+                repo,
+                git_ref,
+                commit,
+                ..
+            } => TemplateLocation::Git {
+                repo,
+                git_ref,
+                dir,
+                commit,
+            },
+        })
+    }
+
+    /// Describe a configured source and its cache state without touching the network.
+    pub fn locate_source(&self, source: &Source) -> Result<SourceLocation, ResolveError> {
+        if let Some(root) = &source.path {
+            return Ok(SourceLocation::Local { root: root.clone() });
+        }
+
+        let Some(repo) = &source.repo else {
+            return Err(ResolveError::SourceRepoMissing {
+                source: source.name.clone(),
+            });
+        };
+        let repo = normalize_repo(repo, source.git_protocol.unwrap_or(GitProtocol::Ssh));
+        let git_ref = source.git_ref.clone().unwrap_or_else(|| "HEAD".to_string());
+        let checkout = self.repo_checkout_dir(&repo, &git_ref);
+        let (commit, fetched_at) = if checkout.exists() {
+            let commit = git_stdout(&[
+                "-C",
+                checkout.to_string_lossy().as_ref(),
+                "rev-parse",
+                "HEAD",
+            ])
+            .ok();
+            // FETCH_HEAD in the shared mirror is rewritten by every fetch; a fresh clone
+            // only has HEAD.
+            let git_dir = self.repo_cache_dir(&repo).join(MIRROR_DIR);
+            let fetched_at = ["FETCH_HEAD", "HEAD"].iter().find_map(|name| {
+                fs::metadata(git_dir.join(name))
+                    .and_then(|m| m.modified())
+                    .ok()
+            });
+            (commit, fetched_at)
+        } else {
+            (None, None)
+        };
+        Ok(SourceLocation::Git {
+            repo,
+            git_ref,
+            subdir: source.subdir.clone(),
+            checkout,
+            commit,
+            fetched_at,
+        })
+    }
+
+    /// `<cache>/repos/<hash of repo>`: one bare mirror plus a worktree per ref.
+    fn repo_cache_dir(&self, repo: &str) -> PathBuf {
+        self.cache_dir.join("repos").join(cache_key(repo))
+    }
+
+    fn repo_checkout_dir(&self, repo: &str, git_ref: &str) -> PathBuf {
+        self.repo_cache_dir(repo)
+            .join(WORKTREES_DIR)
+            .join(cache_key(git_ref))
+    }
+
     fn resolve_template_def(
         &self,
         cfg: &Config,
         template_name: &str,
         def: &TemplateDef,
+        ref_override: Option<&str>,
     ) -> Result<PathBuf, ResolveError> {
         let path = def.path();
         if path.is_absolute() {
+            if ref_override.is_some() {
+                return Err(ResolveError::NotGitTemplate(template_name.to_string()));
+            }
             debug!(template = template_name, path = %path.display(), "resolve absolute");
             return Ok(path.to_path_buf());
         }
@@ -183,6 +433,9 @@ impl TemplateResolver {
             .ok_or_else(|| ResolveError::UnknownSource(source_name.to_string()))?;
 
         if let Some(root) = &source.path {
+            if ref_override.is_some() {
+                return Err(ResolveError::NotGitTemplate(template_name.to_string()));
+            }
             debug!(template = template_name, source = source_name, root = %root.display(), path = %path.display(), "resolve local");
             return Ok(root.join(path));
         }
@@ -193,7 +446,7 @@ impl TemplateResolver {
             });
         };
         let repo = normalize_repo(repo, source.git_protocol.unwrap_or(GitProtocol::Ssh));
-        let git_ref = source.git_ref.as_deref().unwrap_or("HEAD");
+        let git_ref = ref_override.or(source.git_ref.as_deref()).unwrap_or("HEAD");
         debug!(template = template_name, source = source_name, repo = %repo, git_ref = %git_ref, "resolve git");
         let repo_root = self.ensure_repo_checkout(&repo, git_ref)?;
         let base = match &source.subdir {
@@ -205,44 +458,309 @@ impl TemplateResolver {
     }
 
     fn ensure_repo_checkout(&self, repo: &str, git_ref: &str) -> Result<PathBuf, ResolveError> {
-        let key = cache_key(repo, git_ref);
-        let repo_dir = self.cache_dir.join("repos").join(key).join("repo");
+        let mirror = self.ensure_mirror(repo)?;
+        // A ref the mirror lacks is the caller's mistake, not cache damage.
+        if git_stdout(&[
+            "-C",
+            mirror.to_string_lossy().as_ref(),
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{git_ref}^{{commit}}"),
+        ])
+        .is_err()
+        {
+            return Err(ResolveError::UnknownRef {
+                repo: repo.to_string(),
+                git_ref: git_ref.to_string(),
+            });
+        }
+        let worktree = self.repo_checkout_dir(repo, git_ref);
+
+        if worktree.exists() {
+            // Only a worktree git can no longer use is recreated from the mirror.
+            match checkout_problem(&worktree) {
+                None => {
+                    self.timed(Phase::Checkout, || git_checkout_detach(&worktree, git_ref))?;
+                    return Ok(worktree);
+                }
+                Some(problem) => {
+                    debug!(dest = %worktree.display(), problem = %problem, "recreating broken worktree");
+                    remove_worktree(&worktree)?;
+                }
+            }
+        }
 
-        if !repo_dir.exists() {
-            fs::create_dir_all(repo_dir.parent().unwrap()).map_err(|e| ResolveError::Io {
-                path: repo_dir.clone(),
-                source: e,
-            })?;
-            debug!(repo = %repo, dest = %repo_dir.display(), "git clone");
-            git(&["clone", repo, repo_dir.to_string_lossy().as_ref()], None)?;
-        } else {
-            // Best-effort update.
-            debug!(repo = %repo, dest = %repo_dir.display(), "git fetch");
-            let _ = git(
+        debug!(repo = %repo, git_ref = %git_ref, dest = %worktree.display(), "git worktree add");
+        self.timed(Phase::Checkout, || {
+            git(
                 &[
                     "-C",
-                    repo_dir.to_string_lossy().as_ref(),
-                    "fetch",
-                    "--tags",
-                    "--prune",
-                    "origin",
+                    mirror.to_string_lossy().as_ref(),
+                    "worktree",
+                    "add",
+                    "--detach",
+                    "--force",
+                    worktree.to_string_lossy().as_ref(),
+                    git_ref,
                 ],
                 None,
-            );
+            )
+        })?;
+        Ok(worktree)
+    }
+
+    /// Clone or update the bare mirror of `repo` that all of its worktrees share.
+    ///
+    /// A mirror git can no longer read (interrupted clone, disk trouble) is thrown
+    /// away, worktrees included, and cloned again once before giving up.
+    fn ensure_mirror(&self, repo: &str) -> Result<PathBuf, ResolveError> {
+        let repo_cache = self.repo_cache_dir(repo);
+        let mirror = repo_cache.join(MIRROR_DIR);
+
+        let legacy = repo_cache.join(LEGACY_CHECKOUT_DIR);
+        if legacy.exists() {
+            debug!(dest = %legacy.display(), "removing checkout from the old cache layout");
+            remove_dir(&legacy)?;
         }
 
-        // Check out the requested ref in a detached HEAD state. If `ref` is a branch name,
-        // try `origin/<ref>` as a fallback.
-        if git_checkout_detach(&repo_dir, git_ref).is_err()
-            && !git_ref.contains('/')
-            && !looks_like_hex(git_ref)
-        {
-            let origin_ref = format!("origin/{git_ref}");
-            git_checkout_detach(&repo_dir, &origin_ref)?;
+        if mirror.exists() {
+            match mirror_problem(&mirror) {
+                None => {
+                    // Best-effort update.
+                    debug!(repo = %repo, dest = %mirror.display(), "git fetch");
+                    let _ = self.timed(Phase::Fetch, || {
+                        git(
+                            &[
+                                "-C",
+                                mirror.to_string_lossy().as_ref(),
+                                "fetch",
+                                "--prune",
+                                "origin",
+                            ],
+                            None,
+                        )
+                    });
+                    return Ok(mirror);
+                }
+                Some(problem) => {
+                    debug!(dest = %mirror.display(), problem = %problem, "re-cloning broken cache mirror");
+                    remove_dir(&repo_cache)?;
+                }
+            }
+        }
+
+        fs::create_dir_all(&repo_cache).map_err(|e| ResolveError::Io {
+            path: repo_cache.clone(),
+            source: e,
+        })?;
+        // Clone beside the final path and rename into place, so a concurrent
+        // resolution never sees a half-written mirror.
+        let partial = repo_cache.join(format!("{MIRROR_DIR}.partial-{}", std::process::id()));
+        remove_dir(&partial)?;
+        debug!(repo = %repo, dest = %mirror.display(), "git clone --mirror");
+        self.timed(Phase::Fetch, || {
+            git(
+                &[
+                    "clone",
+                    "--mirror",
+                    repo,
+                    partial.to_string_lossy().as_ref(),
+                ],
+                None,
+            )
+        })?;
+        if let Err(e) = fs::rename(&partial, &mirror) {
+            remove_dir(&partial)?;
+            // Losing the race to another resolution is fine; its mirror is just as good.
+            if !mirror.exists() {
+                return Err(ResolveError::Io {
+                    path: mirror,
+                    source: e,
+                });
+            }
+        }
+        Ok(mirror)
+    }
+
+    /// Check every cached mirror and worktree without touching the network.
+    pub fn verify_cache(&self) -> Result<Vec<CachedCheckout>, ResolveError> {
+        let mut checkouts = Vec::new();
+        for repo_cache in read_dirs(&self.cache_dir.join("repos"))? {
+            let mirror = repo_cache.join(MIRROR_DIR);
+            let legacy = repo_cache.join(LEGACY_CHECKOUT_DIR);
+            if legacy.exists() {
+                let repo = git_stdout(&[
+                    "-C",
+                    legacy.to_string_lossy().as_ref(),
+                    "config",
+                    "--get",
+                    "remote.origin.url",
+                ])
+                .ok();
+                checkouts.push(CachedCheckout {
+                    dir: legacy,
+                    repo,
+                    problem: Some(
+                        "checkout from an older pinit cache layout; it is replaced by a mirror on next use"
+                            .to_string(),
+                    ),
+                });
+                if !mirror.exists() {
+                    continue;
+                }
+            }
+            let problem = mirror_problem(&mirror);
+            let repo = problem
+                .is_none()
+                .then(|| {
+                    git_stdout(&[
+                        "-C",
+                        mirror.to_string_lossy().as_ref(),
+                        "config",
+                        "--get",
+                        "remote.origin.url",
+                    ])
+                    .ok()
+                })
+                .flatten();
+            checkouts.push(CachedCheckout {
+                dir: mirror,
+                repo: repo.clone(),
+                problem,
+            });
+            for worktree in read_dirs(&repo_cache.join(WORKTREES_DIR))? {
+                let problem = checkout_problem(&worktree);
+                checkouts.push(CachedCheckout {
+                    dir: worktree,
+                    repo: repo.clone(),
+                    problem,
+                });
+            }
+        }
+        Ok(checkouts)
+    }
+}
+
+/// Bare mirror of a repo, under `<cache>/repos/<hash of repo>`.
+const MIRROR_DIR: &str = "mirror.git";
+/// One worktree per ref, under `<cache>/repos/<hash of repo>/worktrees/<hash of ref>`.
+const WORKTREES_DIR: &str = "worktrees";
+/// The single full clone per repo that caches used before mirrors and worktrees.
+const LEGACY_CHECKOUT_DIR: &str = "repo";
+
+/// One cached mirror or worktree, as reported by [`TemplateResolver::verify_cache`].
+#[derive(Clone, Debug)]
+pub struct CachedCheckout {
+    /// The bare mirror (`.../mirror.git`), a worktree checked out from it, or a
+    /// clone left over from the old `.../repo` layout.
+    pub dir: PathBuf,
+    /// The `origin` URL, when git can still read it.
+    pub repo: Option<String>,
+    /// Why git cannot use it; `None` when it is healthy.
+    pub problem: Option<String>,
+}
+
+impl CachedCheckout {
+    pub fn is_healthy(&self) -> bool {
+        self.problem.is_none()
+    }
+
+    pub fn is_mirror(&self) -> bool {
+        self.dir.file_name() == Some(OsStr::new(MIRROR_DIR))
+    }
+
+    /// Delete it; it is cloned or checked out again the next time it is needed.
+    /// Removing a mirror removes its worktrees too.
+    pub fn remove(&self) -> Result<(), ResolveError> {
+        if self.is_mirror() {
+            remove_dir(self.dir.parent().unwrap_or(&self.dir))
+        } else if self.dir.file_name() == Some(OsStr::new(LEGACY_CHECKOUT_DIR)) {
+            remove_dir(&self.dir)
+        } else {
+            remove_worktree(&self.dir)
+        }
+    }
+}
+
+/// Why git cannot use the bare mirror at `mirror`, if anything.
+fn mirror_problem(mirror: &Path) -> Option<String> {
+    // Without HEAD, git would look for a repository in the parent directories.
+    if !mirror.join("HEAD").is_file() {
+        return Some("not a git repository".to_string());
+    }
+    match git_stdout(&[
+        "-C",
+        mirror.to_string_lossy().as_ref(),
+        "rev-parse",
+        "--is-bare-repository",
+    ]) {
+        Ok(out) if out == "true" => None,
+        Ok(_) => Some("not a bare repository".to_string()),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Why git cannot use the worktree at `worktree`, if anything.
+fn checkout_problem(worktree: &Path) -> Option<String> {
+    if !worktree.join(".git").exists() {
+        return Some("not a git checkout".to_string());
+    }
+    let dir = worktree.to_string_lossy();
+    [
+        &["-C", dir.as_ref(), "status", "--porcelain"][..],
+        &["-C", dir.as_ref(), "rev-parse", "--verify", "HEAD^{commit}"][..],
+    ]
+    .iter()
+    .find_map(|args| git_stdout(args).err().map(|e| e.to_string()))
+}
+
+/// Remove a worktree and let its mirror forget about it.
+fn remove_worktree(worktree: &Path) -> Result<(), ResolveError> {
+    remove_dir(worktree)?;
+    if let Some(repo_cache) = worktree.parent().and_then(Path::parent) {
+        let mirror = repo_cache.join(MIRROR_DIR);
+        if mirror_problem(&mirror).is_none() {
+            let _ = git(
+                &["-C", mirror.to_string_lossy().as_ref(), "worktree", "prune"],
+                None,
+            );
         }
+    }
+    Ok(())
+}
 
-        Ok(repo_dir)
+/// `fs::remove_dir_all`, treating a missing directory as already removed.
+fn remove_dir(dir: &Path) -> Result<(), ResolveError> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(ResolveError::Io {
+            path: dir.to_path_buf(),
+            source: e,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Subdirectories of `dir`, sorted; none when `dir` does not exist.
+fn read_dirs(dir: &Path) -> Result<Vec<PathBuf>, ResolveError> {
+    let io_err = |e| ResolveError::Io {
+        path: dir.to_path_buf(),
+        source: e,
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_err(e)),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(io_err)?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
     }
+    dirs.sort();
+    Ok(dirs)
 }
 
 fn git_checkout_detach(repo_dir: &Path, git_ref: &str) -> Result<(), ResolveError> {
@@ -259,20 +777,8 @@ fn git_checkout_detach(repo_dir: &Path, git_ref: &str) -> Result<(), ResolveErro
     )
 }
 
-fn cache_key(repo: &str, git_ref: &str) -> String {
-    let mut hasher = blake3::Hasher::new();
-    hasher.update(repo.as_bytes());
-    hasher.update(b"\n");
-    hasher.update(git_ref.as_bytes());
-    let digest = hasher.finalize();
-    digest.to_hex().to_string()
-}
-
-fn looks_like_hex(s: &str) -> bool {
-    if s.len() < 7 {
-        return false;
-    }
-    s.bytes().all(|b| b.is_ascii_hexdigit())
+fn cache_key(key: &str) -> String {
+    blake3::hash(key.as_bytes()).to_hex().to_string()
 }
 
 fn git(args: &[&str], cwd: Option<&Path>) -> Result<(), ResolveError> {
@@ -298,6 +804,25 @@ fn git(args: &[&str], cwd: Option<&Path>) -> Result<(), ResolveError> {
     })
 }
 
+fn git_stdout(args: &[&str]) -> Result<String, ResolveError> {
+    debug!(cmd = %format!("git {}", args.join(" ")), "run");
+    let out = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| ResolveError::Io {
+            path: PathBuf::from("git"),
+            source: e,
+        })?;
+    if !out.status.success() {
+        return Err(ResolveError::GitCommandFailed {
+            cmd: format!("git {}", args.join(" ")),
+            status: out.status.code().unwrap_or(1),
+            stderr: String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
 fn ensure_is_dir(path: &Path) -> Result<(), ResolveError> {
     let meta = fs::symlink_metadata(path).map_err(|e| {
         if e.kind() == io::ErrorKind::NotFound {
@@ -373,14 +898,6 @@ pub fn path_is_git_dir(path: &Path) -> bool {
 mod tests {
     use super::*;
 
-    #[test]
-    fn looks_like_hex_requires_min_len_and_hex_chars() {
-        assert!(!looks_like_hex("abc"));
-        assert!(!looks_like_hex("zzzzzzz"));
-        assert!(looks_like_hex("0123456"));
-        assert!(looks_like_hex("deadBEEF"));
-    }
-
     #[test]
     fn path_is_git_dir_matches_dot_git_dir() {
         let tmp = std::env::temp_dir().join(format!("pinit-path-is-git-{}", std::process::id()));
@@ -395,10 +912,10 @@ mod tests {
 
     #[test]
     fn cache_key_is_stable() {
-        let a = cache_key("repo", "ref");
-        let b = cache_key("repo", "ref");
+        let a = cache_key("repo");
+        let b = cache_key("repo");
         assert_eq!(a, b);
-        assert_ne!(a, cache_key("repo", "ref2"));
+        assert_ne!(a, cache_key("repo2"));
     }
 
     #[test]
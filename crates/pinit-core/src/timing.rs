@@ -0,0 +1,120 @@
+#![forbid(unsafe_code)]
+
+//! Wall-clock timings for the phases of a run, for `--timings` and JSON reports.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A phase of resolving and applying templates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Loading config and resolving names into a template stack.
+    Resolve,
+    /// Cloning and fetching git sources.
+    Fetch,
+    /// Checking out the requested refs of git sources.
+    Checkout,
+    /// Walking template directories and writing files, merges excluded.
+    Walk,
+    /// Running merge drivers on existing files.
+    Merge,
+    /// Running hook commands.
+    Hooks,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 6] = [
+        Phase::Resolve,
+        Phase::Fetch,
+        Phase::Checkout,
+        Phase::Walk,
+        Phase::Merge,
+        Phase::Hooks,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Resolve => "resolve",
+            Phase::Fetch => "fetch",
+            Phase::Checkout => "checkout",
+            Phase::Walk => "walk",
+            Phase::Merge => "merge",
+            Phase::Hooks => "hooks",
+        }
+    }
+}
+
+/// Time spent in each [`Phase`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    totals: [Duration; Phase::ALL.len()],
+}
+
+impl Timings {
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.totals[phase as usize]
+    }
+
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        self.totals[phase as usize] += elapsed;
+    }
+
+    /// Run `f`, adding its wall-clock time to `phase`.
+    pub fn time<T>(&mut self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let out = f();
+        self.add(phase, start.elapsed());
+        out
+    }
+
+    /// Add every phase of `other` to this one.
+    pub fn absorb(&mut self, other: &Timings) {
+        for phase in Phase::ALL {
+            self.add(phase, other.get(phase));
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.totals.iter().sum()
+    }
+
+    /// Phases in run order with their times.
+    pub fn iter(&self) -> impl Iterator<Item = (Phase, Duration)> + '_ {
+        Phase::ALL.into_iter().map(|phase| (phase, self.get(phase)))
+    }
+}
+
+/// `resolve 1.2ms, fetch 0ns, ... (total 4.5ms)`.
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (phase, elapsed)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {elapsed:.1?}", phase.as_str())?;
+        }
+        write!(f, " (total {:.1?})", self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_accumulate_and_display_in_order() {
+        let mut timings = Timings::default();
+        timings.add(Phase::Merge, Duration::from_millis(2));
+        let mut other = Timings::default();
+        other.add(Phase::Merge, Duration::from_millis(3));
+        other.add(Phase::Fetch, Duration::from_secs(1));
+        timings.absorb(&other);
+
+        assert_eq!(timings.get(Phase::Merge), Duration::from_millis(5));
+        assert_eq!(timings.total(), Duration::from_millis(1005));
+        assert_eq!(
+            timings.to_string(),
+            "resolve 0.0ns, fetch 1.0s, checkout 0.0ns, walk 0.0ns, merge 5.0ms, hooks 0.0ns (total 1.0s)"
+        );
+    }
+}
@@ -0,0 +1,360 @@
+#![forbid(unsafe_code)]
+
+//! Per-file transform pipeline.
+//!
+//! Every template file passes through an ordered list of steps on its way to
+//! the destination. The built-in order is variable rendering, path remapping,
+//! then any configured steps (line-ending normalization, mode changes).
+//!
+//! A step can rewrite the destination path, the file contents, or both:
+//! [`FileTransform::path`] runs during the template walk, before ignore checks,
+//! so ignore rules and override patterns see the final path;
+//! [`FileTransform::apply`] runs when the file is written.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::{EolStyle, TransformDef};
+use crate::glob::glob_match;
+use crate::vars::{self, Variables};
+
+/// A file on its way to the destination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileData {
+    /// Destination-relative path (already remapped).
+    pub rel: PathBuf,
+    pub contents: Vec<u8>,
+    /// Unix permission bits to set after writing; `None` keeps the template's mode.
+    pub mode: Option<u32>,
+}
+
+/// One step of the transform pipeline.
+pub trait FileTransform: fmt::Debug + Send + Sync {
+    /// Short name used in traces and errors.
+    fn name(&self) -> &str;
+
+    /// Rewrite a destination-relative path. Called for directories as well as files.
+    fn path(&self, rel: PathBuf) -> PathBuf {
+        rel
+    }
+
+    /// Whether [`FileTransform::apply`] does anything; path-only steps return `false`
+    /// so files can be copied without being read.
+    fn edits_files(&self) -> bool {
+        true
+    }
+
+    /// Rewrite the file contents or mode.
+    fn apply(&self, file: &mut FileData) -> Result<(), String> {
+        let _ = file;
+        Ok(())
+    }
+}
+
+/// Ordered list of transform steps.
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    steps: Vec<Arc<dyn FileTransform>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a step; steps run in the order they were added.
+    pub fn push(&mut self, step: impl FileTransform + 'static) {
+        self.steps.push(Arc::new(step));
+    }
+
+    /// Append every step of `other`.
+    pub fn extend(&mut self, other: &Pipeline) {
+        self.steps.extend(other.steps.iter().cloned());
+    }
+
+    /// Build the configured steps from `transforms` config entries.
+    pub fn from_defs(defs: &[TransformDef]) -> Self {
+        let mut pipeline = Self::new();
+        for def in defs {
+            match def {
+                TransformDef::Eol { eol, pattern } => pipeline.push(NormalizeEol {
+                    eol: *eol,
+                    pattern: pattern.clone(),
+                }),
+                TransformDef::Mode { mode, pattern } => {
+                    // Validated at config load; an unparsable mode is a no-op here.
+                    if let Some(mode) = parse_mode(mode) {
+                        pipeline.push(SetMode {
+                            mode,
+                            pattern: pattern.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        pipeline
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn edits_files(&self) -> bool {
+        self.steps.iter().any(|s| s.edits_files())
+    }
+
+    /// Run every step's path rewrite over `rel`.
+    pub fn map_path(&self, rel: PathBuf) -> PathBuf {
+        self.steps.iter().fold(rel, |rel, step| step.path(rel))
+    }
+
+    /// Run every step's content rewrite; the error names the failing step.
+    pub fn apply(&self, file: &mut FileData) -> Result<(), (String, String)> {
+        for step in &self.steps {
+            step.apply(file)
+                .map_err(|message| (step.name().to_string(), message))?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace `{{ name }}` references in text files.
+#[derive(Clone, Debug)]
+pub struct RenderVariables(pub Variables);
+
+impl FileTransform for RenderVariables {
+    fn name(&self) -> &str {
+        "render"
+    }
+
+    fn apply(&self, file: &mut FileData) -> Result<(), String> {
+        if let Some(rendered) = vars::render_bytes(&file.contents, &self.0) {
+            file.contents = rendered;
+        }
+        Ok(())
+    }
+}
+
+/// Rename paths through a `map` table, then place them under `dest_prefix`.
+#[derive(Clone, Debug, Default)]
+pub struct RemapPaths {
+    pub map: BTreeMap<PathBuf, PathBuf>,
+    pub dest_prefix: Option<PathBuf>,
+}
+
+impl FileTransform for RemapPaths {
+    fn name(&self) -> &str {
+        "remap"
+    }
+
+    fn path(&self, rel: PathBuf) -> PathBuf {
+        let rel = map_path(&self.map, &rel).unwrap_or(rel);
+        match &self.dest_prefix {
+            Some(prefix) => prefix.join(rel),
+            None => rel,
+        }
+    }
+
+    fn edits_files(&self) -> bool {
+        false
+    }
+}
+
+/// Convert line endings in text files; binary files are left alone.
+#[derive(Clone, Debug)]
+pub struct NormalizeEol {
+    pub eol: EolStyle,
+    /// Only files matching this glob; `None` means every text file.
+    pub pattern: Option<String>,
+}
+
+impl FileTransform for NormalizeEol {
+    fn name(&self) -> &str {
+        "eol"
+    }
+
+    fn apply(&self, file: &mut FileData) -> Result<(), String> {
+        if !matches_pattern(self.pattern.as_deref(), &file.rel) || crate::is_binary(&file.contents)
+        {
+            return Ok(());
+        }
+        let mut lf = Vec::with_capacity(file.contents.len());
+        let mut bytes = file.contents.iter().peekable();
+        while let Some(&b) = bytes.next() {
+            if b == b'\r' && bytes.peek() == Some(&&b'\n') {
+                continue;
+            }
+            lf.push(b);
+        }
+        file.contents = match self.eol {
+            EolStyle::Lf => lf,
+            EolStyle::Crlf => {
+                let mut out = Vec::with_capacity(lf.len() + lf.len() / 32);
+                for b in lf {
+                    if b == b'\n' {
+                        out.push(b'\r');
+                    }
+                    out.push(b);
+                }
+                out
+            }
+        };
+        Ok(())
+    }
+}
+
+/// Set permission bits on files matching a glob.
+#[derive(Clone, Debug)]
+pub struct SetMode {
+    pub mode: u32,
+    pub pattern: String,
+}
+
+impl FileTransform for SetMode {
+    fn name(&self) -> &str {
+        "mode"
+    }
+
+    fn apply(&self, file: &mut FileData) -> Result<(), String> {
+        if matches_pattern(Some(&self.pattern), &file.rel) {
+            file.mode = Some(self.mode);
+        }
+        Ok(())
+    }
+}
+
+/// Parse an octal mode string such as `"755"` or `"0644"`.
+pub fn parse_mode(mode: &str) -> Option<u32> {
+    let mode = mode.trim();
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    if digits.is_empty() {
+        return None;
+    }
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|bits| *bits <= 0o7777)
+}
+
+/// Destination path for `rel` under a `map` table, if an entry covers it.
+///
+/// An exact key wins; otherwise the deepest key that is a parent of `rel`
+/// carries the rest of the path along.
+pub(crate) fn map_path(map: &BTreeMap<PathBuf, PathBuf>, rel: &Path) -> Option<PathBuf> {
+    if let Some(to) = map.get(rel) {
+        return Some(to.clone());
+    }
+    map.iter()
+        .filter(|(from, _)| rel.starts_with(from))
+        .max_by_key(|(from, _)| from.components().count())
+        .and_then(|(from, to)| rel.strip_prefix(from).ok().map(|rest| to.join(rest)))
+}
+
+fn matches_pattern(pattern: Option<&str>, rel: &Path) -> bool {
+    match pattern {
+        Some(pattern) => glob_match(pattern, &rel.to_string_lossy().replace('\\', "/")),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(rel: &str, contents: &str) -> FileData {
+        FileData {
+            rel: PathBuf::from(rel),
+            contents: contents.as_bytes().to_vec(),
+            mode: None,
+        }
+    }
+
+    #[test]
+    fn map_path_prefers_exact_then_deepest_dir() {
+        let map: BTreeMap<PathBuf, PathBuf> = [
+            ("ci/github.yml", ".github/workflows/ci.yml"),
+            ("ci", ".ci"),
+            ("ci/scripts/", "scripts"),
+        ]
+        .into_iter()
+        .map(|(a, b)| (PathBuf::from(a), PathBuf::from(b)))
+        .collect();
+        let mapped = |p: &str| map_path(&map, Path::new(p));
+        assert_eq!(
+            mapped("ci/github.yml"),
+            Some(PathBuf::from(".github/workflows/ci.yml"))
+        );
+        assert_eq!(mapped("ci/other.yml"), Some(PathBuf::from(".ci/other.yml")));
+        assert_eq!(
+            mapped("ci/scripts/x.sh"),
+            Some(PathBuf::from("scripts/x.sh"))
+        );
+        assert_eq!(mapped("ci"), Some(PathBuf::from(".ci")));
+        assert_eq!(mapped("cinema.txt"), None);
+    }
+
+    #[test]
+    fn steps_run_in_order() {
+        let mut pipeline = Pipeline::new();
+        pipeline.push(RenderVariables(vars::project_variables("demo")));
+        pipeline.push(RemapPaths {
+            map: BTreeMap::new(),
+            dest_prefix: Some(PathBuf::from("svc")),
+        });
+        pipeline.push(NormalizeEol {
+            eol: EolStyle::Crlf,
+            pattern: Some("**/*.bat".to_string()),
+        });
+        pipeline.push(SetMode {
+            mode: 0o755,
+            pattern: "svc/*.bat".to_string(),
+        });
+        assert_eq!(pipeline.step_names(), ["render", "remap", "eol", "mode"]);
+
+        let rel = pipeline.map_path(PathBuf::from("run.bat"));
+        assert_eq!(rel, PathBuf::from("svc/run.bat"));
+        let mut data = file("svc/run.bat", "echo {{ package_name }}\r\nexit\n");
+        pipeline.apply(&mut data).unwrap();
+        assert_eq!(data.contents, b"echo demo\r\nexit\r\n");
+        assert_eq!(data.mode, Some(0o755));
+
+        let mut other = file("svc/notes.txt", "a\r\nb\n");
+        pipeline.apply(&mut other).unwrap();
+        assert_eq!(other.contents, b"a\r\nb\n");
+        assert_eq!(other.mode, None);
+    }
+
+    #[test]
+    fn eol_lf_leaves_binary_files_alone() {
+        let step = NormalizeEol {
+            eol: EolStyle::Lf,
+            pattern: None,
+        };
+        let mut text = file("a.txt", "a\r\nb\r\n");
+        step.apply(&mut text).unwrap();
+        assert_eq!(text.contents, b"a\nb\n");
+
+        let mut binary = FileData {
+            rel: PathBuf::from("a.bin"),
+            contents: b"\0\r\n".to_vec(),
+            mode: None,
+        };
+        step.apply(&mut binary).unwrap();
+        assert_eq!(binary.contents, b"\0\r\n");
+    }
+
+    #[test]
+    fn parses_octal_modes() {
+        assert_eq!(parse_mode("755"), Some(0o755));
+        assert_eq!(parse_mode("0644"), Some(0o644));
+        assert_eq!(parse_mode("0o600"), Some(0o600));
+        assert_eq!(parse_mode("789"), None);
+        assert_eq!(parse_mode("17777"), None);
+        assert_eq!(parse_mode(""), None);
+    }
+}
@@ -0,0 +1,126 @@
+#![forbid(unsafe_code)]
+
+//! Template variables.
+//!
+//! Text files may reference variables as `{{ name }}`. Only names present in the
+//! variable map are replaced; everything else (including GitHub Actions
+//! expressions such as `${{ github.ref }}`) is left untouched.
+
+use std::collections::BTreeMap;
+
+/// Variable name to value.
+pub type Variables = BTreeMap<String, String>;
+
+/// Derive the standard project variables from a project name.
+///
+/// Sets `project_name` as given, plus `crate_name` (snake_case) and
+/// `package_name` (kebab-case), both lowercased.
+pub fn project_variables(project_name: &str) -> Variables {
+    let mut vars = Variables::new();
+    vars.insert("project_name".to_string(), project_name.to_string());
+    vars.insert("crate_name".to_string(), normalize_name(project_name, '_'));
+    vars.insert(
+        "package_name".to_string(),
+        normalize_name(project_name, '-'),
+    );
+    vars
+}
+
+fn normalize_name(name: &str, sep: char) -> String {
+    let mut out = String::with_capacity(name.len());
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with(sep) {
+            out.push(sep);
+        }
+    }
+    while out.ends_with(sep) {
+        out.pop();
+    }
+    out
+}
+
+/// Replace known `{{ name }}` references in `text`.
+///
+/// Returns `None` when nothing was replaced so callers can keep the original
+/// bytes (and skip an extra allocation).
+pub fn render(text: &str, vars: &Variables) -> Option<String> {
+    if vars.is_empty() || !text.contains("{{") {
+        return None;
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut idx = 0usize;
+    let mut replaced = false;
+    while let Some(open_rel) = text[idx..].find("{{") {
+        let open = idx + open_rel;
+        let Some(close_rel) = text[open + 2..].find("}}") else {
+            break;
+        };
+        let close = open + 2 + close_rel;
+        let key = text[open + 2..close].trim();
+        let escaped = text[..open].ends_with('$');
+
+        out.push_str(&text[idx..open]);
+        match vars.get(key) {
+            Some(value) if !escaped => {
+                out.push_str(value);
+                replaced = true;
+            }
+            _ => out.push_str(&text[open..close + 2]),
+        }
+        idx = close + 2;
+    }
+
+    if !replaced {
+        return None;
+    }
+    out.push_str(&text[idx..]);
+    Some(out)
+}
+
+/// Byte-oriented [`render`]; non-UTF-8 content is never rewritten.
+pub fn render_bytes(bytes: &[u8], vars: &Variables) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    render(text, vars).map(String::into_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> Variables {
+        project_variables("My-Cool_Project")
+    }
+
+    #[test]
+    fn derives_crate_and_package_names() {
+        let vars = vars();
+        assert_eq!(vars["project_name"], "My-Cool_Project");
+        assert_eq!(vars["crate_name"], "my_cool_project");
+        assert_eq!(vars["package_name"], "my-cool-project");
+        assert_eq!(project_variables("  x..y  ")["package_name"], "x-y");
+    }
+
+    #[test]
+    fn replaces_known_names_only() {
+        let out = render(
+            "name = \"{{ crate_name }}\"\n# {{unknown}} {{package_name}}\n",
+            &vars(),
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "name = \"my_cool_project\"\n# {{unknown}} my-cool-project\n"
+        );
+    }
+
+    #[test]
+    fn leaves_github_expressions_and_plain_text_alone() {
+        assert_eq!(render("ref: ${{ project_name }}", &vars()), None);
+        assert_eq!(render("no braces here", &vars()), None);
+        assert_eq!(render("{{ unterminated", &vars()), None);
+        assert_eq!(render_bytes(&[0xff, b'{', b'{'], &vars()), None);
+    }
+}
@@ -0,0 +1,126 @@
+#![forbid(unsafe_code)]
+
+//! Minimum pinit version requirements (`requires_pinit`).
+//!
+//! Configs and template manifests may declare the pinit versions they need,
+//! e.g. `requires_pinit = ">=0.5"`. A requirement is a comma-separated list of
+//! comparisons (`>=`, `>`, `<=`, `<`, `=`); a bare version means `>=`. Missing
+//! version parts count as zero, so `>=0.5` is `>=0.5.0`.
+
+use std::fmt;
+
+/// Version of this pinit build.
+pub const PINIT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Why a `requires_pinit` value was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequirementError {
+    /// The value is not a version requirement.
+    Invalid(String),
+    /// This build does not satisfy the requirement.
+    Unsatisfied { required: String },
+}
+
+impl fmt::Display for RequirementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequirementError::Invalid(raw) => write!(
+                f,
+                "requires_pinit must be a version requirement such as \">=0.5\" (got {raw:?})"
+            ),
+            RequirementError::Unsatisfied { required } => write!(
+                f,
+                "requires pinit {required}, but this is pinit {PINIT_VERSION}; upgrade pinit to use it"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RequirementError {}
+
+/// Check `required` against this build's version.
+pub fn check_pinit_version(required: &str) -> Result<(), RequirementError> {
+    let current = parse_version(PINIT_VERSION.split(['-', '+']).next().unwrap_or_default())
+        .ok_or_else(|| RequirementError::Invalid(PINIT_VERSION.to_string()))?;
+    if satisfies(required, current)? {
+        Ok(())
+    } else {
+        Err(RequirementError::Unsatisfied {
+            required: required.trim().to_string(),
+        })
+    }
+}
+
+/// Whether `version` meets every comparison in `required`.
+pub fn satisfies(required: &str, version: (u64, u64, u64)) -> Result<bool, RequirementError> {
+    let invalid = || RequirementError::Invalid(required.to_string());
+    if required.trim().is_empty() {
+        return Err(invalid());
+    }
+    let mut ok = true;
+    for part in required.split(',') {
+        let part = part.trim();
+        let (op, rest) = [">=", "<=", ">", "<", "="]
+            .iter()
+            .find_map(|op| part.strip_prefix(op).map(|rest| (*op, rest)))
+            .unwrap_or((">=", part));
+        let bound = parse_version(rest.trim().trim_start_matches('v')).ok_or_else(invalid)?;
+        ok &= match op {
+            ">=" => version >= bound,
+            "<=" => version <= bound,
+            ">" => version > bound,
+            "<" => version < bound,
+            _ => version == bound,
+        };
+    }
+    Ok(ok)
+}
+
+/// `1`, `1.2`, or `1.2.3`; missing parts are zero.
+fn parse_version(s: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = s.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparisons_and_bare_versions() {
+        let v = (0, 5, 2);
+        assert_eq!(satisfies(">=0.5", v), Ok(true));
+        assert_eq!(satisfies("0.6", v), Ok(false));
+        assert_eq!(satisfies(">0.5.2", v), Ok(false));
+        assert_eq!(satisfies(">=0.4, <0.6", v), Ok(true));
+        assert_eq!(satisfies("=0.5.2", v), Ok(true));
+        assert_eq!(satisfies("<=v0.5.1", v), Ok(false));
+    }
+
+    #[test]
+    fn rejects_malformed_requirements() {
+        for raw in ["", ">=", "~0.5", ">=0.5.x", "0.5.1.2", ">=0.5,"] {
+            assert_eq!(
+                satisfies(raw, (0, 5, 0)),
+                Err(RequirementError::Invalid(raw.to_string())),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn this_build_meets_its_own_version() {
+        check_pinit_version(&format!(">={PINIT_VERSION}")).unwrap();
+        let err = check_pinit_version(">=999").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("requires pinit >=999, but this is pinit")
+        );
+    }
+}
@@ -21,6 +21,18 @@ fn covers_display_and_small_helpers() {
     let e = pinit_core::ApplyError::SymlinkNotSupported(PathBuf::from("x"));
     assert!(e.to_string().contains("symlinks are not supported"));
 
+    let e = pinit_core::ApplyError::MaxDepthExceeded {
+        path: PathBuf::from("x"),
+        max_depth: 3,
+    };
+    assert!(e.to_string().contains("deeper than 3 directories"));
+
+    let e = pinit_core::ApplyError::MaxFilesExceeded {
+        template_dir: PathBuf::from("x"),
+        max_files: 10,
+    };
+    assert!(e.to_string().contains("more than 10 files"));
+
     let e = pinit_core::ApplyError::GitIgnoreFailed {
         cmd: "git".into(),
         status: 128,
@@ -28,6 +40,13 @@ fn covers_display_and_small_helpers() {
     };
     assert!(e.to_string().contains("git ignore check failed"));
 
+    let e = pinit_core::ApplyError::TransformFailed {
+        path: PathBuf::from("x"),
+        step: "eol".into(),
+        message: "bad".into(),
+    };
+    assert_eq!(e.to_string(), "eol transform failed for x: bad");
+
     let io = std::io::Error::other("boom");
     let e = pinit_core::ApplyError::Io {
         path: PathBuf::from("x"),
@@ -160,3 +160,86 @@ fn apply_template_dir_errors_when_template_is_symlink() {
         pinit_core::ApplyError::SymlinkNotSupported(_)
     ));
 }
+
+#[test]
+fn apply_template_dir_errors_when_max_depth_exceeded_before_writing() {
+    let root = make_temp_root();
+    let template = root.join("template");
+    let dest = root.join("dest");
+    fs::create_dir_all(template.join("a/b/c")).unwrap();
+    fs::write(template.join("top.txt"), "top\n").unwrap();
+    fs::write(template.join("a/b/c/deep.txt"), "deep\n").unwrap();
+
+    let err = pinit_core::apply_template_dir(
+        &template,
+        &dest,
+        pinit_core::ApplyOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap_err();
+
+    match err {
+        pinit_core::ApplyError::MaxDepthExceeded { path, max_depth } => {
+            assert_eq!(max_depth, 2);
+            assert_eq!(path, template.join("a/b/c"));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert!(!dest.join("top.txt").exists());
+}
+
+#[test]
+fn apply_template_dir_errors_when_max_files_exceeded_before_writing() {
+    let root = make_temp_root();
+    let template = root.join("template");
+    let dest = root.join("dest");
+    fs::create_dir_all(template.join("sub")).unwrap();
+    fs::write(template.join("a.txt"), "a\n").unwrap();
+    fs::write(template.join("b.txt"), "b\n").unwrap();
+    fs::write(template.join("sub/c.txt"), "c\n").unwrap();
+
+    let err = pinit_core::apply_template_dir(
+        &template,
+        &dest,
+        pinit_core::ApplyOptions {
+            max_files: Some(2),
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        err,
+        pinit_core::ApplyError::MaxFilesExceeded { max_files: 2, .. }
+    ));
+    assert!(err.to_string().contains("more than 2 files"));
+    assert!(!dest.join("a.txt").exists());
+}
+
+#[test]
+fn apply_template_dir_within_limits_succeeds() {
+    let root = make_temp_root();
+    let template = root.join("template");
+    let dest = root.join("dest");
+    fs::create_dir_all(template.join("sub")).unwrap();
+    fs::write(template.join("a.txt"), "a\n").unwrap();
+    fs::write(template.join("sub/b.txt"), "b\n").unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template,
+        &dest,
+        pinit_core::ApplyOptions {
+            max_depth: Some(1),
+            max_files: Some(2),
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+
+    assert_eq!(report.created_files, 2);
+}
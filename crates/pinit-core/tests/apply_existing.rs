@@ -105,3 +105,140 @@ fn existing_env_merge_adds_missing_keys_only() {
     assert!(out.contains("B=template\n"));
     assert!(!out.contains("A=template\n"));
 }
+
+struct RecordingDecider(Vec<(PathBuf, bool, i64)>);
+
+impl ExistingFileDecider for RecordingDecider {
+    fn decide(&mut self, ctx: ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        self.0
+            .push((ctx.rel_path.to_path_buf(), ctx.is_binary, ctx.size_delta));
+        ExistingFileAction::Skip
+    }
+}
+
+#[test]
+fn decision_context_reports_binary_and_size_delta() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(&template_dir).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+
+    fs::write(template_dir.join("logo.png"), b"\x89PNG\0\0new-bytes").unwrap();
+    fs::write(dest_dir.join("logo.png"), b"\x89PNG\0old").unwrap();
+    fs::write(template_dir.join("notes.txt"), "short\n").unwrap();
+    fs::write(dest_dir.join("notes.txt"), "a longer line\n").unwrap();
+
+    let mut decider = RecordingDecider(Vec::new());
+    pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+
+    decider.0.sort();
+    assert_eq!(
+        decider.0,
+        vec![
+            (PathBuf::from("logo.png"), true, 7),
+            (PathBuf::from("notes.txt"), false, -8),
+        ]
+    );
+    assert!(pinit_core::is_binary(b"a\0b"));
+    assert!(!pinit_core::is_binary("héllo".as_bytes()));
+}
+
+struct AttrDecider(Vec<(PathBuf, Option<pinit_core::MergeAttribute>, Option<Vec<u8>>)>);
+
+impl ExistingFileDecider for AttrDecider {
+    fn decide(&mut self, ctx: ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        self.0.push((
+            ctx.rel_path.to_path_buf(),
+            ctx.merge_attr.cloned(),
+            ctx.merge_bytes.map(<[u8]>::to_vec),
+        ));
+        ExistingFileAction::Skip
+    }
+}
+
+#[test]
+fn decision_context_carries_gitattributes_merge_driver() {
+    use pinit_core::MergeAttribute;
+
+    if std::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(&template_dir).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    assert!(
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .arg(&dest_dir)
+            .status()
+            .unwrap()
+            .success()
+    );
+    fs::write(
+        dest_dir.join(".gitattributes"),
+        "*.lock merge=ours\nCHANGES merge=union\n*.dat -merge\nlocal.cfg merge=custom\n",
+    )
+    .unwrap();
+
+    for (name, dest, src) in [
+        ("Cargo.lock", "old\n", "new\n"),
+        ("CHANGES", "a\n", "b\n"),
+        ("blob.dat", "x=1\n", "x=2\n"),
+        ("local.cfg", "k\n", "v\n"),
+        ("plain.txt", "1\n", "2\n"),
+    ] {
+        fs::write(dest_dir.join(name), dest).unwrap();
+        fs::write(template_dir.join(name), src).unwrap();
+    }
+
+    let mut decider = AttrDecider(Vec::new());
+    pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+
+    decider.0.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        decider.0,
+        vec![
+            (
+                PathBuf::from("CHANGES"),
+                Some(MergeAttribute::Union),
+                Some(b"a\nb\n".to_vec())
+            ),
+            (
+                PathBuf::from("Cargo.lock"),
+                Some(MergeAttribute::Ours),
+                Some(b"old\nnew\n".to_vec())
+            ),
+            (
+                PathBuf::from("blob.dat"),
+                Some(MergeAttribute::Binary),
+                None
+            ),
+            (
+                PathBuf::from("local.cfg"),
+                Some(MergeAttribute::Driver("custom".to_string())),
+                Some(b"k\nv\n".to_vec())
+            ),
+            (PathBuf::from("plain.txt"), None, Some(b"1\n2\n".to_vec())),
+        ]
+    );
+}
@@ -120,6 +120,39 @@ fn apply_generated_merge_or_skip_does_not_write() {
     assert_eq!(fs::read_to_string(dest.join("LICENSE")).unwrap(), "old\n");
 }
 
+#[test]
+fn merge_generated_file_merges_ignore_patterns() {
+    let root = make_temp_root();
+    let dest = root.join("dest");
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(dest.join(".gitignore"), "/target/\n").unwrap();
+
+    let mut decider = FixedDecider(ExistingFileAction::Merge);
+    let r = pinit_core::merge_generated_file(
+        &dest,
+        ".gitignore",
+        b"# rust\n/target/\n\n# macos\n.DS_Store\n",
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+    assert_eq!(r.updated_files, 1);
+    assert_eq!(
+        fs::read_to_string(dest.join(".gitignore")).unwrap(),
+        "/target/\n\n# macos\n.DS_Store\n"
+    );
+
+    let r = pinit_core::merge_generated_file(
+        &dest,
+        ".gitignore",
+        b"# macos\n.DS_Store\n",
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+    assert_eq!(r.skipped_files, 1);
+}
+
 #[test]
 fn apply_generated_respects_always_ignore() {
     let root = make_temp_root();
@@ -276,3 +309,56 @@ fn apply_generated_errors_when_dest_is_not_dir() {
         other => panic!("unexpected error: {other:?}"),
     }
 }
+
+#[test]
+fn apply_license_file_reports_the_existing_license() {
+    struct Recording(Vec<Option<pinit_core::LicenseConflict>>);
+
+    impl ExistingFileDecider for Recording {
+        fn decide(&mut self, ctx: ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+            self.0.push(ctx.license.cloned());
+            ExistingFileAction::Skip
+        }
+    }
+
+    let root = make_temp_root();
+    let dest = root.join("dest");
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(
+        dest.join("LICENSE"),
+        "Copyright 2020 Jo\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\n",
+    )
+    .unwrap();
+    fs::write(dest.join("NOTICE"), "old\n").unwrap();
+
+    let mut decider = Recording(Vec::new());
+    let report = pinit_core::apply_license_file(
+        &dest,
+        "LICENSE",
+        b"Apache License\nVersion 2.0\n",
+        "Apache-2.0",
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+    assert_eq!(report.skipped_files, 1);
+    pinit_core::apply_generated_file(
+        &dest,
+        "NOTICE",
+        b"new\n",
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+
+    assert_eq!(
+        decider.0,
+        vec![
+            Some(pinit_core::LicenseConflict {
+                spdx: "Apache-2.0".to_string(),
+                existing_spdx: Some("MIT".to_string()),
+            }),
+            None,
+        ]
+    );
+}
@@ -108,3 +108,321 @@ fn honors_destination_gitignore() {
     assert!(!dest_dir.join("ignored.txt").exists());
     assert!(!dest_dir.join("ignored-dir/file.txt").exists());
 }
+
+fn git_init(dir: &Path) {
+    assert!(
+        Command::new("git")
+            .arg("init")
+            .arg("-q")
+            .arg(dir)
+            .output()
+            .unwrap()
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn honors_destination_info_exclude() {
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+
+    fs::create_dir_all(&template_dir).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    git_init(&dest_dir);
+
+    fs::create_dir_all(dest_dir.join(".git/info")).unwrap();
+    fs::write(dest_dir.join(".git/info/exclude"), "local-only.txt\n").unwrap();
+
+    fs::write(template_dir.join("local-only.txt"), "nope\n").unwrap();
+    fs::write(template_dir.join("ok.txt"), "ok\n").unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert_eq!(report.ignored_paths, 1);
+    assert!(!dest_dir.join("local-only.txt").exists());
+}
+
+#[test]
+fn honors_core_excludes_file() {
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    let excludes = root.join("global-excludes");
+
+    fs::create_dir_all(&template_dir).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    git_init(&dest_dir);
+
+    // Repo-local config keeps the test hermetic while exercising the same
+    // `core.excludesFile` lookup git uses for a user's global excludes.
+    fs::write(&excludes, "*.swp\n").unwrap();
+    assert!(
+        Command::new("git")
+            .arg("-C")
+            .arg(&dest_dir)
+            .args(["config", "core.excludesFile"])
+            .arg(&excludes)
+            .output()
+            .unwrap()
+            .status
+            .success()
+    );
+
+    fs::write(template_dir.join("notes.swp"), "nope\n").unwrap();
+    fs::write(template_dir.join("ok.txt"), "ok\n").unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert_eq!(report.ignored_paths, 1);
+    assert!(!dest_dir.join("notes.swp").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn prunes_ignored_directories_without_descending() {
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+
+    fs::create_dir_all(template_dir.join("node_modules/pkg")).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    git_init(&dest_dir);
+    fs::write(dest_dir.join(".gitignore"), "node_modules\n").unwrap();
+
+    fs::write(template_dir.join("ok.txt"), "ok\n").unwrap();
+    for i in 0..5 {
+        fs::write(
+            template_dir.join(format!("node_modules/pkg/f{i}.js")),
+            "x\n",
+        )
+        .unwrap();
+    }
+    // A symlink would be rejected if the walk descended into the ignored tree.
+    std::os::unix::fs::symlink(&dest_dir, template_dir.join("node_modules/pkg/link")).unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions {
+            max_files: Some(1),
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert_eq!(report.ignored_paths, 1);
+    assert!(!dest_dir.join("node_modules").exists());
+}
+
+#[test]
+fn report_entries_name_the_matching_rule() {
+    use pinit_core::{EntryOutcome, IgnoreReason};
+
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+
+    fs::create_dir_all(template_dir.join("build")).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    git_init(&dest_dir);
+    fs::write(
+        dest_dir.join(".gitignore"),
+        "# generated\n*.log\n!keep.log\nbuild/\n",
+    )
+    .unwrap();
+
+    fs::write(template_dir.join(".DS_Store"), "junk").unwrap();
+    fs::write(template_dir.join("debug.log"), "nope\n").unwrap();
+    fs::write(template_dir.join("keep.log"), "ok\n").unwrap();
+    fs::write(template_dir.join("build/out.txt"), "nope\n").unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert_eq!(report.ignored_paths, 3);
+    assert!(dest_dir.join("keep.log").is_file());
+
+    let outcome = |path: &str| {
+        report
+            .entries
+            .iter()
+            .find(|e| e.path == Path::new(path))
+            .map(|e| e.outcome.clone())
+    };
+    assert_eq!(
+        outcome(".DS_Store"),
+        Some(EntryOutcome::Ignored(IgnoreReason::Always))
+    );
+    assert_eq!(outcome("keep.log"), Some(EntryOutcome::Created));
+
+    let Some(EntryOutcome::Ignored(IgnoreReason::Git(rule))) = outcome("debug.log") else {
+        panic!("debug.log should be ignored by git: {:?}", report.entries);
+    };
+    assert_eq!(rule.source, ".gitignore");
+    assert_eq!(rule.line, 2);
+    assert_eq!(rule.pattern, "*.log");
+    assert_eq!(rule.to_string(), ".gitignore:2: *.log");
+
+    let Some(EntryOutcome::Ignored(IgnoreReason::Git(rule))) = outcome("build") else {
+        panic!("build/ should be pruned by git: {:?}", report.entries);
+    };
+    assert_eq!(rule.pattern, "build/");
+}
+
+#[test]
+fn report_separates_ignored_files_from_pruned_dirs() {
+    use pinit_core::PrunedDir;
+
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+
+    fs::create_dir_all(template_dir.join("build/nested")).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    git_init(&dest_dir);
+    fs::write(dest_dir.join(".gitignore"), "*.log\nbuild/\n").unwrap();
+
+    fs::write(template_dir.join("ok.txt"), "ok\n").unwrap();
+    fs::write(template_dir.join("a.log"), "nope\n").unwrap();
+    fs::write(template_dir.join("b.log"), "nope\n").unwrap();
+    fs::write(template_dir.join("build/out.txt"), "nope\n").unwrap();
+    fs::write(template_dir.join("build/nested/more.txt"), "nope\n").unwrap();
+
+    let apply = |count_pruned_files| {
+        pinit_core::apply_template_dir(
+            &template_dir,
+            &dest_dir,
+            pinit_core::ApplyOptions {
+                dry_run: true,
+                count_pruned_files,
+                ..Default::default()
+            },
+            &mut pinit_core::SkipExisting,
+        )
+        .unwrap()
+    };
+
+    let report = apply(false);
+    assert_eq!(report.ignored_paths, 3);
+    assert_eq!(report.ignored_files, 2);
+    assert_eq!(report.pruned_dirs, 1);
+    assert_eq!(report.pruned_dir_files, 0);
+    let pruned = |report: &pinit_core::ApplyReport| {
+        report
+            .entries
+            .iter()
+            .find(|e| e.path == Path::new("build"))
+            .and_then(|e| e.pruned)
+    };
+    assert_eq!(pruned(&report), Some(PrunedDir { files: None }));
+    let a_log = report
+        .entries
+        .iter()
+        .find(|e| e.path == Path::new("a.log"))
+        .unwrap();
+    assert_eq!(a_log.pruned, None);
+
+    let report = apply(true);
+    assert_eq!(report.ignored_paths, 3);
+    assert_eq!(report.pruned_dir_files, 2);
+    assert_eq!(pruned(&report), Some(PrunedDir { files: Some(2) }));
+}
+
+#[test]
+fn nested_repos_are_skipped_or_entered_without_outer_rules() {
+    use pinit_core::config::NestedRepoAction;
+    use pinit_core::{EntryOutcome, IgnoreReason};
+
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+
+    fs::create_dir_all(template_dir.join("vendor/lib")).unwrap();
+    fs::write(template_dir.join("vendor/README.md"), "vendored\n").unwrap();
+    fs::write(template_dir.join("vendor/lib/notes.txt"), "notes\n").unwrap();
+    fs::create_dir_all(dest_dir.join("vendor/lib")).unwrap();
+    git_init(&dest_dir);
+    git_init(&dest_dir.join("vendor/lib"));
+    fs::write(dest_dir.join(".gitignore"), "*.txt\n").unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert!(dest_dir.join("vendor/README.md").is_file());
+    assert!(!dest_dir.join("vendor/lib/notes.txt").exists());
+    let entry = report
+        .entries
+        .iter()
+        .find(|e| e.path == Path::new("vendor/lib"))
+        .unwrap();
+    assert_eq!(
+        entry.outcome,
+        EntryOutcome::Ignored(IgnoreReason::NestedRepo(PathBuf::from("vendor/lib")))
+    );
+
+    // Entering writes into the nested repo; the outer `*.txt` rule does not reach it.
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions {
+            nested_repos: NestedRepoAction::Enter,
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("vendor/lib/notes.txt")).unwrap(),
+        "notes\n"
+    );
+}
@@ -144,6 +144,77 @@ fn apply_always_ignores_dot_git_paths() {
     assert!(!dest_dir.join(".git/config").exists());
 }
 
+#[test]
+fn apply_renders_known_variables_in_text_files() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(&template_dir).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    fs::write(
+        template_dir.join("new.txt"),
+        "{{ crate_name }} {{ other }}\n",
+    )
+    .unwrap();
+    fs::write(template_dir.join("same.txt"), "{{ package_name }}\n").unwrap();
+    fs::write(
+        template_dir.join("bin.dat"),
+        [0xffu8, b'{', b'{', b'}', b'}'],
+    )
+    .unwrap();
+    fs::write(dest_dir.join("same.txt"), "my-app\n").unwrap();
+
+    let mut decider = FixedDecider(ExistingFileAction::Overwrite);
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions {
+            variables: pinit_core::vars::project_variables("My App"),
+            ..Default::default()
+        },
+        &mut decider,
+    )
+    .unwrap();
+
+    assert_eq!(report.created_files, 2);
+    assert_eq!(report.skipped_files, 1);
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("new.txt")).unwrap(),
+        "my_app {{ other }}\n"
+    );
+    assert_eq!(
+        fs::read(dest_dir.join("bin.dat")).unwrap(),
+        [0xffu8, b'{', b'{', b'}', b'}']
+    );
+}
+
+#[test]
+fn apply_never_copies_root_template_manifest() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(template_dir.join("nested")).unwrap();
+    fs::write(
+        template_dir.join("pinit-template.toml"),
+        "description = \"x\"\n",
+    )
+    .unwrap();
+    fs::write(template_dir.join("nested/pinit-template.toml"), "kept\n").unwrap();
+
+    let mut decider = FixedDecider(ExistingFileAction::Overwrite);
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+    )
+    .unwrap();
+    assert_eq!(report.ignored_paths, 1);
+    assert_eq!(report.created_files, 1);
+    assert!(!dest_dir.join("pinit-template.toml").exists());
+    assert!(dest_dir.join("nested/pinit-template.toml").is_file());
+}
+
 #[test]
 fn apply_generated_dry_run_reports_updates_without_writing() {
     let root = make_temp_root();
@@ -203,3 +274,256 @@ fn overwrite_preserves_existing_permissions() {
         & 0o777;
     assert_eq!(out_perms, 0o600);
 }
+
+#[test]
+fn path_map_renames_files_and_directories() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(template_dir.join("ci/scripts")).unwrap();
+    fs::write(template_dir.join("ci/github.yml"), "on: push\n").unwrap();
+    fs::write(template_dir.join("ci/scripts/test.sh"), "cargo test\n").unwrap();
+    fs::write(template_dir.join("editorconfig"), "root = true\n").unwrap();
+
+    let path_map = [
+        ("ci/github.yml", ".github/workflows/ci.yml"),
+        ("ci/scripts", "scripts"),
+        ("editorconfig", ".editorconfig"),
+    ]
+    .into_iter()
+    .map(|(a, b)| (PathBuf::from(a), PathBuf::from(b)))
+    .collect();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions {
+            dest_prefix: Some(PathBuf::from("svc")),
+            path_map,
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 3);
+    assert!(dest_dir.join("svc/.github/workflows/ci.yml").is_file());
+    assert!(dest_dir.join("svc/scripts/test.sh").is_file());
+    assert!(dest_dir.join("svc/.editorconfig").is_file());
+    assert!(!dest_dir.join("svc/ci").exists());
+}
+
+#[test]
+fn git_dirs_are_never_written_even_through_a_rename() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(template_dir.join("hooks")).unwrap();
+    fs::create_dir_all(template_dir.join("vendor/.git")).unwrap();
+    fs::write(template_dir.join("hooks/pre-commit"), "#!/bin/sh\n").unwrap();
+    fs::write(template_dir.join("vendor/.git/config"), "[core]\n").unwrap();
+    fs::write(template_dir.join("vendor/lib.rs"), "\n").unwrap();
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions {
+            path_map: [(PathBuf::from("hooks"), PathBuf::from(".git/hooks"))]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 1);
+    assert!(dest_dir.join("vendor/lib.rs").is_file());
+    assert!(!dest_dir.join(".git").exists());
+    assert!(!dest_dir.join("vendor/.git").exists());
+}
+
+#[test]
+fn only_existing_updates_without_creating() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(template_dir.join("new")).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    fs::write(template_dir.join("rustfmt.toml"), "edition = \"2024\"\n").unwrap();
+    fs::write(template_dir.join("new/scaffold.rs"), "fn main() {}\n").unwrap();
+    fs::write(dest_dir.join("rustfmt.toml"), "edition = \"2021\"\n").unwrap();
+
+    let options = pinit_core::ApplyOptions {
+        only_existing: true,
+        ..Default::default()
+    };
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        options.clone(),
+        &mut FixedDecider(ExistingFileAction::Overwrite),
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 0);
+    assert_eq!(report.updated_files, 1);
+    assert_eq!(report.skipped_files, 1);
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("rustfmt.toml")).unwrap(),
+        "edition = \"2024\"\n"
+    );
+    assert!(!dest_dir.join("new").exists());
+
+    let report = pinit_core::apply_generated_file(
+        &dest_dir,
+        "LICENSE",
+        b"MIT\n",
+        options,
+        &mut FixedDecider(ExistingFileAction::Overwrite),
+    )
+    .unwrap();
+    assert_eq!(report.skipped_files, 1);
+    assert!(!dest_dir.join("LICENSE").exists());
+}
+
+#[test]
+fn transform_pipeline_runs_configured_steps() {
+    use pinit_core::config::EolStyle;
+    use pinit_core::transform::{NormalizeEol, Pipeline, SetMode};
+
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(template_dir.join("scripts")).unwrap();
+    fs::write(
+        template_dir.join("scripts/run.sh"),
+        "echo {{ package_name }}\r\n",
+    )
+    .unwrap();
+    fs::write(template_dir.join("notes.txt"), "a\r\nb\r\n").unwrap();
+
+    let mut transforms = Pipeline::new();
+    transforms.push(NormalizeEol {
+        eol: EolStyle::Lf,
+        pattern: None,
+    });
+    transforms.push(SetMode {
+        mode: 0o755,
+        pattern: "scripts/*.sh".to_string(),
+    });
+
+    let report = pinit_core::apply_template_dir(
+        &template_dir,
+        &dest_dir,
+        pinit_core::ApplyOptions {
+            variables: pinit_core::vars::project_variables("demo"),
+            transforms,
+            ..Default::default()
+        },
+        &mut pinit_core::SkipExisting,
+    )
+    .unwrap();
+    assert_eq!(report.created_files, 2);
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("scripts/run.sh")).unwrap(),
+        "echo demo\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("notes.txt")).unwrap(),
+        "a\nb\n"
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(dest_dir.join("scripts/run.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+}
+
+#[test]
+fn report_counts_bytes_written_and_largest_files() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(template_dir.join("assets")).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+
+    fs::write(template_dir.join("assets/logo.png"), vec![7u8; 4096]).unwrap();
+    fs::write(template_dir.join("README.md"), "hello\n").unwrap();
+    fs::write(template_dir.join("same.txt"), "same\n").unwrap();
+    fs::write(template_dir.join("hello.txt"), "template\n").unwrap();
+    fs::write(dest_dir.join("same.txt"), "same\n").unwrap();
+    fs::write(dest_dir.join("hello.txt"), "dest\n").unwrap();
+
+    let apply = |dry_run| {
+        let mut decider = FixedDecider(ExistingFileAction::Overwrite);
+        pinit_core::apply_template_dir(
+            &template_dir,
+            &dest_dir,
+            pinit_core::ApplyOptions {
+                dry_run,
+                ..Default::default()
+            },
+            &mut decider,
+        )
+        .unwrap()
+    };
+
+    // A dry run reports what would be written; skipped files count for nothing.
+    let report = apply(true);
+    assert_eq!(report.bytes_written(), 4096 + 6 + 9);
+    let largest: Vec<(&Path, Option<u64>)> = report
+        .largest_files(2)
+        .into_iter()
+        .map(|e| (e.path.as_path(), e.bytes))
+        .collect();
+    assert_eq!(
+        largest,
+        [
+            (Path::new("assets/logo.png"), Some(4096)),
+            (Path::new("hello.txt"), Some(9)),
+        ]
+    );
+
+    assert_eq!(apply(false).bytes_written(), 4096 + 6 + 9);
+    assert_eq!(apply(false).bytes_written(), 0);
+}
+
+#[test]
+fn report_records_how_updated_files_were_changed() {
+    let root = make_temp_root();
+    let template_dir = root.join("template");
+    let dest_dir = root.join("dest");
+    fs::create_dir_all(&template_dir).unwrap();
+    fs::create_dir_all(&dest_dir).unwrap();
+    fs::write(template_dir.join("notes.txt"), "template\n").unwrap();
+    fs::write(template_dir.join("README.md"), "hello\n").unwrap();
+    fs::write(dest_dir.join("notes.txt"), "dest\n").unwrap();
+
+    for action in [ExistingFileAction::Merge, ExistingFileAction::Overwrite] {
+        let mut decider = FixedDecider(action);
+        let report = pinit_core::apply_template_dir(
+            &template_dir,
+            &dest_dir,
+            pinit_core::ApplyOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+            &mut decider,
+        )
+        .unwrap();
+        let actions: Vec<(&Path, Option<ExistingFileAction>)> = report
+            .entries
+            .iter()
+            .map(|e| (e.path.as_path(), e.action))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (Path::new("README.md"), None),
+                (Path::new("notes.txt"), Some(action)),
+            ]
+        );
+    }
+}
@@ -43,6 +43,8 @@ templates:
     let args = lic.template_args();
     assert_eq!(args.get("year").unwrap(), "2025");
     assert_eq!(args.get("fullname").unwrap(), "Clay");
+    assert_eq!(args.get("owner").unwrap(), "Clay");
+    assert_eq!(args.get("name of author").unwrap(), "Clay");
     let _ = fs::remove_dir_all(&root);
 }
 
@@ -170,3 +172,854 @@ recipes:
 
     let _ = fs::remove_dir_all(&root);
 }
+
+#[test]
+fn remote_section_parses_from_toml_and_yaml() {
+    let root = std::env::temp_dir().join(format!("pinit-config-remote-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(
+        &toml_path,
+        r#"
+[remote]
+command = ["gh", "repo", "create", "{{ project_name }}", "--private"]
+url = "git@github.com:me/{{ package_name }}.git"
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    let remote = cfg.remote.unwrap();
+    assert_eq!(remote.command[3], "{{ project_name }}");
+    assert_eq!(remote.remote_name(), "origin");
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        r#"
+remote:
+  command: [gh, repo, create, "{{ project_name }}"]
+  name: upstream
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    let remote = cfg.remote.unwrap();
+    assert_eq!(remote.command.len(), 4);
+    assert_eq!(remote.url, None);
+    assert_eq!(remote.remote_name(), "upstream");
+
+    fs::write(&toml_path, "[remote]\ncommand = []\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(err.to_string().contains("remote must set command or url"));
+
+    fs::write(
+        &toml_path,
+        "[remote]\nurl = \"git@example.invalid:x.git\"\nprotect = [\"gh\", \"api\"]\ncommit_message = \"chore: init\"\n",
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    let remote = cfg.remote.unwrap();
+    assert!(remote.command.is_empty());
+    assert_eq!(remote.protect, vec!["gh", "api"]);
+    assert_eq!(remote.commit_message.as_deref(), Some("chore: init"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn gitignore_fragments_parse_from_yaml() {
+    let root = std::env::temp_dir().join(format!("pinit-config-gitignore-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let path = root.join("pinit.yaml");
+    fs::write(
+        &path,
+        r#"
+gitignore:
+  team: [.DS_Store, /scratch/]
+recipes:
+  app:
+    templates: []
+    gitignore: [rust, team]
+"#,
+    )
+    .unwrap();
+
+    let (_, cfg) = pinit_core::config::load_config(Some(&path)).unwrap();
+    assert_eq!(cfg.gitignore["team"], vec![".DS_Store", "/scratch/"]);
+    let resolved = cfg.resolve_recipe("app").unwrap();
+    assert_eq!(resolved.gitignore, vec!["rust", "team"]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn update_check_parses_from_toml_and_yaml() {
+    let root = std::env::temp_dir().join(format!("pinit-config-update-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "[update_check]\nenabled = true\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    let update = cfg.update_check.unwrap();
+    assert!(update.enabled);
+    assert_eq!(update.repo, None);
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        "update_check:\n  enabled: false\n  repo: https://example.invalid/pinit.git\n",
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    let update = cfg.update_check.unwrap();
+    assert!(!update.enabled);
+    assert_eq!(
+        update.repo.as_deref(),
+        Some("https://example.invalid/pinit.git")
+    );
+
+    fs::write(&toml_path, "[update_check]\nrepo = \" \"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("update_check.repo must not be empty")
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn binary_action_parses_and_rejects_unknown_values() {
+    use pinit_core::config::BinaryAction;
+
+    let root = std::env::temp_dir().join(format!("pinit-config-binary-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "binary_action = \"skip\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.binary_action, Some(BinaryAction::Skip));
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(&yaml_path, "binary_action: Prompt\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.binary_action, Some(BinaryAction::Prompt));
+
+    fs::write(&yaml_path, "binary_action: merge\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("binary_action must be skip, overwrite, or prompt")
+    );
+
+    fs::write(&toml_path, "binary_action = \"merge\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("binary_action must be skip, overwrite, or prompt"),
+        "{err}"
+    );
+
+    // Both formats ignore case the same way.
+    fs::write(&toml_path, "binary_action = \"Prompt\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.binary_action, Some(BinaryAction::Prompt));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn apply_lockfiles_defaults_off_and_parses() {
+    let root = std::env::temp_dir().join(format!("pinit-config-lockfiles-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert!(!cfg.apply_lockfiles);
+
+    fs::write(&toml_path, "apply_lockfiles = true\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert!(cfg.apply_lockfiles);
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(&yaml_path, "apply_lockfiles: true\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert!(cfg.apply_lockfiles);
+
+    assert!(pinit_core::is_lockfile(std::path::Path::new(
+        "crates/a/Cargo.lock"
+    )));
+    assert!(!pinit_core::is_lockfile(std::path::Path::new("Cargo.toml")));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn nested_repos_parses_and_rejects_unknown_values() {
+    use pinit_core::config::NestedRepoAction;
+
+    let root = std::env::temp_dir().join(format!("pinit-config-nested-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.nested_repos, NestedRepoAction::Skip);
+
+    fs::write(&toml_path, "nested_repos = \"enter\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.nested_repos, NestedRepoAction::Enter);
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(&yaml_path, "nested_repos: Enter\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.nested_repos, NestedRepoAction::Enter);
+
+    fs::write(&yaml_path, "nested_repos: stomp\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("nested_repos must be skip or enter")
+    );
+
+    fs::write(&toml_path, "nested_repos = \"stomp\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("nested_repos must be skip or enter"),
+        "{err}"
+    );
+
+    // Both formats ignore case the same way.
+    fs::write(&toml_path, "nested_repos = \"Enter\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.nested_repos, NestedRepoAction::Enter);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn dirty_dest_parses_and_rejects_unknown_values() {
+    use pinit_core::config::DirtyDestAction;
+
+    let root = std::env::temp_dir().join(format!("pinit-config-dirty-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.dirty_dest, DirtyDestAction::Warn);
+
+    fs::write(&toml_path, "dirty_dest = \"abort\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.dirty_dest, DirtyDestAction::Abort);
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(&yaml_path, "dirty_dest: Stash\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.dirty_dest, DirtyDestAction::Stash);
+
+    fs::write(&yaml_path, "dirty_dest: ignore\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("dirty_dest must be warn, abort, or stash")
+    );
+
+    fs::write(&toml_path, "dirty_dest = \"ignore\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("dirty_dest must be warn, abort, or stash"),
+        "{err}"
+    );
+
+    // Both formats ignore case the same way.
+    fs::write(&toml_path, "dirty_dest = \"Stash\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.dirty_dest, DirtyDestAction::Stash);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn license_conflict_parses_and_rejects_unknown_values() {
+    use pinit_core::config::LicenseConflictAction;
+
+    let root = std::env::temp_dir().join(format!(
+        "pinit-config-license-conflict-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.license_conflict, None);
+
+    fs::write(&toml_path, "license_conflict = \"keep\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.license_conflict, Some(LicenseConflictAction::Keep));
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(&yaml_path, "license_conflict: Matching\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.license_conflict, Some(LicenseConflictAction::Matching));
+
+    fs::write(&yaml_path, "license_conflict: merge\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("license_conflict must be keep, overwrite, matching, or prompt")
+    );
+
+    fs::write(&toml_path, "license_conflict = \"merge\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("license_conflict must be keep, overwrite, matching, or prompt"),
+        "{err}"
+    );
+
+    // Both formats ignore case the same way.
+    fs::write(&toml_path, "license_conflict = \"Matching\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.license_conflict, Some(LicenseConflictAction::Matching));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn template_entries_accept_dest_prefix() {
+    use pinit_core::config::TemplateRef;
+    use std::path::Path;
+
+    let root = std::env::temp_dir().join(format!("pinit-config-prefix-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(
+        &toml_path,
+        r#"
+[targets]
+services = ["common", { template = "rust", dest_prefix = "api" }, { template = "rust", dest_prefix = "worker" }]
+
+[recipes.mono]
+templates = ["common", { name = "node", dest_prefix = "web/" }]
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    let services = cfg.resolve_recipe("services").unwrap();
+    assert_eq!(services.templates.len(), 3);
+    assert_eq!(services.templates[0], TemplateRef::from("common"));
+    assert_eq!(services.templates[1].name(), "rust");
+    assert_eq!(services.templates[1].dest_prefix(), Some(Path::new("api")));
+    assert_eq!(services.templates[2].to_string(), "rust -> worker");
+    assert_eq!(
+        cfg.recipes["mono"].templates[1].dest_prefix(),
+        Some(Path::new("web/"))
+    );
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        r#"
+targets:
+  services:
+    - common
+    - template: rust
+      dest_prefix: api
+recipes:
+  mono:
+    templates:
+      - name: node
+        dest_prefix: web
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    let services = cfg.targets["services"].templates();
+    assert_eq!(services[0].name(), "common");
+    assert_eq!(services[1].dest_prefix(), Some(Path::new("api")));
+    assert_eq!(
+        cfg.recipes["mono"].templates[0].dest_prefix(),
+        Some(Path::new("web"))
+    );
+
+    fs::write(
+        &toml_path,
+        "[targets]\nbad = [{ template = \"rust\", dest_prefix = \"../escape\" }]\n",
+    )
+    .unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("targets.bad[0].dest_prefix must be a relative path inside the destination"),
+        "{err}"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn map_rules_parse_and_layer_per_stack() {
+    use std::path::PathBuf;
+
+    let root = std::env::temp_dir().join(format!("pinit-config-map-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(
+        &toml_path,
+        r#"
+map = { "ci/github.yml" = ".github/workflows/ci.yml", "docs" = "site" }
+
+[targets.rust]
+templates = ["rust"]
+map = { "docs" = "book/src" }
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.map.len(), 2);
+    let resolved = cfg.resolve_recipe("rust").unwrap();
+    assert_eq!(
+        resolved.map.get(&PathBuf::from("docs")),
+        Some(&PathBuf::from("book/src"))
+    );
+    assert_eq!(
+        resolved.map.get(&PathBuf::from("ci/github.yml")),
+        Some(&PathBuf::from(".github/workflows/ci.yml"))
+    );
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        r#"
+map:
+  ci/github.yml: .github/workflows/ci.yml
+recipes:
+  full:
+    templates: [rust]
+    map:
+      editorconfig: .editorconfig
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    let resolved = cfg.resolve_recipe("full").unwrap();
+    assert_eq!(resolved.map.len(), 2);
+    assert_eq!(
+        resolved.map.get(&PathBuf::from("editorconfig")),
+        Some(&PathBuf::from(".editorconfig"))
+    );
+
+    fs::write(&toml_path, "map = { \"ci.yml\" = \"../ci.yml\" }\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("map: ../ci.yml must be a relative path without '..'"),
+        "{err}"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn targets_expand_targets_they_reference() {
+    use pinit_core::config::TemplateRef;
+    use std::path::{Path, PathBuf};
+
+    let root = std::env::temp_dir().join(format!("pinit-config-nested-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(
+        &toml_path,
+        r#"
+[templates]
+common = "/t/common"
+rust = "/t/rust"
+service = "/t/service"
+
+[targets]
+rust = ["common", "rust"]
+platform = ["rust-service", { template = "rust", dest_prefix = "api" }]
+
+[targets.rust-service]
+templates = ["rust", "service"]
+map = { "docs" = "book" }
+
+[[targets.rust-service.overrides]]
+pattern = "Cargo.toml"
+action = "skip"
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    let lint = cfg.lint();
+    assert!(
+        lint.iter().all(|w| !w.message.contains("undefined")),
+        "{lint:?}"
+    );
+
+    // `rust` is both a template and a target; the template wins inside lists.
+    let resolved = cfg.resolve_recipe("rust-service").unwrap();
+    let names: Vec<&str> = resolved.templates.iter().map(TemplateRef::name).collect();
+    assert_eq!(names, ["rust", "service"]);
+
+    let resolved = cfg.resolve_recipe("platform").unwrap();
+    let names: Vec<String> = resolved.templates.iter().map(|t| t.to_string()).collect();
+    assert_eq!(names, ["rust", "service", "rust -> api"]);
+    assert_eq!(resolved.overrides.len(), 1);
+    assert_eq!(
+        resolved.map.get(&PathBuf::from("docs")),
+        Some(&PathBuf::from("book"))
+    );
+
+    fs::write(
+        &toml_path,
+        r#"
+[templates]
+rust = "/t/rust"
+
+[targets]
+inner = ["rust", { template = "rust", dest_prefix = "b" }]
+outer = [{ template = "inner", dest_prefix = "a" }]
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    let resolved = cfg.resolve_recipe("outer").unwrap();
+    let prefixes: Vec<Option<&Path>> = resolved
+        .templates
+        .iter()
+        .map(TemplateRef::dest_prefix)
+        .collect();
+    assert_eq!(prefixes, [Some(Path::new("a")), Some(Path::new("a/b"))]);
+
+    fs::write(
+        &toml_path,
+        "[targets]\na = [\"b\"]\nb = [\"c\"]\nc = [\"a\"]\n",
+    )
+    .unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("targets reference each other in a cycle: a -> b -> c -> a"),
+        "{err}"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn aliases_resolve_to_templates_targets_and_recipes() {
+    use pinit_core::config::ResolvedKind;
+
+    let root = std::env::temp_dir().join(format!("pinit-config-aliases-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(
+        &toml_path,
+        r#"
+[templates]
+python = { path = "/t/python", aliases = ["py", "python3"] }
+
+[targets.rust-service]
+templates = ["python"]
+aliases = ["rs"]
+
+[recipes.full]
+templates = ["python"]
+aliases = ["all"]
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.templates["python"].aliases(), ["py", "python3"]);
+    assert_eq!(cfg.canonical_name("python3"), "python");
+    assert_eq!(cfg.canonical_name("nope"), "nope");
+
+    let resolved = cfg.resolve_recipe("py").unwrap();
+    assert_eq!(resolved.name, "python");
+    assert_eq!(resolved.kind, ResolvedKind::Template);
+    assert_eq!(cfg.resolve_recipe("rs").unwrap().name, "rust-service");
+    assert_eq!(
+        cfg.resolve_recipe("all").unwrap().kind,
+        ResolvedKind::Recipe
+    );
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        r#"
+templates:
+  python:
+    path: /t/python
+    aliases: [py]
+targets:
+  svc:
+    templates: [python]
+    aliases: [service]
+recipes:
+  full:
+    templates: [python]
+    aliases: [all]
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    let aliases: Vec<(&str, &str)> = cfg.aliases().collect();
+    assert_eq!(
+        aliases,
+        [("all", "full"), ("service", "svc"), ("py", "python")]
+    );
+
+    for (body, expected) in [
+        (
+            "[templates]\npython = { path = \"/t\", aliases = [\"rust\"] }\nrust = \"/r\"\n",
+            "templates.python.aliases: 'rust' is already the name of a template, target, or recipe",
+        ),
+        (
+            "[templates]\na = { path = \"/a\", aliases = [\"x\"] }\nb = { path = \"/b\", aliases = [\"x\"] }\n",
+            "templates.b.aliases: 'x' is already an alias of templates.a",
+        ),
+        (
+            "[recipes.r]\naliases = [\" \"]\n",
+            "recipes.r.aliases must not contain empty names",
+        ),
+    ] {
+        fs::write(&toml_path, body).unwrap();
+        let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+        assert!(err.to_string().contains(expected), "{err}");
+    }
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn deprecations_cover_the_stack_and_its_templates() {
+    let root = std::env::temp_dir().join(format!("pinit-config-deprecated-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        r#"
+templates:
+  common: /t/common
+  rust-2021:
+    path: /t/rust
+    deprecated: use rust-2024 instead
+recipes:
+  legacy:
+    templates: [common, rust-2021]
+    deprecated: use full instead
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(
+        cfg.templates["rust-2021"].deprecated(),
+        Some("use rust-2024 instead")
+    );
+    let resolved = cfg.resolve_recipe("legacy").unwrap();
+    let notices: Vec<String> = cfg
+        .deprecations(&resolved)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        notices,
+        [
+            "recipes.legacy: recipe 'legacy' is deprecated: use full instead",
+            "templates.rust-2021: template 'rust-2021' is deprecated: use rust-2024 instead",
+        ]
+    );
+    let common = cfg.resolve_recipe("common").unwrap();
+    assert!(cfg.deprecations(&common).is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn requires_pinit_is_checked_before_parsing() {
+    let root = std::env::temp_dir().join(format!("pinit-config-requires-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "requires_pinit = \">=0.1\"\n").unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.requires_pinit.as_deref(), Some(">=0.1"));
+
+    // A newer config may use values this build rejects; the version wins.
+    fs::write(
+        &toml_path,
+        "requires_pinit = \">=999\"\nbinary_action = \"quantum\"\n",
+    )
+    .unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("requires pinit >=999, but this is pinit"),
+        "{err}"
+    );
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(&yaml_path, "requires_pinit: \">=999, <1000\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(err.to_string().contains("upgrade pinit"), "{err}");
+
+    fs::write(&yaml_path, "requires_pinit: soon\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "requires_pinit must be a version requirement such as \">=0.5\" (got \"soon\")"
+        ),
+        "{err}"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn cache_dir_must_be_absolute() {
+    let root = std::env::temp_dir().join(format!("pinit-config-cache-dir-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        format!("cache_dir: {}\n", root.join("cache").display()),
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.cache_dir, Some(root.join("cache")));
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "cache_dir = \"cache\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("cache_dir must be an absolute path (got \"cache\")"),
+        "{err}"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn default_recipe_must_name_a_known_stack() {
+    let root = std::env::temp_dir().join(format!(
+        "pinit-config-default-recipe-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        "default_recipe: rs\nrecipes:\n  rust:\n    templates: [rust]\n    aliases: [rs]\n",
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.default_recipe.as_deref(), Some("rs"));
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(&toml_path, "default_recipe = \"rust\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("default_recipe must name a template, target, or recipe (got \"rust\")"),
+        "{err}"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn transforms_parse_in_order_and_validate() {
+    use pinit_core::config::{EolStyle, TransformDef};
+
+    let root = std::env::temp_dir().join(format!("pinit-config-transforms-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let expected = vec![
+        TransformDef::Eol {
+            eol: EolStyle::Crlf,
+            pattern: Some("**/*.bat".to_string()),
+        },
+        TransformDef::Mode {
+            mode: "755".to_string(),
+            pattern: "scripts/*".to_string(),
+        },
+    ];
+
+    let toml_path = root.join("pinit.toml");
+    fs::write(
+        &toml_path,
+        r#"
+[[transforms]]
+step = "eol"
+eol = "CRLF"
+pattern = "**/*.bat"
+
+[[transforms]]
+step = "mode"
+mode = "755"
+pattern = "scripts/*"
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&toml_path)).unwrap();
+    assert_eq!(cfg.transforms, expected);
+
+    let yaml_path = root.join("pinit.yaml");
+    fs::write(
+        &yaml_path,
+        r#"
+transforms:
+  - step: eol
+    eol: CRLF
+    pattern: "**/*.bat"
+  - step: mode
+    mode: "755"
+    pattern: scripts/*
+"#,
+    )
+    .unwrap();
+    let (_, cfg) = pinit_core::config::load_config(Some(&yaml_path)).unwrap();
+    assert_eq!(cfg.transforms, expected);
+
+    fs::write(&yaml_path, "transforms:\n  - step: chmod\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&yaml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("transforms[0].step must be eol or mode"),
+        "{err}"
+    );
+
+    fs::write(
+        &toml_path,
+        "[[transforms]]\nstep = \"mode\"\nmode = \"rwx\"\npattern = \"*\"\n",
+    )
+    .unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("transforms[0].mode must be an octal mode"),
+        "{err}"
+    );
+
+    fs::write(&toml_path, "[[transforms]]\nstep = \"eol\"\neol = \"cr\"\n").unwrap();
+    let err = pinit_core::config::load_config(Some(&toml_path)).unwrap_err();
+    assert!(err.to_string().contains("eol must be lf or crlf"), "{err}");
+    let _ = fs::remove_dir_all(&root);
+}
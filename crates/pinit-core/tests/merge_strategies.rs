@@ -110,6 +110,21 @@ fn merge_yaml_inserts_missing_mapping_keys() {
     assert!(out.contains("b: 4"));
 }
 
+#[test]
+fn merge_ignore_files_dedupe_patterns_and_keep_section_comments() {
+    let (out, report) = run_merge(
+        ".gitignore",
+        b"# build\n/target/\n.DS_Store  \n",
+        b"# build\n/target/\n\n# editors\n.idea/\n.DS_Store\n\n# unchanged\n/target/\n",
+    );
+    assert_eq!(report.updated_files, 1);
+    assert_eq!(out, "# build\n/target/\n.DS_Store  \n\n# editors\n.idea/\n");
+
+    let (out, report) = run_merge(".dockerignore", b"node_modules\n", b"node_modules \n");
+    assert_eq!(report.skipped_files, 1);
+    assert_eq!(out, "node_modules\n");
+}
+
 #[test]
 fn merge_lines_appends_missing_exact_lines() {
     let (out, report) = run_merge("notes.txt", b"a\nb", b"b\nc\n");
@@ -117,6 +132,23 @@ fn merge_lines_appends_missing_exact_lines() {
     assert_eq!(out, "a\nb\nc\n");
 }
 
+#[test]
+fn merge_lines_fallback_is_reported_as_warning() {
+    let (_, report) = run_merge("notes.txt", b"a\n", b"b\n");
+    assert_eq!(report.warnings.len(), 1);
+    let warning = &report.warnings[0];
+    assert_eq!(warning.kind, pinit_core::WarningKind::MergeFallback);
+    assert_eq!(warning.path.as_deref(), Some(Path::new("notes.txt")));
+    assert!(
+        warning
+            .to_string()
+            .starts_with("notes.txt: no merge driver")
+    );
+
+    let (_, report) = run_merge("config.toml", b"a = 1\n", b"b = 2\n");
+    assert!(report.warnings.is_empty());
+}
+
 #[test]
 fn merge_toml_ignores_type_mismatches_but_inserts_other_keys() {
     let (out, report) = run_merge("config.toml", b"a = 1\n", b"b = 2\n[a]\nx = 2\n");
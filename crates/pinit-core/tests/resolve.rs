@@ -4,7 +4,7 @@ use std::process::Command;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use pinit_core::config::{Config, Source, TemplateDef};
-use pinit_core::resolve::{ResolveError, TemplateResolver};
+use pinit_core::resolve::{ResolveError, SourceLocation, TemplateLocation, TemplateResolver};
 
 static TEMP_COUNTER: AtomicU64 = AtomicU64::new(1);
 
@@ -81,12 +81,23 @@ fn resolves_local_template_from_source_path() {
         TemplateDef::Detailed {
             source: Some("local".into()),
             path: PathBuf::from("rust"),
+            aliases: Vec::new(),
+            deprecated: None,
         },
     );
 
     let resolver = TemplateResolver::new(root.join("cache"));
     let resolved = resolver.resolve_template_dir(&cfg, "rust").unwrap();
     assert_eq!(resolved, templates_root.join("rust"));
+
+    let location = resolver.locate_template(&cfg, "rust").unwrap();
+    assert_eq!(
+        location,
+        TemplateLocation::Local {
+            dir: templates_root.join("rust")
+        }
+    );
+    assert!(location.is_available());
 }
 
 #[test]
@@ -136,14 +147,258 @@ fn resolves_git_template_from_cached_clone() {
         TemplateDef::Detailed {
             source: Some("repo".into()),
             path: PathBuf::from("rust"),
+            aliases: Vec::new(),
+            deprecated: None,
         },
     );
 
     let cache_dir = root.join("cache");
     let resolver = TemplateResolver::new(cache_dir);
+    let before = resolver.locate_template(&cfg, "rust").unwrap();
+    assert!(!before.is_available());
+    assert!(matches!(
+        &before,
+        TemplateLocation::Git { commit: None, .. }
+    ));
+    assert!(matches!(
+        resolver.locate_source(&cfg.sources[0]).unwrap(),
+        SourceLocation::Git {
+            commit: None,
+            fetched_at: None,
+            ..
+        }
+    ));
+
     let resolved = resolver.resolve_template_dir(&cfg, "rust").unwrap();
     assert!(resolved.is_dir());
     assert!(resolved.join("hello.txt").is_file());
+
+    let after = resolver.locate_template(&cfg, "rust").unwrap();
+    assert!(after.is_available());
+    assert_eq!(after.dir(), resolved.as_path());
+    match after {
+        TemplateLocation::Git {
+            git_ref, commit, ..
+        } => assert_eq!(commit.as_deref(), Some(git_ref.as_str())),
+        other => panic!("expected git location, got {other:?}"),
+    }
+
+    match resolver.locate_source(&cfg.sources[0]).unwrap() {
+        SourceLocation::Git {
+            repo,
+            subdir,
+            commit,
+            fetched_at,
+            checkout,
+            ..
+        } => {
+            assert_eq!(repo, repo_dir.to_string_lossy());
+            assert_eq!(subdir.as_deref(), Some(Path::new("templates")));
+            assert!(commit.is_some());
+            assert!(fetched_at.is_some());
+            assert_eq!(checkout.join("templates/rust"), resolved);
+        }
+        other => panic!("expected git source, got {other:?}"),
+    }
+}
+
+#[test]
+fn broken_cache_checkouts_are_recloned_and_reported() {
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let repo_dir = root.join("repo");
+    fs::create_dir_all(repo_dir.join("rust")).unwrap();
+    git_ok(&repo_dir, &["init", "-q"]);
+    git_ok(
+        &repo_dir,
+        &["config", "user.email", "pinit@example.invalid"],
+    );
+    git_ok(&repo_dir, &["config", "user.name", "pinit"]);
+    fs::write(repo_dir.join("rust/hello.txt"), "hello\n").unwrap();
+    git_ok(&repo_dir, &["add", "."]);
+    git_ok(&repo_dir, &["commit", "-q", "-m", "init"]);
+
+    let mut cfg = Config::default();
+    cfg.sources.push(Source {
+        name: "repo".into(),
+        repo: Some(repo_dir.to_string_lossy().to_string()),
+        ..Default::default()
+    });
+    cfg.templates.insert(
+        "rust".into(),
+        TemplateDef::Detailed {
+            source: Some("repo".into()),
+            path: PathBuf::from("rust"),
+            aliases: Vec::new(),
+            deprecated: None,
+        },
+    );
+
+    let resolver = TemplateResolver::new(root.join("cache"));
+    assert!(resolver.verify_cache().unwrap().is_empty());
+    let resolved = resolver.resolve_template_dir(&cfg, "rust").unwrap();
+    let cached = resolver.verify_cache().unwrap();
+    assert_eq!(cached.len(), 2, "{cached:?}");
+    assert!(cached.iter().all(|c| c.is_healthy()));
+    assert!(
+        cached
+            .iter()
+            .all(|c| c.repo.as_deref() == Some(repo_dir.to_string_lossy().as_ref()))
+    );
+    let (mirror, worktree) = (cached[0].dir.clone(), cached[1].dir.clone());
+    assert!(cached[0].is_mirror());
+    assert_eq!(worktree.join("rust"), resolved);
+
+    // A worktree whose git link is damaged is recreated from the mirror.
+    fs::write(worktree.join(".git"), "gitdir: /nonexistent\n").unwrap();
+    assert!(!resolver.verify_cache().unwrap()[1].is_healthy());
+    let again = resolver.resolve_template_dir(&cfg, "rust").unwrap();
+    assert_eq!(again, resolved);
+    assert!(again.join("hello.txt").is_file());
+    assert!(resolver.verify_cache().unwrap()[1].is_healthy());
+
+    // An interrupted clone (HEAD points nowhere) is thrown away and cloned again.
+    fs::write(mirror.join("HEAD"), "garbage\n").unwrap();
+    let cached = resolver.verify_cache().unwrap();
+    assert!(!cached[0].is_healthy(), "{cached:?}");
+    let again = resolver.resolve_template_dir(&cfg, "rust").unwrap();
+    assert_eq!(again, resolved);
+    assert!(again.join("hello.txt").is_file());
+    assert!(
+        resolver
+            .verify_cache()
+            .unwrap()
+            .iter()
+            .all(|c| c.is_healthy())
+    );
+
+    // Removing the mirror takes its worktrees with it.
+    resolver.verify_cache().unwrap()[0].remove().unwrap();
+    assert!(resolver.verify_cache().unwrap().is_empty());
+
+    // A clone from the old `repos/<hash>/repo` layout is reported as stale,
+    // not as a broken mirror, and is dropped once the mirror is cloned.
+    let repo_cache = mirror.parent().unwrap();
+    let legacy = repo_cache.join("repo");
+    git_ok(
+        &root.0,
+        &[
+            "clone",
+            "-q",
+            repo_dir.to_str().unwrap(),
+            legacy.to_str().unwrap(),
+        ],
+    );
+    let cached = resolver.verify_cache().unwrap();
+    assert_eq!(cached.len(), 1, "{cached:?}");
+    assert_eq!(cached[0].dir, legacy);
+    assert!(
+        cached[0]
+            .problem
+            .as_deref()
+            .unwrap()
+            .contains("older pinit cache layout")
+    );
+    resolver.resolve_template_dir(&cfg, "rust").unwrap();
+    assert!(!legacy.exists());
+    assert!(
+        resolver
+            .verify_cache()
+            .unwrap()
+            .iter()
+            .all(|c| c.is_healthy())
+    );
+
+    // `--repair` style removal deletes just the stale clone.
+    git_ok(
+        &root.0,
+        &[
+            "clone",
+            "-q",
+            repo_dir.to_str().unwrap(),
+            legacy.to_str().unwrap(),
+        ],
+    );
+    resolver.verify_cache().unwrap()[0].remove().unwrap();
+    assert!(!legacy.exists());
+    assert!(mirror.join("HEAD").is_file());
+}
+
+#[test]
+fn resolves_git_template_at_explicit_refs() {
+    if !git_available() {
+        return;
+    }
+
+    let root = make_temp_root();
+    let repo_dir = root.join("repo");
+    fs::create_dir_all(repo_dir.join("rust")).unwrap();
+    git_ok(&repo_dir, &["init", "-q"]);
+    git_ok(
+        &repo_dir,
+        &["config", "user.email", "pinit@example.invalid"],
+    );
+    git_ok(&repo_dir, &["config", "user.name", "pinit"]);
+    fs::write(repo_dir.join("rust/hello.txt"), "v1\n").unwrap();
+    git_ok(&repo_dir, &["add", "."]);
+    git_ok(&repo_dir, &["commit", "-q", "-m", "v1"]);
+    git_ok(&repo_dir, &["tag", "v1"]);
+    fs::write(repo_dir.join("rust/hello.txt"), "v2\n").unwrap();
+    git_ok(&repo_dir, &["commit", "-q", "-am", "v2"]);
+    git_ok(&repo_dir, &["tag", "v2"]);
+
+    let mut cfg = Config::default();
+    cfg.sources.push(Source {
+        name: "repo".into(),
+        repo: Some(repo_dir.to_string_lossy().to_string()),
+        git_ref: Some("v2".into()),
+        ..Default::default()
+    });
+    cfg.templates.insert(
+        "rust".into(),
+        TemplateDef::Detailed {
+            source: Some("repo".into()),
+            path: PathBuf::from("rust"),
+            aliases: Vec::new(),
+            deprecated: None,
+        },
+    );
+    cfg.templates
+        .insert("local".into(), TemplateDef::Path(root.join("repo/rust")));
+
+    let resolver = TemplateResolver::new(root.join("cache"));
+    let old = resolver
+        .resolve_template_dir_at(&cfg, "rust", "v1")
+        .unwrap();
+    let new = resolver
+        .resolve_template_dir_at(&cfg, "rust", "v2")
+        .unwrap();
+    assert_ne!(old, new);
+    assert_eq!(fs::read_to_string(old.join("hello.txt")).unwrap(), "v1\n");
+    assert_eq!(fs::read_to_string(new.join("hello.txt")).unwrap(), "v2\n");
+
+    // Both refs are worktrees of one shared mirror, checked out side by side.
+    let cached = resolver.verify_cache().unwrap();
+    assert_eq!(cached.iter().filter(|c| c.is_mirror()).count(), 1);
+    assert_eq!(cached.len(), 3, "{cached:?}");
+    assert!(cached.iter().all(|c| c.is_healthy()));
+
+    // A ref deleted upstream is reported as unknown; nothing cached is thrown away.
+    git_ok(&repo_dir, &["tag", "-d", "v1"]);
+    let err = resolver
+        .resolve_template_dir_at(&cfg, "rust", "v1")
+        .unwrap_err();
+    assert!(matches!(err, ResolveError::UnknownRef { ref git_ref, .. } if git_ref == "v1"));
+    assert_eq!(resolver.verify_cache().unwrap().len(), 3);
+    assert!(new.join("hello.txt").is_file());
+
+    let err = resolver
+        .resolve_template_dir_at(&cfg, "local", "v1")
+        .unwrap_err();
+    assert!(matches!(err, ResolveError::NotGitTemplate(ref name) if name == "local"));
 }
 
 #[test]
@@ -180,15 +435,25 @@ fn missing_git_ref_returns_error() {
         TemplateDef::Detailed {
             source: Some("repo".into()),
             path: PathBuf::from("rust"),
+            aliases: Vec::new(),
+            deprecated: None,
         },
     );
 
     let resolver = TemplateResolver::new(root.join("cache"));
     let err = resolver.resolve_template_dir(&cfg, "rust").unwrap_err();
-    match err {
-        ResolveError::GitCommandFailed { .. } => {}
+    match &err {
+        ResolveError::UnknownRef { git_ref, .. } => assert_eq!(git_ref, "definitely-not-a-ref"),
         other => panic!("unexpected error: {other:?}"),
     }
+    assert!(
+        err.to_string()
+            .starts_with("unknown ref 'definitely-not-a-ref' in ")
+    );
+    // The mirror is healthy, so it is kept rather than cloned again.
+    let cached = resolver.verify_cache().unwrap();
+    assert_eq!(cached.len(), 1, "{cached:?}");
+    assert!(cached[0].is_mirror() && cached[0].is_healthy());
 }
 
 #[test]
@@ -211,6 +476,8 @@ fn resolve_errors_on_unknown_source() {
         TemplateDef::Detailed {
             source: Some("missing".into()),
             path: PathBuf::from("x"),
+            aliases: Vec::new(),
+            deprecated: None,
         },
     );
     let err = resolver.resolve_template_dir(&cfg, "t").unwrap_err();
@@ -233,6 +500,8 @@ fn resolve_errors_on_source_repo_missing() {
         TemplateDef::Detailed {
             source: Some("local".into()),
             path: PathBuf::from("x"),
+            aliases: Vec::new(),
+            deprecated: None,
         },
     );
     let err = resolver.resolve_template_dir(&cfg, "t").unwrap_err();
@@ -261,6 +530,8 @@ fn resolve_errors_when_template_path_not_dir() {
         TemplateDef::Detailed {
             source: Some("local".into()),
             path: PathBuf::from("not_a_dir"),
+            aliases: Vec::new(),
+            deprecated: None,
         },
     );
 
@@ -0,0 +1,62 @@
+//! Build metadata for `pinit version`: git commit, build time, target, and cargo features.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo::rerun-if-changed=build.rs");
+    println!("cargo::rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    // Rebuild the metadata when HEAD moves, whether by checkout or by commit.
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo::rerun-if-changed={}", git_dir.join("HEAD").display());
+        if let Some(head_ref) = git(&["rev-parse", "--symbolic-full-name", "HEAD"]) {
+            let ref_file = git_dir.join(head_ref);
+            if ref_file.is_file() {
+                println!("cargo::rerun-if-changed={}", ref_file.display());
+            }
+        }
+    }
+    let commit = git(&["rev-parse", "--short=12", "HEAD"]).unwrap_or_default();
+    println!("cargo::rustc-env=PINIT_GIT_COMMIT={commit}");
+
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible.
+    let epoch = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo::rustc-env=PINIT_BUILD_EPOCH={epoch}");
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(str::to_string))
+        .map(|name| name.to_ascii_lowercase().replace('_', "-"))
+        .collect();
+    features.sort();
+    println!("cargo::rustc-env=PINIT_FEATURES={}", features.join(","));
+
+    for (name, var) in [("PINIT_TARGET", "TARGET"), ("PINIT_PROFILE", "PROFILE")] {
+        let value = std::env::var(var).unwrap_or_default();
+        println!("cargo::rustc-env={name}={value}");
+    }
+}
+
+/// Trimmed stdout of a successful `git` command run in this crate's directory.
+fn git(args: &[&str]) -> Option<String> {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(out.stdout).ok()?.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
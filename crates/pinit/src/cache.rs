@@ -0,0 +1,52 @@
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+
+use pinit::{CacheCommand, CacheVerifyArgs};
+
+pub(crate) fn cmd_cache(config_path: Option<&Path>, command: CacheCommand) -> Result<(), String> {
+    // The cache can be checked without a config; it is only consulted for `cache_dir`.
+    let cfg = pinit_core::config::load_config(config_path)
+        .ok()
+        .map(|(_, cfg)| cfg);
+    let resolver = crate::template_resolver(cfg.as_ref())?;
+    match command {
+        CacheCommand::Verify(args) => cmd_cache_verify(&resolver, &args),
+    }
+}
+
+fn cmd_cache_verify(
+    resolver: &pinit_core::resolve::TemplateResolver,
+    args: &CacheVerifyArgs,
+) -> Result<(), String> {
+    let checkouts = resolver.verify_cache().map_err(|e| e.to_string())?;
+    if checkouts.is_empty() {
+        println!(
+            "no cached git checkouts in {}",
+            resolver.cache_dir().display()
+        );
+        return Ok(());
+    }
+
+    let mut broken = 0;
+    for checkout in &checkouts {
+        let repo = checkout.repo.as_deref().unwrap_or("unknown repo");
+        let Some(problem) = &checkout.problem else {
+            println!("ok: {} ({repo})", checkout.dir.display());
+            continue;
+        };
+        println!("broken: {} ({repo}): {problem}", checkout.dir.display());
+        if args.repair {
+            checkout.remove().map_err(|e| e.to_string())?;
+            println!("  removed; it will be recreated on next use");
+        } else {
+            broken += 1;
+        }
+    }
+    if broken > 0 {
+        return Err(format!(
+            "{broken} cached checkout(s) are broken; run `pinit cache verify --repair` to remove them"
+        ));
+    }
+    Ok(())
+}
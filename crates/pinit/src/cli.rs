@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 
@@ -11,10 +11,22 @@ pub struct Cli {
     #[arg(short = 'v', long = "verbose", action = ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Print only errors (and the summary asked for with --summary line, markdown, or json)
+    #[arg(short = 'q', long = "quiet", global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Also write trace-level logs to FILE, whatever the console verbosity (for bug reports)
+    #[arg(long = "trace-file", global = true, value_name = "FILE")]
+    pub trace_file: Option<PathBuf>,
+
     /// Config file path (overrides default discovery)
     #[arg(long = "config", global = true)]
     pub config: Option<PathBuf>,
 
+    /// Cache directory for git sources and license texts (overrides PINIT_CACHE_DIR and config)
+    #[arg(long = "cache-dir", global = true, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -25,13 +37,165 @@ pub enum Command {
     Apply(ApplyArgs),
 
     /// List available recipes/templates
-    List,
+    List(ListArgs),
 
     /// Create a new project directory from a recipe/template
     New(NewArgs),
 
-    /// Print the CLI version
-    Version,
+    /// Tools for template authors
+    #[command(subcommand)]
+    Template(TemplateCommand),
+
+    /// Inspect the SPDX licenses pinit can render
+    #[command(subcommand)]
+    License(LicenseCommand),
+
+    /// Inspect pinit's cache of git sources
+    #[command(subcommand)]
+    Cache(CacheCommand),
+
+    /// Print the version, build details, and the config and cache paths in use
+    Version(VersionArgs),
+}
+
+#[derive(Args, Debug, Default)]
+pub struct VersionArgs {
+    /// Emit machine-readable JSON instead of text
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ListArgs {
+    /// Emit machine-readable JSON instead of text
+    #[arg(long = "json")]
+    pub json: bool,
+
+    /// Expand targets/recipes into the template stacks they resolve to
+    #[arg(long = "tree")]
+    pub tree: bool,
+
+    /// Show configured sources with their repository, ref, and cache state
+    #[arg(long = "sources", conflicts_with = "tree")]
+    pub sources: bool,
+
+    /// Print every template, target, recipe, and alias name, one per line (for shell completion)
+    #[arg(long = "names", hide = true, conflicts_with_all = ["tree", "sources", "json"])]
+    pub names: bool,
+
+    /// Only show the stack for this template/target/recipe name
+    #[arg(requires = "tree")]
+    pub name: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommand {
+    /// Apply a template into a temp directory and check its manifest assertions
+    Test(TemplateTestArgs),
+
+    /// Apply a template over a fixture and compare with the expected output
+    Snapshot(TemplateSnapshotArgs),
+
+    /// Show what changed in a git-sourced template between two refs
+    Diff(TemplateDiffArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LicenseCommand {
+    /// Render an SPDX license, optionally with its metadata as JSON
+    Render(LicenseRenderArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// Check that every cached git checkout is usable
+    Verify(CacheVerifyArgs),
+}
+
+#[derive(Args, Debug, Default)]
+pub struct CacheVerifyArgs {
+    /// Remove broken checkouts so they are cloned again on next use
+    #[arg(long = "repair")]
+    pub repair: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct LicenseRenderArgs {
+    /// SPDX license identifier, e.g. MIT or Apache-2.0
+    pub spdx: String,
+
+    /// Emit the text and license metadata as JSON
+    #[arg(long = "json")]
+    pub json: bool,
+
+    /// Copyright year (default: the current year, if the license has one)
+    #[arg(long = "year")]
+    pub year: Option<String>,
+
+    /// Copyright holder; fills `fullname`, `copyright holders`, `owner`, and `name of author`
+    #[arg(long = "name")]
+    pub name: Option<String>,
+
+    /// Set an SPDX template variable (repeatable)
+    #[arg(long = "arg", value_name = "KEY=VALUE", action = ArgAction::Append)]
+    pub args: Vec<String>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TemplateTestArgs {
+    /// Template/recipe name from config, or a path to a template directory
+    pub template: String,
+
+    /// Set a template variable (repeatable; overrides manifest test values)
+    #[arg(long = "var", value_name = "KEY=VALUE", action = ArgAction::Append)]
+    pub vars: Vec<String>,
+
+    /// Keep the temp directory and print its path
+    #[arg(long = "keep")]
+    pub keep: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TemplateSnapshotArgs {
+    /// Template/recipe name from config, or a path to a template directory
+    pub template: String,
+
+    /// Fixture directory holding `input/` (optional) and `expected/`
+    #[arg(long = "against", value_name = "FIXTURE_DIR")]
+    pub against: PathBuf,
+
+    /// Set a template variable (repeatable; overrides manifest test values)
+    #[arg(long = "var", value_name = "KEY=VALUE", action = ArgAction::Append)]
+    pub vars: Vec<String>,
+
+    /// Rewrite `expected/` from the current output instead of comparing
+    #[arg(long = "update")]
+    pub update: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TemplateDiffArgs {
+    /// Template name from config (must use a git source)
+    pub name: String,
+
+    /// Git ref to diff from (branch, tag, or commit)
+    pub old_ref: String,
+
+    /// Git ref to diff to (branch, tag, or commit)
+    pub new_ref: String,
+}
+
+/// What `apply` and `new` do with files that already exist in the destination.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OnConflict {
+    /// Attempt an additive merge; files that cannot be merged are left alone
+    Merge,
+    /// Replace the file with the template's version
+    Overwrite,
+    /// Leave the file alone
+    Skip,
+    /// Ask about each file (the default, unless --yes is given)
+    Prompt,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -41,13 +205,26 @@ pub enum OverrideActionArg {
     Skip,
 }
 
-#[derive(Args, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+    /// One line of counts
+    #[default]
+    Text,
+    /// Templates, files grouped by action, and hook results as Markdown
+    Markdown,
+    /// Counts, per-file outcomes, hook results, and phase timings as JSON
+    Json,
+    /// One line of space-separated `key=value` counts, for scripts
+    Line,
+}
+
+#[derive(Args, Debug, Default)]
 pub struct ApplyArgs {
     /// Template/recipe name from config, or a path to a template directory
     pub template: String,
 
-    /// Destination directory (default: current directory)
-    pub dest_dir: Option<PathBuf>,
+    #[command(flatten)]
+    pub dest: DestArg,
 
     /// Print what would change without writing
     #[arg(short = 'n', long = "dry-run")]
@@ -57,16 +234,25 @@ pub struct ApplyArgs {
     #[arg(short = 'y', long = "yes")]
     pub yes: bool,
 
-    /// When a file exists, overwrite it
-    #[arg(long, conflicts_with_all = ["merge", "skip"])]
+    /// What to do when a file exists (default: prompt, or merge with --yes)
+    #[arg(
+        long = "on-conflict",
+        value_enum,
+        value_name = "ACTION",
+        group = "conflict"
+    )]
+    pub on_conflict: Option<OnConflict>,
+
+    /// When a file exists, overwrite it (same as --on-conflict overwrite)
+    #[arg(long, group = "conflict")]
     pub overwrite: bool,
 
-    /// When a file exists, attempt an additive merge (default)
-    #[arg(long, conflicts_with_all = ["overwrite", "skip"])]
+    /// When a file exists, attempt an additive merge (same as --on-conflict merge)
+    #[arg(long, group = "conflict")]
     pub merge: bool,
 
-    /// When a file exists, skip it
-    #[arg(long, conflicts_with_all = ["overwrite", "merge"])]
+    /// When a file exists, skip it (same as --on-conflict skip)
+    #[arg(long, group = "conflict")]
     pub skip: bool,
 
     /// Override template precedence for matching paths (repeatable)
@@ -76,12 +262,113 @@ pub struct ApplyArgs {
     /// Override action for --override patterns (default: overwrite)
     #[arg(long = "override-action", value_enum)]
     pub override_action: Option<OverrideActionArg>,
+
+    /// Refuse templates nested deeper than N directories (default: 32, 0 = unlimited)
+    #[arg(long = "max-depth", value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Refuse templates with more than N files (default: 10000, 0 = unlimited)
+    #[arg(long = "max-files", value_name = "N")]
+    pub max_files: Option<usize>,
+
+    /// Only create missing files; never touch existing ones (no prompts, no merges)
+    #[arg(long = "only-new", conflicts_with_all = ["overwrite", "merge", "overrides"])]
+    pub only_new: bool,
+
+    /// Only update files that already exist; never create new ones
+    #[arg(long = "only-existing", conflicts_with = "only_new")]
+    pub only_existing: bool,
+
+    /// Collect every conflict first, then review and change the answers before anything is written
+    #[arg(long = "review", conflicts_with_all = ["yes", "conflict", "only_new"])]
+    pub review: bool,
+
+    /// Create and check out a new branch (config `apply_branch`) before applying
+    #[arg(long = "branch-per-apply")]
+    pub branch_per_apply: bool,
+
+    /// Summary printed after applying
+    #[arg(long = "summary", value_enum, default_value_t = SummaryFormat::Text)]
+    pub summary: SummaryFormat,
+
+    /// Also write a Markdown summary (for a PR description) to FILE
+    #[arg(long = "report-md", value_name = "FILE")]
+    pub report_md: Option<PathBuf>,
+
+    /// Fail instead of warning when a deprecated template, target, or recipe is used
+    #[arg(long = "strict")]
+    pub strict: bool,
+
+    /// Print how long each phase took (also shown with -v)
+    #[arg(long = "timings")]
+    pub timings: bool,
+
+    /// Report the total bytes written and the largest files
+    #[arg(long = "sizes")]
+    pub sizes: bool,
+}
+
+/// `pinit apply`'s destination: the DEST argument or `--dest`, not both.
+#[derive(Args, Clone, Debug, Default)]
+#[group(id = "dest", multiple = false)]
+pub struct DestArg {
+    /// Destination directory (default: current directory)
+    #[arg(value_name = "DEST")]
+    dest_dir: Option<PathBuf>,
+
+    /// Destination directory, as an option instead of the DEST argument
+    #[arg(long = "dest", id = "dest_flag", value_name = "DIR")]
+    dest_flag: Option<PathBuf>,
+}
+
+impl DestArg {
+    /// The destination directory, however it was given.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dest_dir.as_deref().or(self.dest_flag.as_deref())
+    }
+}
+
+impl From<PathBuf> for DestArg {
+    fn from(dir: PathBuf) -> Self {
+        Self {
+            dest_dir: Some(dir),
+            dest_flag: None,
+        }
+    }
 }
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Default)]
 pub struct NewArgs {
-    pub template: String,
-    pub dir: PathBuf,
+    /// Template/recipe name from config, or a path to a template directory
+    /// (default: config `default_recipe`, when DIR is the only argument);
+    /// with --template, this argument is the directory instead
+    #[arg(value_name = "TEMPLATE", required_unless_present_any = ["list", "dest"])]
+    pub template: Option<String>,
+
+    /// Directory to create
+    #[arg(value_name = "DIR", conflicts_with_all = ["template_flag", "dest"])]
+    pub dir: Option<PathBuf>,
+
+    /// Template to use; the lone positional argument is then the directory
+    #[arg(
+        long = "template",
+        id = "template_flag",
+        value_name = "TEMPLATE",
+        group = "slot"
+    )]
+    pub template_flag: Option<String>,
+
+    /// Directory to create; the lone positional argument is then the template
+    #[arg(long = "dest", value_name = "DIR", group = "slot")]
+    pub dest: Option<PathBuf>,
+
+    /// Show the default template stack `pinit new <DIR>` would use, then exit
+    #[arg(long = "list", conflicts_with_all = ["template", "dir", "template_flag", "dest"])]
+    pub list: bool,
+
+    /// Project name for template variables (default: the directory name)
+    #[arg(long = "name")]
+    pub name: Option<String>,
 
     /// Print what would change without writing
     #[arg(short = 'n', long = "dry-run")]
@@ -91,16 +378,25 @@ pub struct NewArgs {
     #[arg(short = 'y', long = "yes")]
     pub yes: bool,
 
-    /// When a file exists, overwrite it
-    #[arg(long, conflicts_with_all = ["merge", "skip"])]
+    /// What to do when a file exists (default: prompt, or merge with --yes)
+    #[arg(
+        long = "on-conflict",
+        value_enum,
+        value_name = "ACTION",
+        group = "conflict"
+    )]
+    pub on_conflict: Option<OnConflict>,
+
+    /// When a file exists, overwrite it (same as --on-conflict overwrite)
+    #[arg(long, group = "conflict")]
     pub overwrite: bool,
 
-    /// When a file exists, attempt an additive merge (default)
-    #[arg(long, conflicts_with_all = ["overwrite", "skip"])]
+    /// When a file exists, attempt an additive merge (same as --on-conflict merge)
+    #[arg(long, group = "conflict")]
     pub merge: bool,
 
-    /// When a file exists, skip it
-    #[arg(long, conflicts_with_all = ["overwrite", "merge"])]
+    /// When a file exists, skip it (same as --on-conflict skip)
+    #[arg(long, group = "conflict")]
     pub skip: bool,
 
     /// Override template precedence for matching paths (repeatable)
@@ -111,6 +407,14 @@ pub struct NewArgs {
     #[arg(long = "override-action", value_enum)]
     pub override_action: Option<OverrideActionArg>,
 
+    /// Refuse templates nested deeper than N directories (default: 32, 0 = unlimited)
+    #[arg(long = "max-depth", value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Refuse templates with more than N files (default: 10000, 0 = unlimited)
+    #[arg(long = "max-files", value_name = "N")]
+    pub max_files: Option<usize>,
+
     /// Initialize a git repository (default: on)
     #[arg(long = "git", action = ArgAction::SetTrue, conflicts_with = "no_git")]
     pub git: bool,
@@ -122,4 +426,24 @@ pub struct NewArgs {
     /// Initial branch name (default: main)
     #[arg(long = "branch", default_value = "main")]
     pub branch: String,
+
+    /// Run the configured remote command and set the git remote
+    #[arg(long = "create-remote", conflicts_with = "no_git")]
+    pub create_remote: bool,
+
+    /// Commit the new project and push the initial branch to the remote
+    #[arg(long = "push", conflicts_with = "no_git")]
+    pub push: bool,
+
+    /// Fail instead of warning when a deprecated template, target, or recipe is used
+    #[arg(long = "strict")]
+    pub strict: bool,
+
+    /// Print how long each phase took (also shown with -v)
+    #[arg(long = "timings")]
+    pub timings: bool,
+
+    /// Report the total bytes written and the largest files
+    #[arg(long = "sizes")]
+    pub sizes: bool,
 }
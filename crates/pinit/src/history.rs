@@ -0,0 +1,70 @@
+#![forbid(unsafe_code)]
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use pinit::Command;
+use pinit_core::time::format_utc;
+use serde_json::json;
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// A command worth recording in the run history (`apply` and `new`, not dry runs).
+pub(crate) struct Run {
+    command: &'static str,
+    template: String,
+    dest: PathBuf,
+}
+
+impl Run {
+    pub(crate) fn from_command(command: &Command) -> Option<Self> {
+        match command {
+            Command::Apply(args) if !args.dry_run => Some(Self {
+                command: "apply",
+                template: args.template.clone(),
+                dest: args
+                    .dest
+                    .dir()
+                    .map_or_else(|| PathBuf::from("."), Path::to_path_buf),
+            }),
+            Command::New(args) if !args.dry_run => Some(Self {
+                command: "new",
+                template: args.template.clone()?,
+                dest: args.dir.clone()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Append this run to `history.jsonl` in pinit's state directory.
+    ///
+    /// History is best-effort: failures are logged, never reported.
+    pub(crate) fn record(&self) {
+        let Some(dir) = pinit_core::config::state_dir() else {
+            tracing::debug!("no state directory; run not recorded");
+            return;
+        };
+        let path = dir.join(HISTORY_FILE);
+        if let Err(e) = self.append(&path) {
+            tracing::debug!(error = %e, path = %path.display(), "history write failed");
+        }
+    }
+
+    fn append(&self, path: &Path) -> std::io::Result<()> {
+        let dest = fs::canonicalize(&self.dest).unwrap_or_else(|_| self.dest.clone());
+        let entry = json!({
+            "at": format_utc(SystemTime::now()),
+            "command": self.command,
+            "template": self.template,
+            "dest": dest.display().to_string(),
+            "version": env!("CARGO_PKG_VERSION"),
+        });
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{entry}")
+    }
+}
@@ -4,7 +4,11 @@ use clap::CommandFactory;
 
 mod cli;
 
-pub use cli::{ApplyArgs, Cli, Command, NewArgs, OverrideActionArg};
+pub use cli::{
+    ApplyArgs, CacheCommand, CacheVerifyArgs, Cli, Command, DestArg, LicenseCommand,
+    LicenseRenderArgs, ListArgs, NewArgs, OnConflict, OverrideActionArg, SummaryFormat,
+    TemplateCommand, TemplateDiffArgs, TemplateSnapshotArgs, TemplateTestArgs, VersionArgs,
+};
 
 pub fn command() -> clap::Command {
     Cli::command()
@@ -0,0 +1,294 @@
+#![forbid(unsafe_code)]
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use pinit::{LicenseCommand, LicenseRenderArgs};
+use pinit_core::config::{LicenseDef, LicenseDetailed};
+use pinit_core::licensing::{self, LicenseCache, RenderedLicense};
+use serde_json::json;
+
+pub(crate) fn cmd_license(
+    config_path: Option<&Path>,
+    command: LicenseCommand,
+) -> Result<(), String> {
+    // Rendering needs no config; it is only consulted for `cache_dir`.
+    let cfg = pinit_core::config::load_config(config_path)
+        .ok()
+        .map(|(_, cfg)| cfg);
+    let cache_dir = crate::template_resolver(cfg.as_ref())
+        .map(|r| r.cache_dir().to_path_buf())
+        .ok();
+    match command {
+        LicenseCommand::Render(args) => cmd_license_render(cache_dir.as_deref(), &args),
+    }
+}
+
+fn cmd_license_render(cache_dir: Option<&Path>, args: &LicenseRenderArgs) -> Result<(), String> {
+    let mut extra = std::collections::BTreeMap::new();
+    for raw in &args.args {
+        let (key, value) = raw
+            .split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+            .ok_or_else(|| format!("--arg expects KEY=VALUE, got {raw:?}"))?;
+        extra.insert(key.trim().to_string(), value.to_string());
+    }
+    let def = LicenseDef::Detailed(LicenseDetailed {
+        spdx: args.spdx.clone(),
+        output: None,
+        year: args.year.clone(),
+        name: args.name.clone(),
+        args: extra,
+    });
+
+    // A read-only command: the cache may supply texts, but is never written.
+    let cache = cache_dir.map(LicenseCache::in_cache_dir);
+    let info = match &cache {
+        Some(cache) => cache.info(&args.spdx),
+        None => licensing::license_info(&args.spdx),
+    }
+    .map_err(|e| e.to_string())?;
+    let rendered = match &cache {
+        Some(cache) => cache.render_read_only(&args.spdx, &def.template_args()),
+        None => licensing::render_spdx_license(&args.spdx, &def.template_args()),
+    }
+    .map_err(|e| e.to_string())?;
+    let mut text = rendered.text;
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    if !args.json {
+        print!("{text}");
+        return Ok(());
+    }
+    // Placeholders still in the output are the ones nothing filled.
+    let unfilled: Vec<&str> = info
+        .placeholders
+        .iter()
+        .map(String::as_str)
+        .filter(|p| text.contains(&format!("<{p}>")))
+        .collect();
+    let out = json!({
+        "spdx": info.spdx,
+        "name": info.name,
+        "osi_approved": info.osi_approved,
+        "fsf_libre": info.fsf_libre,
+        "deprecated": info.deprecated,
+        "requires": {
+            "year": info.requires_year(),
+            "fullname": info.requires_fullname(),
+        },
+        "placeholders": info.placeholders,
+        "unfilled": unfilled,
+        "text": text,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&out).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
+
+/// Render `spdx` through the license cache under `cache_dir`, or uncached without one.
+pub(crate) fn render(
+    cache_dir: Option<&Path>,
+    spdx: &str,
+    template_args: &std::collections::BTreeMap<String, String>,
+) -> Result<RenderedLicense, String> {
+    let rendered = match cache_dir {
+        Some(dir) => LicenseCache::in_cache_dir(dir).render(spdx, template_args),
+        None => {
+            tracing::debug!("no cache directory; rendering license uncached");
+            licensing::render_spdx_license(spdx, template_args)
+        }
+    };
+    rendered.map_err(|e| e.to_string())
+}
+
+/// Licenses offered by `pinit new` when config sets none: (SPDX id, description).
+pub(crate) const LICENSE_CHOICES: &[(&str, &str)] = &[
+    ("MIT", "permissive, short"),
+    ("Apache-2.0", "permissive, with a patent grant"),
+    ("GPL-3.0-only", "copyleft"),
+];
+
+/// Ask which license a new project should use.
+///
+/// Returns `None` for "proprietary / none" (and on end of input), in which case
+/// no LICENSE file is written.
+pub(crate) fn choose_license(input: &mut dyn BufRead, output: &mut dyn Write) -> Option<String> {
+    let none = LICENSE_CHOICES.len() + 1;
+    loop {
+        let _ = writeln!(output);
+        let _ = writeln!(
+            output,
+            "no license configured; choose one for this project:"
+        );
+        for (i, (spdx, about)) in LICENSE_CHOICES.iter().enumerate() {
+            let _ = writeln!(output, "  {}) {spdx} ({about})", i + 1);
+        }
+        let _ = writeln!(output, "  {none}) proprietary / none");
+        let _ = write!(output, "> [default: {none}] ");
+        let _ = output.flush();
+
+        let mut line = String::new();
+        if input.read_line(&mut line).unwrap_or(0) == 0 {
+            return None;
+        }
+        let choice = line.trim();
+        if choice.is_empty() || choice == none.to_string() {
+            return None;
+        }
+        if let Some((spdx, _)) = choice
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| LICENSE_CHOICES.get(i))
+            .or_else(|| {
+                LICENSE_CHOICES
+                    .iter()
+                    .find(|(spdx, _)| spdx.eq_ignore_ascii_case(choice))
+            })
+        {
+            return Some((*spdx).to_string());
+        }
+        let _ = writeln!(output, "unknown choice: {choice}");
+    }
+}
+
+/// Ask whether to save `spdx` as the default license in `config_path`.
+pub(crate) fn confirm_save(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    spdx: &str,
+    config_path: &Path,
+) -> bool {
+    let _ = write!(
+        output,
+        "save {spdx} as the default license in {}? [y/N] ",
+        config_path.display()
+    );
+    let _ = output.flush();
+    let mut line = String::new();
+    if input.read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Add a top-level `license` key to the config file at `config_path`.
+///
+/// Only called when the config has no license, so the new key cannot collide
+/// with an existing one. Comments and layout are left as is.
+pub(crate) fn save_license(config_path: &Path, spdx: &str) -> Result<(), String> {
+    let content = std::fs::read_to_string(config_path)
+        .map_err(|e| format!("failed to read {}: {e}", config_path.display()))?;
+    let updated = with_license(config_path, &content, spdx);
+
+    // Never leave a config behind that no longer loads.
+    let tmp = config_path.with_extension(format!(
+        "{}.pinit-tmp",
+        config_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+    ));
+    std::fs::write(&tmp, &updated)
+        .map_err(|e| format!("failed to write {}: {e}", tmp.display()))?;
+    let check = pinit_core::config::load_config(Some(&tmp));
+    let check = match check {
+        Ok((_, cfg)) if cfg.license == Some(LicenseDef::Spdx(spdx.to_string())) => Ok(()),
+        Ok(_) => Err(format!(
+            "could not add a license to {}; add `license = \"{spdx}\"` yourself",
+            config_path.display()
+        )),
+        Err(e) => Err(format!(
+            "could not add a license to {}: {e}",
+            config_path.display()
+        )),
+    };
+    if let Err(e) = check {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, config_path)
+        .map_err(|e| format!("failed to write {}: {e}", config_path.display()))
+}
+
+fn with_license(config_path: &Path, content: &str, spdx: &str) -> String {
+    let yaml = config_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+    let mut out = content.to_string();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    if yaml {
+        out.push_str(&format!("license: {spdx}\n"));
+    } else if out.trim().is_empty() {
+        out.push_str(&format!("license = \"{spdx}\"\n"));
+    } else {
+        // A bare key after a table header would land inside that table, so
+        // prepend it to keep it at the top level.
+        out = format!("license = \"{spdx}\"\n\n{out}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choose(input: &str) -> Option<String> {
+        choose_license(&mut input.as_bytes(), &mut Vec::new())
+    }
+
+    #[test]
+    fn choose_license_accepts_numbers_and_ids() {
+        assert_eq!(choose("1\n").as_deref(), Some("MIT"));
+        assert_eq!(choose("apache-2.0\n").as_deref(), Some("Apache-2.0"));
+        assert_eq!(choose("bogus\n3\n").as_deref(), Some("GPL-3.0-only"));
+        assert_eq!(choose("4\n"), None);
+        assert_eq!(choose("\n"), None);
+        assert_eq!(choose(""), None);
+    }
+
+    #[test]
+    fn with_license_keeps_the_key_top_level() {
+        let toml = with_license(
+            Path::new("pinit.toml"),
+            "[templates]\nbase = \"/t\"\n",
+            "MIT",
+        );
+        assert!(toml.starts_with("license = \"MIT\"\n\n[templates]"));
+        let yaml = with_license(Path::new("pinit.yaml"), "templates:\n  base: /t", "MIT");
+        assert!(yaml.ends_with("  base: /t\nlicense: MIT\n"));
+    }
+
+    #[test]
+    fn save_license_round_trips_through_config_loading() {
+        let dir = std::env::temp_dir().join(format!("pinit-license-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, body) in [
+            ("pinit.toml", "# mine\n[templates]\nbase = \"/t\"\n"),
+            ("pinit.yaml", "templates:\n  base: /t\n"),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, body).unwrap();
+            save_license(&path, "MIT").unwrap();
+            let (_, cfg) = pinit_core::config::load_config(Some(&path)).unwrap();
+            assert_eq!(cfg.license, Some(LicenseDef::Spdx("MIT".to_string())));
+            assert!(std::fs::read_to_string(&path).unwrap().contains(body));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn every_choice_is_a_known_spdx_id() {
+        for (spdx, _) in LICENSE_CHOICES {
+            pinit_core::licensing::render_spdx_license(spdx, &Default::default()).unwrap();
+        }
+    }
+}
@@ -0,0 +1,597 @@
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+
+use pinit::ListArgs;
+use pinit_core::config::{Config, ConfigError, ResolvedKind, ResolvedRecipe, Source, TemplateRef};
+use pinit_core::manifest::{self, TemplateManifest};
+use pinit_core::resolve::{SourceLocation, TemplateLocation, TemplateResolver};
+use pinit_core::time::format_utc;
+use serde_json::{Value, json};
+
+/// Metadata gathered for one configured template.
+struct TemplateInfo {
+    name: String,
+    aliases: Vec<String>,
+    deprecated: Option<String>,
+    source: Option<String>,
+    path: String,
+    location: Option<TemplateLocation>,
+    manifest: Option<TemplateManifest>,
+    manifest_error: Option<String>,
+}
+
+pub(crate) fn cmd_list(config_path: Option<&Path>, args: &ListArgs) -> Result<(), String> {
+    let (path, cfg) = match pinit_core::config::load_config(config_path) {
+        Ok(loaded) => loaded,
+        Err(_) if args.names => return Ok(()),
+        Err(ConfigError::NotFound) => {
+            if args.json {
+                println!("{}", json!({ "config": null }));
+            } else {
+                println!("no config found");
+            }
+            return Ok(());
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    tracing::debug!(config = %path.display(), "loaded config");
+
+    if args.names {
+        for name in completion_names(&cfg) {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+
+    let warnings: Vec<String> = cfg.lint().iter().map(ToString::to_string).collect();
+    if !args.json {
+        for warning in &warnings {
+            eprintln!("warning: {warning}");
+        }
+    }
+
+    if args.tree {
+        let stacks = collect_stacks(&cfg, args.name.as_deref())?;
+        if args.json {
+            print_tree_json(&path, &cfg, &stacks, &warnings);
+        } else {
+            print_tree_text(&path, &cfg, &stacks);
+        }
+        return Ok(());
+    }
+
+    let resolver = crate::template_resolver(Some(&cfg)).ok();
+    if args.sources {
+        let sources = collect_source_info(&cfg, resolver.as_ref());
+        if args.json {
+            print_sources_json(&path, &sources, &warnings);
+        } else {
+            print_sources_text(&path, &sources);
+        }
+        return Ok(());
+    }
+    let templates = collect_template_info(&cfg, resolver.as_ref());
+
+    if args.json {
+        print_json(&path, &cfg, &templates, &warnings);
+    } else {
+        print_text(&path, &cfg, &templates);
+    }
+    Ok(())
+}
+
+/// Names `pinit apply`/`new` accept, sorted and deduplicated.
+fn completion_names(cfg: &Config) -> std::collections::BTreeSet<&str> {
+    cfg.templates
+        .keys()
+        .chain(cfg.targets.keys())
+        .chain(cfg.recipes.keys())
+        .map(String::as_str)
+        .chain(cfg.aliases().map(|(alias, _)| alias))
+        .collect()
+}
+
+/// `name [aliases: a, b]`, or just `name`.
+fn with_aliases(name: &str, aliases: &[String]) -> String {
+    if aliases.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name} [aliases: {}]", aliases.join(", "))
+    }
+}
+
+/// A configured source, where it points, and the templates that use it.
+struct SourceInfo<'a> {
+    source: &'a Source,
+    location: Option<Result<SourceLocation, String>>,
+    templates: Vec<&'a str>,
+}
+
+fn collect_source_info<'a>(
+    cfg: &'a Config,
+    resolver: Option<&TemplateResolver>,
+) -> Vec<SourceInfo<'a>> {
+    cfg.sources
+        .iter()
+        .map(|source| SourceInfo {
+            source,
+            location: resolver.map(|r| r.locate_source(source).map_err(|e| e.to_string())),
+            templates: cfg
+                .templates
+                .iter()
+                .filter(|(_, def)| !def.path().is_absolute() && def.source() == Some(&source.name))
+                .map(|(name, _)| name.as_str())
+                .collect(),
+        })
+        .collect()
+}
+
+fn print_sources_text(path: &Path, sources: &[SourceInfo<'_>]) {
+    println!("config: {}", path.display());
+    if sources.is_empty() {
+        println!("\nno sources configured");
+        return;
+    }
+
+    println!("\nsources:");
+    for info in sources {
+        match &info.location {
+            Some(Ok(SourceLocation::Local { root })) => {
+                let state = if root.is_dir() { "" } else { " (missing)" };
+                println!("  {} (local)", info.source.name);
+                println!("    path: {}{state}", root.display());
+            }
+            Some(Ok(SourceLocation::Git {
+                repo,
+                git_ref,
+                subdir,
+                commit,
+                fetched_at,
+                ..
+            })) => {
+                println!("  {} (git)", info.source.name);
+                println!("    repo: {repo}");
+                println!("    ref: {git_ref}");
+                if let Some(subdir) = subdir {
+                    println!("    subdir: {}", subdir.display());
+                }
+                match commit {
+                    Some(commit) => {
+                        let short = commit.get(..12).unwrap_or(commit);
+                        let fetched = fetched_at
+                            .map(|t| format!(", fetched {}", format_utc(t)))
+                            .unwrap_or_default();
+                        println!("    cache: {short}{fetched}");
+                    }
+                    None => println!("    cache: not cached"),
+                }
+            }
+            Some(Err(e)) => {
+                println!("  {}", info.source.name);
+                println!("    error: {e}");
+            }
+            None => println!("  {} (no cache directory)", info.source.name),
+        }
+        if !info.templates.is_empty() {
+            println!("    templates: {}", info.templates.join(", "));
+        }
+    }
+}
+
+fn print_sources_json(path: &Path, sources: &[SourceInfo<'_>], warnings: &[String]) {
+    let sources: Vec<Value> = sources
+        .iter()
+        .map(|info| {
+            let mut out = match &info.location {
+                Some(Ok(SourceLocation::Local { root })) => json!({
+                    "kind": "local",
+                    "path": root.display().to_string(),
+                    "available": root.is_dir(),
+                }),
+                Some(Ok(SourceLocation::Git {
+                    repo,
+                    git_ref,
+                    subdir,
+                    checkout,
+                    commit,
+                    fetched_at,
+                })) => json!({
+                    "kind": "git",
+                    "repo": repo,
+                    "ref": git_ref,
+                    "subdir": subdir.as_ref().map(|s| s.display().to_string()),
+                    "cache": {
+                        "dir": checkout.display().to_string(),
+                        "cached": commit.is_some(),
+                        "commit": commit,
+                        "fetched_at": fetched_at.map(format_utc),
+                    },
+                }),
+                Some(Err(e)) => json!({ "error": e }),
+                None => json!({}),
+            };
+            out["name"] = json!(info.source.name);
+            out["templates"] = json!(info.templates);
+            out
+        })
+        .collect();
+    let out = json!({
+        "config": path.display().to_string(),
+        "sources": sources,
+        "warnings": warnings,
+    });
+    println!("{out:#}");
+}
+
+fn collect_template_info(cfg: &Config, resolver: Option<&TemplateResolver>) -> Vec<TemplateInfo> {
+    cfg.templates
+        .iter()
+        .map(|(name, def)| {
+            let location = resolver.and_then(|r| r.locate_template(cfg, name).ok());
+            let (manifest, manifest_error) = match &location {
+                Some(loc) if loc.is_available() => match manifest::load_manifest(loc.dir()) {
+                    Ok(m) => (m, None),
+                    Err(e) => (None, Some(e.to_string())),
+                },
+                _ => (None, None),
+            };
+            TemplateInfo {
+                name: name.clone(),
+                aliases: def.aliases().to_vec(),
+                deprecated: def.deprecated().map(str::to_string),
+                source: def.source().map(str::to_string),
+                path: def.path().display().to_string(),
+                location,
+                manifest,
+                manifest_error,
+            }
+        })
+        .collect()
+}
+
+fn print_text(path: &Path, cfg: &Config, templates: &[TemplateInfo]) {
+    println!("config: {}", path.display());
+
+    if !templates.is_empty() {
+        println!("\ntemplates:");
+        for info in templates {
+            let source = info.source.as_deref().unwrap_or("-");
+            println!(
+                "  {} (source: {source}, path: {})",
+                with_aliases(&info.name, &info.aliases),
+                info.path
+            );
+            if let Some(note) = &info.deprecated {
+                println!("    deprecated: {note}");
+            }
+            if let Some(desc) = info.manifest.as_ref().and_then(|m| m.description.as_ref()) {
+                println!("    description: {desc}");
+            }
+            if let Some(m) = &info.manifest
+                && !m.variables.is_empty()
+            {
+                println!("    variables: {}", variable_summary(m));
+            }
+            if let Some(loc) = &info.location {
+                println!("    location: {}", location_summary(loc));
+            }
+            if let Some(err) = &info.manifest_error {
+                println!("    manifest error: {err}");
+            }
+        }
+    }
+
+    if !cfg.targets.is_empty() {
+        println!("\ntargets:");
+        for (name, stack) in &cfg.targets {
+            println!(
+                "  {} = {}",
+                with_aliases(name, stack.aliases()),
+                join_refs(stack.templates())
+            );
+            if let Some(note) = stack.deprecated() {
+                println!("    deprecated: {note}");
+            }
+        }
+    }
+
+    if !cfg.recipes.is_empty() {
+        println!("\nrecipes:");
+        for (name, recipe) in &cfg.recipes {
+            let tmpl = if recipe.templates.is_empty() {
+                "-".to_string()
+            } else {
+                join_refs(&recipe.templates)
+            };
+            println!(
+                "  {} (templates: {tmpl}, filesets: {})",
+                with_aliases(name, &recipe.aliases),
+                recipe.files.len()
+            );
+            if let Some(note) = &recipe.deprecated {
+                println!("    deprecated: {note}");
+            }
+        }
+    }
+}
+
+fn join_refs(templates: &[TemplateRef]) -> String {
+    templates
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn ref_names(templates: &[TemplateRef]) -> Vec<&str> {
+    templates.iter().map(TemplateRef::name).collect()
+}
+
+fn variable_summary(manifest: &TemplateManifest) -> String {
+    manifest
+        .variables
+        .iter()
+        .map(|(name, var)| match (&var.default, var.required) {
+            (_, true) => format!("{name} (required)"),
+            (Some(default), false) => format!("{name} = {default}"),
+            (None, false) => name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn location_summary(location: &TemplateLocation) -> String {
+    match location {
+        TemplateLocation::Local { dir } => {
+            let state = if dir.is_dir() { "" } else { " (missing)" };
+            format!("local {}{state}", dir.display())
+        }
+        TemplateLocation::Git {
+            repo,
+            git_ref,
+            commit,
+            ..
+        } => match commit {
+            Some(commit) => {
+                let short = commit.get(..12).unwrap_or(commit);
+                format!("git {repo}@{git_ref} (cached {short})")
+            }
+            None => format!("git {repo}@{git_ref} (not cached)"),
+        },
+    }
+}
+
+fn print_json(path: &Path, cfg: &Config, templates: &[TemplateInfo], warnings: &[String]) {
+    let templates: Vec<Value> = templates.iter().map(template_json).collect();
+    let targets: Vec<Value> = cfg
+        .targets
+        .iter()
+        .map(|(name, stack)| {
+            json!({
+                "name": name,
+                "aliases": stack.aliases(),
+                "deprecated": stack.deprecated(),
+                "templates": ref_names(stack.templates()),
+            })
+        })
+        .collect();
+    let recipes: Vec<Value> = cfg
+        .recipes
+        .iter()
+        .map(|(name, recipe)| {
+            json!({
+                "name": name,
+                "aliases": recipe.aliases,
+                "deprecated": recipe.deprecated,
+                "templates": ref_names(&recipe.templates),
+                "filesets": recipe.files.len(),
+            })
+        })
+        .collect();
+
+    let out = json!({
+        "config": path.display().to_string(),
+        "templates": templates,
+        "targets": targets,
+        "recipes": recipes,
+        "warnings": warnings,
+    });
+    println!("{out:#}");
+}
+
+fn template_json(info: &TemplateInfo) -> Value {
+    let variables: serde_json::Map<String, Value> = info
+        .manifest
+        .iter()
+        .flat_map(|m| m.variables.iter())
+        .map(|(name, var)| {
+            (
+                name.clone(),
+                json!({
+                    "description": var.description,
+                    "default": var.default,
+                    "required": var.required,
+                }),
+            )
+        })
+        .collect();
+    let location = info.location.as_ref().map(|loc| match loc {
+        TemplateLocation::Local { dir } => json!({
+            "kind": "local",
+            "dir": dir.display().to_string(),
+            "available": dir.is_dir(),
+        }),
+        TemplateLocation::Git {
+            repo,
+            git_ref,
+            dir,
+            commit,
+        } => json!({
+            "kind": "git",
+            "repo": repo,
+            "ref": git_ref,
+            "dir": dir.display().to_string(),
+            "cached": commit.is_some(),
+            "commit": commit,
+        }),
+    });
+
+    json!({
+        "name": info.name,
+        "aliases": info.aliases,
+        "deprecated": info.deprecated,
+        "source": info.source,
+        "path": info.path,
+        "description": info.manifest.as_ref().and_then(|m| m.description.clone()),
+        "variables": variables,
+        "location": location,
+        "manifest_error": info.manifest_error,
+    })
+}
+
+/// `pinit new --list`: the configured `default_recipe` and its stack.
+pub(crate) fn cmd_new_list(config_path: Option<&Path>) -> Result<(), String> {
+    let (path, cfg) = pinit_core::config::load_config(config_path).map_err(|e| e.to_string())?;
+    let Some(name) = cfg.default_recipe.as_deref() else {
+        println!("config: {}", path.display());
+        println!("no default_recipe set; name one with `pinit new <TEMPLATE> <DIR>`");
+        return Ok(());
+    };
+    println!("default_recipe: {name}");
+    let stacks = collect_stacks(&cfg, Some(name))?;
+    print_tree_text(&path, &cfg, &stacks);
+    Ok(())
+}
+
+/// Stacks to show in tree mode: one name, or every target and recipe.
+fn collect_stacks(cfg: &Config, name: Option<&str>) -> Result<Vec<ResolvedRecipe>, String> {
+    if let Some(name) = name {
+        let resolved = cfg
+            .resolve_recipe(name)
+            .ok_or_else(|| format!("unknown template: {name}"))?;
+        return Ok(vec![resolved]);
+    }
+
+    Ok(cfg
+        .targets
+        .keys()
+        .chain(cfg.recipes.keys())
+        .filter_map(|name| cfg.resolve_recipe(name))
+        .collect())
+}
+
+fn kind_label(kind: ResolvedKind) -> &'static str {
+    match kind {
+        ResolvedKind::Recipe => "recipe",
+        ResolvedKind::Target => "target",
+        ResolvedKind::Template => "template",
+    }
+}
+
+fn stack_entry_label(cfg: &Config, stack: &ResolvedRecipe, entry: &TemplateRef) -> String {
+    let template = entry.name();
+    let mut label = match cfg.templates.get(template) {
+        Some(def) => format!(
+            "{template} (source: {}, path: {})",
+            def.source().unwrap_or("-"),
+            def.path().display()
+        ),
+        None => format!("{template} (undefined)"),
+    };
+    if stack.kind == ResolvedKind::Template && template != stack.name {
+        label.push_str(" [base_template]");
+    }
+    if let Some(prefix) = entry.dest_prefix() {
+        label.push_str(&format!(" -> {}", prefix.display()));
+    }
+    label
+}
+
+fn print_tree_text(path: &Path, cfg: &Config, stacks: &[ResolvedRecipe]) {
+    println!("config: {}", path.display());
+    for stack in stacks {
+        println!("\n{} ({})", stack.name, kind_label(stack.kind));
+        if stack.templates.is_empty() && stack.files.is_empty() {
+            println!("  (empty)");
+        }
+        for (idx, template) in stack.templates.iter().enumerate() {
+            println!("  {}. {}", idx + 1, stack_entry_label(cfg, stack, template));
+        }
+        for files in &stack.files {
+            let include = if files.include.is_empty() {
+                "*".to_string()
+            } else {
+                files.include.join(", ")
+            };
+            match &files.dest_prefix {
+                Some(prefix) => println!(
+                    "  files: {} (include: {include}) -> {}",
+                    files.root.display(),
+                    prefix.display()
+                ),
+                None => println!("  files: {} (include: {include})", files.root.display()),
+            }
+        }
+        for (from, to) in &stack.map {
+            println!("  map: {} -> {}", from.display(), to.display());
+        }
+        if !stack.overrides.is_empty() {
+            println!("  overrides: {}", stack.overrides.len());
+        }
+    }
+}
+
+fn print_tree_json(path: &Path, cfg: &Config, stacks: &[ResolvedRecipe], warnings: &[String]) {
+    let stacks: Vec<Value> = stacks
+        .iter()
+        .map(|stack| {
+            let templates: Vec<Value> = stack
+                .templates
+                .iter()
+                .map(|entry| {
+                    let name = entry.name();
+                    let def = cfg.templates.get(name);
+                    json!({
+                        "name": name,
+                        "defined": def.is_some(),
+                        "source": def.and_then(|d| d.source()),
+                        "path": def.map(|d| d.path().display().to_string()),
+                        "base_template": stack.kind == ResolvedKind::Template && name != stack.name,
+                        "dest_prefix": entry.dest_prefix().map(|p| p.display().to_string()),
+                    })
+                })
+                .collect();
+            let files: Vec<Value> = stack
+                .files
+                .iter()
+                .map(|files| {
+                    json!({
+                        "root": files.root.display().to_string(),
+                        "include": files.include,
+                        "dest_prefix": files.dest_prefix.as_ref().map(|p| p.display().to_string()),
+                    })
+                })
+                .collect();
+            json!({
+                "name": stack.name,
+                "kind": kind_label(stack.kind),
+                "templates": templates,
+                "files": files,
+                "map": stack
+                    .map
+                    .iter()
+                    .map(|(from, to)| (from.display().to_string(), Value::String(to.display().to_string())))
+                    .collect::<serde_json::Map<_, _>>(),
+                "overrides": stack.overrides.len(),
+            })
+        })
+        .collect();
+
+    let out = json!({
+        "config": path.display().to_string(),
+        "stacks": stacks,
+        "warnings": warnings,
+    });
+    println!("{out:#}");
+}
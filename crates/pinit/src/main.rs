@@ -1,19 +1,65 @@
 #![forbid(unsafe_code)]
 
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process::Command as ProcessCommand;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
+use clap::error::ErrorKind;
 use clap::{CommandFactory, Parser};
-use pinit::{ApplyArgs, Cli, Command, NewArgs, OverrideActionArg};
-use pinit_core::config::{HookDef, HookRunOn, HookSet, LicenseDef, OverrideAction, OverrideRule};
-use pinit_core::resolve::ResolvedTemplate;
-use pinit_core::{ExistingFileAction, ExistingFileDecider, ExistingFileDecisionContext};
+use pinit::{ApplyArgs, Cli, Command, NewArgs, OnConflict, OverrideActionArg, SummaryFormat};
+use pinit_core::config::{
+    BinaryAction, Config, DirtyDestAction, HookDef, HookRunOn, HookSet, LicenseConflictAction,
+    LicenseDef, NestedRepoAction, OverrideAction, OverrideRule, RemoteDef,
+};
+use pinit_core::glob::glob_match;
+use pinit_core::resolve::{ResolvedTemplate, TemplateResolver};
+use pinit_core::timing::{Phase, Timings};
+use pinit_core::{
+    EntryOutcome, ExistingFileAction, ExistingFileDecider, ExistingFileDecisionContext,
+    MergeAttribute, Warning, WarningKind,
+};
 use similar::TextDiff;
 use tracing_subscriber::EnvFilter;
 
+/// `eprintln!` for status messages, which `--quiet` leaves out.
+macro_rules! note {
+    ($($arg:tt)*) => {
+        if !$crate::quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+mod cache;
+mod history;
+mod license;
+mod list;
+mod panic;
+mod remote;
+mod review;
+mod style;
+mod summary;
+mod template;
+mod update;
+mod version;
+mod worktree;
+
+use summary::{HookOutcome, HookResult};
+
 fn main() {
+    panic::install();
+    update::run_worker_if_requested();
     let cli = Cli::parse();
-    init_tracing(cli.verbose);
+    QUIET.store(cli.quiet, Ordering::Relaxed);
+    VERBOSE.store(cli.verbose > 0, Ordering::Relaxed);
+    if let Err(message) = init_tracing(cli.verbose, cli.quiet, cli.trace_file.as_deref()) {
+        eprintln!("error: {message}");
+        std::process::exit(1);
+    }
 
     let Some(command) = cli.command else {
         let _ = Cli::command().print_help();
@@ -21,23 +67,95 @@ fn main() {
         std::process::exit(2);
     };
 
-    let result = match command {
-        Command::Apply(args) => cmd_apply(cli.config.as_deref(), args),
-        Command::List => cmd_list(cli.config.as_deref()),
-        Command::New(args) => cmd_new(cli.config.as_deref(), args),
-        Command::Version => {
-            println!("pinit {}", env!("CARGO_PKG_VERSION"));
-            Ok(())
+    if let Some(dir) = cli.cache_dir {
+        let _ = CACHE_DIR.set(dir);
+    }
+    let update_check = if cli.quiet {
+        None
+    } else {
+        update::start(cli.config.as_deref())
+    };
+
+    let command = match command {
+        Command::Apply(mut args) => {
+            args.on_conflict = on_conflict(args.on_conflict, args.overwrite, args.merge, args.skip);
+            check_on_conflict("apply", args.on_conflict, args.yes, args.only_new);
+            Ok(Command::Apply(args))
+        }
+        Command::New(mut args) => {
+            args.on_conflict = on_conflict(args.on_conflict, args.overwrite, args.merge, args.skip);
+            check_on_conflict("new", args.on_conflict, args.yes, false);
+            resolve_new_args(cli.config.as_deref(), args).map(Command::New)
         }
+        command => Ok(command),
     };
+    let run = command.as_ref().ok().and_then(history::Run::from_command);
+    let result = command.and_then(|command| match command {
+        Command::Apply(mut args) => {
+            args.timings |= cli.verbose > 0;
+            cmd_apply(cli.config.as_deref(), args)
+        }
+        Command::List(args) => list::cmd_list(cli.config.as_deref(), &args),
+        Command::New(mut args) => {
+            args.timings |= cli.verbose > 0;
+            cmd_new(cli.config.as_deref(), args)
+        }
+        Command::Template(command) => template::cmd_template(cli.config.as_deref(), command),
+        Command::License(command) => license::cmd_license(cli.config.as_deref(), command),
+        Command::Cache(command) => cache::cmd_cache(cli.config.as_deref(), command),
+        Command::Version(args) => version::cmd_version(cli.config.as_deref(), &args),
+    });
 
     if let Err(message) = result {
         eprintln!("error: {message}");
         std::process::exit(1);
     }
+    if let Some(run) = run {
+        run.record();
+    }
+
+    if let Some(check) = update_check {
+        check.finish();
+    }
+}
+
+/// `--cache-dir`, set once at startup before any command runs.
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// `--quiet`, set once at startup before any command runs.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Any `-v`, set once at startup; lists each file in the apply summary.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
 }
 
-fn init_tracing(verbosity: u8) {
+/// Resolver for pinit's cache: `--cache-dir`, then `PINIT_CACHE_DIR`, then
+/// the config's `cache_dir`, then the platform default.
+pub(crate) fn template_resolver(cfg: Option<&Config>) -> Result<TemplateResolver, String> {
+    if let Some(dir) = CACHE_DIR.get() {
+        return Ok(TemplateResolver::new(dir.clone()));
+    }
+    match cfg {
+        Some(cfg) => TemplateResolver::for_config(cfg),
+        None => TemplateResolver::with_default_cache(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Console logging from `-v`/`-q` (or `PINIT_LOG`), plus every trace-level event in
+/// `trace_file` when `--trace-file` is given.
+fn init_tracing(verbosity: u8, quiet: bool, trace_file: Option<&Path>) -> Result<(), String> {
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::prelude::*;
+
     let default_level = match verbosity {
         0 => "warn",
         1 => "info",
@@ -45,238 +163,480 @@ fn init_tracing(verbosity: u8) {
         _ => "trace",
     };
 
-    let default_filter = format!("warn,pinit={default_level},pinit_core={default_level}");
+    let default_filter = if quiet {
+        "error".to_string()
+    } else {
+        format!("warn,pinit={default_level},pinit_core={default_level}")
+    };
 
     let filter = EnvFilter::try_from_env("PINIT_LOG")
         .or_else(|_| EnvFilter::try_from_default_env())
         .unwrap_or_else(|_| EnvFilter::new(default_filter));
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
+    let console = tracing_subscriber::fmt::layer()
         .with_target(false)
         .with_level(true)
+        .with_ansi(style::enabled(style::Stream::Stdout))
         .compact()
+        .with_filter(filter);
+
+    let file = match trace_file {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .map_err(|e| format!("failed to create trace file {}: {e}", path.display()))?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(std::sync::Mutex::new(file))
+                .with_ansi(false)
+                .with_thread_names(true)
+                .with_file(true)
+                .with_line_number(true)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_filter(LevelFilter::TRACE);
+            Some(layer)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(file)
         .init();
+    tracing::trace!(
+        version = env!("CARGO_PKG_VERSION"),
+        command = %panic::command_line(),
+        "pinit started"
+    );
+    Ok(())
 }
 
 fn cmd_apply(config_path: Option<&std::path::Path>, args: ApplyArgs) -> Result<(), String> {
-    let dest_for_log = args
-        .dest_dir
-        .as_deref()
-        .unwrap_or_else(|| std::path::Path::new("."))
-        .display()
-        .to_string();
-    tracing::debug!(template = %args.template, dest_dir = %dest_for_log, dry_run = args.dry_run, "apply");
+    let dest_dir = args
+        .dest
+        .dir()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+    tracing::debug!(template = %args.template, dest_dir = %dest_dir.display(), dry_run = args.dry_run, "apply");
 
-    let dest_dir = args.dest_dir.unwrap_or_else(|| PathBuf::from("."));
-
-    let default_action = if args.overwrite {
-        ExistingFileAction::Overwrite
-    } else if args.skip {
-        ExistingFileAction::Skip
-    } else {
-        ExistingFileAction::Merge
-    };
+    let default_action = default_action(args.on_conflict)?;
 
     let resolved = resolve_template_stack(config_path, &args.template)?;
+    let mut warnings = check_deprecations(&resolved, args.strict)?;
     let overrides = combined_overrides(&resolved, &args.overrides, args.override_action);
     let mut decider = CliDecider::new(
         default_action,
-        args.yes || args.overwrite || args.merge || args.skip,
+        non_interactive(args.yes, args.on_conflict),
         overrides,
-    );
+    )
+    .with_explicit_action(explicit_action(args.on_conflict))
+    .with_binary_action(resolved.binary_action)
+    .with_license_conflict(resolved.license_conflict)
+    .with_apply_lockfiles(resolved.apply_lockfiles)
+    .with_template_policies(resolved.policies.clone())
+    .with_only_new(args.only_new);
+
+    let want_markdown = args.report_md.is_some() || args.summary == SummaryFormat::Markdown;
+    let options = pinit_core::ApplyOptions {
+        only_existing: args.only_existing,
+        // The Markdown summary lists pruned directories with their sizes.
+        count_pruned_files: want_markdown,
+        ..base_apply_options(args.dry_run, args.max_depth, args.max_files)
+    };
+    let generated_options = pinit_core::ApplyOptions {
+        dry_run: args.dry_run,
+        only_existing: args.only_existing,
+        ..Default::default()
+    };
 
-    let mut report = apply_template_stack(&resolved, &dest_dir, args.dry_run, &mut decider)?;
+    if !args.dry_run {
+        warnings.extend(worktree::check_dirty(
+            &dest_dir,
+            resolved.dirty_dest,
+            &args.template,
+        )?);
+    }
 
-    report = maybe_apply_license(
-        resolved.license.as_ref(),
-        &dest_dir,
-        pinit_core::ApplyOptions {
-            dry_run: args.dry_run,
-            ..Default::default()
-        },
-        &mut decider,
-        report,
-    )?;
+    if args.review {
+        // A dry pass finds the conflicts; nothing is written until the queue is applied.
+        // The overlay lets later templates in a stack see what earlier ones would write.
+        decider.review = review::Review::Collect(Vec::new());
+        let overlay = pinit_core::DryRunOverlay::new();
+        apply_all(
+            &resolved,
+            &dest_dir,
+            &pinit_core::ApplyOptions {
+                dry_run: true,
+                dry_run_overlay: Some(overlay.clone()),
+                ..options.clone()
+            },
+            pinit_core::ApplyOptions {
+                dry_run: true,
+                dry_run_overlay: Some(overlay),
+                ..generated_options.clone()
+            },
+            &mut decider,
+        )?;
+        decider.merge_unavailable.clear();
+        let decisions = match std::mem::take(&mut decider.review) {
+            review::Review::Collect(conflicts) if !conflicts.is_empty() => {
+                review::run_queue(conflicts)?
+            }
+            _ => BTreeMap::new(),
+        };
+        decider.review = review::Review::Decided(decisions);
+    }
 
-    run_hooks(
-        "after_recipe",
-        &resolved.recipe_hooks.after_recipe,
-        &dest_dir,
-        RunMode::Update,
-        args.dry_run,
-        resolved.recipe_name.as_deref(),
-    )?;
-    run_hooks(
-        "after_all",
-        &resolved.hooks.after_all,
+    let branch = if args.branch_per_apply {
+        let name_template = resolved
+            .apply_branch
+            .as_deref()
+            .unwrap_or(worktree::DEFAULT_APPLY_BRANCH);
+        Some(worktree::create_apply_branch(
+            &dest_dir,
+            name_template,
+            &args.template,
+            args.dry_run,
+        )?)
+    } else {
+        None
+    };
+
+    let report = apply_all(
+        &resolved,
         &dest_dir,
-        RunMode::Update,
-        args.dry_run,
-        resolved.recipe_name.as_deref(),
+        &options,
+        generated_options,
+        &mut decider,
     )?;
+    if !decider.unreviewed.is_empty() {
+        let paths: Vec<String> = decider
+            .unreviewed
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(format!(
+            "conflicts that were not in the review queue were left unchanged: {}",
+            paths.join(", ")
+        ));
+    }
 
-    print_apply_summary(args.dry_run, report);
+    let mut timings = resolved.timings.clone();
+    let mut hooks = timings.time(Phase::Hooks, || {
+        run_hooks(
+            "after_recipe",
+            &resolved.recipe_hooks.after_recipe,
+            &dest_dir,
+            RunMode::Update,
+            args.dry_run,
+            resolved.recipe_name.as_deref(),
+        )
+    })?;
+    hooks.extend(timings.time(Phase::Hooks, || {
+        run_hooks(
+            "after_all",
+            &resolved.hooks.after_all,
+            &dest_dir,
+            RunMode::Update,
+            args.dry_run,
+            resolved.recipe_name.as_deref(),
+        )
+    })?);
+    timings.absorb(&report.timings);
+    let warnings = collect_warnings(warnings, &report, &decider.overrides, &hooks);
+
+    let summary = summary::ApplySummary {
+        template: &args.template,
+        dry_run: args.dry_run,
+        branch: branch.as_deref(),
+        templates: if want_markdown || args.summary == SummaryFormat::Json {
+            resolved
+                .templates
+                .iter()
+                .map(summary::AppliedTemplate::from_resolved)
+                .collect()
+        } else {
+            Vec::new()
+        },
+        report: &report,
+        hooks: &hooks,
+        merge_unavailable: &decider.merge_unavailable,
+        warnings: &warnings,
+        timings: &timings,
+        sizes: args.sizes,
+    };
+    let markdown = want_markdown.then(|| summary.markdown());
+    if let (Some(path), Some(markdown)) = (&args.report_md, &markdown) {
+        std::fs::write(path, markdown)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    }
+    match (args.summary, markdown) {
+        (SummaryFormat::Markdown, Some(markdown)) => print!("{markdown}"),
+        (SummaryFormat::Json, _) => println!("{:#}", summary.json()),
+        (SummaryFormat::Line, _) => println!("{}", summary.line()),
+        _ => {
+            print_apply_summary(args.dry_run, &report, &decider.merge_unavailable);
+            print_sizes(args.sizes, args.dry_run, &report);
+        }
+    }
+    print_warnings(&warnings);
+    print_timings(args.timings, &timings);
     Ok(())
 }
 
 fn cmd_new(config_path: Option<&std::path::Path>, args: NewArgs) -> Result<(), String> {
+    if args.list {
+        return list::cmd_new_list(config_path);
+    }
+    let (Some(template), Some(dir)) = (args.template.clone(), args.dir.clone()) else {
+        return Err("pinit new needs a template and a directory".to_string());
+    };
     tracing::debug!(
-        template = %args.template,
-        dir = %args.dir.display(),
+        template = %template,
+        dir = %dir.display(),
         dry_run = args.dry_run,
         git = %(!args.no_git),
         branch = %args.branch,
         "new"
     );
 
-    if args.dry_run {
-        let default_action = if args.overwrite {
-            ExistingFileAction::Overwrite
-        } else if args.skip {
-            ExistingFileAction::Skip
-        } else {
-            ExistingFileAction::Merge
-        };
+    let project_name = project_name_for(&dir, args.name.as_deref())?;
+    tracing::debug!(project_name = %project_name, "project variables");
+    let variables = pinit_core::vars::project_variables(&project_name);
+
+    let default_action = default_action(args.on_conflict)?;
+    if !args.dry_run {
+        create_empty_dir(&dir)?;
+    }
+
+    let mut resolved = resolve_template_stack(config_path, &template)?;
+    let warnings = check_deprecations(&resolved, args.strict)?;
+    if args.create_remote {
+        remote_config(&resolved)?;
+    }
+    if !args.dry_run && resolved.license.is_none() && !args.yes && std::io::stdin().is_terminal() {
+        resolved.license = prompt_for_license(resolved.config_path.as_deref())?;
+    }
+
+    let overrides = combined_overrides(&resolved, &args.overrides, args.override_action);
+    let mut decider = CliDecider::new(
+        default_action,
+        args.dry_run || non_interactive(args.yes, args.on_conflict),
+        overrides,
+    )
+    .with_explicit_action(explicit_action(args.on_conflict))
+    .with_binary_action(resolved.binary_action)
+    .with_license_conflict(resolved.license_conflict)
+    .with_apply_lockfiles(resolved.apply_lockfiles)
+    .with_template_policies(resolved.policies.clone());
+    let (report, hooks, timings) = init_project(
+        &resolved,
+        &dir,
+        &args,
+        &variables,
+        &mut decider,
+        args.dry_run,
+    )?;
+    let warnings = collect_warnings(warnings, &report, &decider.overrides, &hooks);
+
+    if args.create_remote {
+        let remote = remote_config(&resolved)?;
+        remote::create_remote(remote, &dir, &variables, args.dry_run)?;
+    }
+    if args.push {
+        remote::push_initial(
+            resolved.remote.as_ref(),
+            &dir,
+            &args.branch,
+            &variables,
+            args.dry_run,
+        )?;
+    }
+
+    print_apply_summary(args.dry_run, &report, &decider.merge_unavailable);
+    print_sizes(args.sizes, args.dry_run, &report);
+    print_warnings(&warnings);
+    print_timings(args.timings, &timings);
+    Ok(())
+}
+
+/// Create `dir` for `pinit new`, or accept it if it is an empty directory already.
+fn create_empty_dir(dir: &Path) -> Result<(), String> {
+    if !dir.exists() {
+        return std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()));
+    }
+    let meta = std::fs::metadata(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    if !meta.is_dir() {
+        return Err(format!("destination is not a directory: {}", dir.display()));
+    }
+    let mut iter = std::fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    if iter.next().is_some() {
+        return Err(format!(
+            "destination already exists and is not empty: {}",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
 
-        let resolved = resolve_template_stack(config_path, &args.template)?;
-        let overrides = combined_overrides(&resolved, &args.overrides, args.override_action);
-        let mut decider = CliDecider::new(default_action, true, overrides);
+/// The hooks, `git init`, and the apply for `pinit new`; under `dry_run`, only what they would do.
+fn init_project(
+    resolved: &TemplateResolution,
+    dir: &Path,
+    args: &NewArgs,
+    variables: &pinit_core::vars::Variables,
+    decider: &mut CliDecider,
+    dry_run: bool,
+) -> Result<(pinit_core::ApplyReport, Vec<HookOutcome>, Timings), String> {
+    let mut timings = resolved.timings.clone();
+    let mut hooks = timings.time(Phase::Hooks, || {
         run_hooks(
             "after_dir_create",
             &resolved.hooks.after_dir_create,
-            &args.dir,
+            dir,
             RunMode::Init,
-            true,
+            dry_run,
             resolved.recipe_name.as_deref(),
-        )?;
-        let mut report = apply_template_stack(&resolved, &args.dir, true, &mut decider)?;
+        )
+    })?;
 
-        report = maybe_apply_license(
-            resolved.license.as_ref(),
-            &args.dir,
-            pinit_core::ApplyOptions {
-                dry_run: true,
-                ..Default::default()
-            },
-            &mut decider,
-            report,
-        )?;
-        run_hooks(
+    if dry_run {
+        note!("dry-run: would create directory {}", dir.display());
+        if args.no_git {
+            note!("dry-run: would skip git init");
+        } else {
+            note!("dry-run: would run git init (branch {})", args.branch);
+        }
+    } else if !args.no_git {
+        git_init(dir, &args.branch)?;
+    }
+
+    let options = pinit_core::ApplyOptions {
+        variables: variables.clone(),
+        ..base_apply_options(dry_run, args.max_depth, args.max_files)
+    };
+    let generated_options = pinit_core::ApplyOptions {
+        dry_run,
+        variables: variables.clone(),
+        ..Default::default()
+    };
+    let report = apply_all(resolved, dir, &options, generated_options, decider)?;
+
+    timings.time(Phase::Hooks, || -> Result<(), String> {
+        hooks.extend(run_hooks(
             "after_recipe",
             &resolved.recipe_hooks.after_recipe,
-            &args.dir,
+            dir,
             RunMode::Init,
-            true,
+            dry_run,
             resolved.recipe_name.as_deref(),
-        )?;
-        run_hooks(
+        )?);
+        hooks.extend(run_hooks(
             "after_all",
             &resolved.hooks.after_all,
-            &args.dir,
+            dir,
             RunMode::Init,
-            true,
+            dry_run,
             resolved.recipe_name.as_deref(),
-        )?;
+        )?);
+        Ok(())
+    })?;
+    timings.absorb(&report.timings);
+    Ok((report, hooks, timings))
+}
 
-        eprintln!("dry-run: would create directory {}", args.dir.display());
-        if args.no_git {
-            eprintln!("dry-run: would skip git init");
-        } else {
-            eprintln!("dry-run: would run git init (branch {})", args.branch);
-        }
-        print_apply_summary(true, report);
-        return Ok(());
-    }
-
-    if args.dir.exists() {
-        let meta =
-            std::fs::metadata(&args.dir).map_err(|e| format!("{}: {e}", args.dir.display()))?;
-        if !meta.is_dir() {
-            return Err(format!(
-                "destination is not a directory: {}",
-                args.dir.display()
-            ));
-        }
-        let mut iter =
-            std::fs::read_dir(&args.dir).map_err(|e| format!("{}: {e}", args.dir.display()))?;
-        if iter.next().is_some() {
-            return Err(format!(
-                "destination already exists and is not empty: {}",
-                args.dir.display()
-            ));
-        }
-    } else {
-        std::fs::create_dir_all(&args.dir).map_err(|e| format!("{}: {e}", args.dir.display()))?;
-    }
+fn remote_config(resolved: &TemplateResolution) -> Result<&RemoteDef, String> {
+    resolved
+        .remote
+        .as_ref()
+        .ok_or_else(|| "--create-remote requires a [remote] section in config".to_string())
+}
 
-    let resolved = resolve_template_stack(config_path, &args.template)?;
+/// Settle `pinit new`'s template and directory into the positional fields.
+///
+/// `--template` and `--dest` fill their own slot and a lone argument takes the other;
+/// `pinit new <DIR>` alone uses the config's `default_recipe`.
+fn resolve_new_args(
+    config_path: Option<&std::path::Path>,
+    mut args: NewArgs,
+) -> Result<NewArgs, String> {
+    if args.list {
+        return Ok(args);
+    }
+    // Clap has rejected the ambiguous shapes; this only moves the lone positional
+    // argument to the slot the option left open.
+    if let Some(template) = args.template_flag.take() {
+        args.dir = args.template.replace(template).map(PathBuf::from);
+    } else if let Some(dest) = args.dest.take() {
+        args.dir = Some(dest);
+    } else if args.dir.is_none() {
+        args.dir = args.template.take().map(PathBuf::from);
+    }
+    if args.template.is_none() {
+        args.template = Some(default_recipe(config_path)?);
+    }
+    Ok(args)
+}
 
-    run_hooks(
-        "after_dir_create",
-        &resolved.hooks.after_dir_create,
-        &args.dir,
-        RunMode::Init,
-        false,
-        resolved.recipe_name.as_deref(),
-    )?;
+/// The config's `default_recipe`, for `pinit new` without a template.
+fn default_recipe(config_path: Option<&std::path::Path>) -> Result<String, String> {
+    let (path, cfg) = pinit_core::config::load_config(config_path).map_err(|e| e.to_string())?;
+    let name = cfg.default_recipe.ok_or_else(|| {
+        format!(
+            "no template given and no default_recipe in {}; run `pinit new <TEMPLATE> <DIR>`",
+            path.display()
+        )
+    })?;
+    tracing::debug!(template = %name, "default_recipe");
+    Ok(name)
+}
 
-    if !args.no_git {
-        git_init(&args.dir, &args.branch)?;
+/// Project name for `pinit new`: `--name`, else the destination directory name.
+fn project_name_for(dir: &std::path::Path, name: Option<&str>) -> Result<String, String> {
+    if let Some(name) = name {
+        if name.trim().is_empty() {
+            return Err("--name must not be empty".to_string());
+        }
+        return Ok(name.to_string());
     }
-
-    let default_action = if args.overwrite {
-        ExistingFileAction::Overwrite
-    } else if args.skip {
-        ExistingFileAction::Skip
-    } else {
-        ExistingFileAction::Merge
+    let derived = match dir.file_name() {
+        Some(name) => Some(name.to_string_lossy().into_owned()),
+        // `.` / `..` have no file name; fall back to the resolved directory.
+        None => dir
+            .canonicalize()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned())),
     };
+    derived.ok_or_else(|| {
+        format!(
+            "cannot derive a project name from {}; pass --name",
+            dir.display()
+        )
+    })
+}
 
-    let overrides = combined_overrides(&resolved, &args.overrides, args.override_action);
-    let mut decider = CliDecider::new(
-        default_action,
-        args.yes || args.overwrite || args.merge || args.skip,
-        overrides,
-    );
-    let mut report = apply_template_stack(&resolved, &args.dir, false, &mut decider)?;
-
-    report = maybe_apply_license(
-        resolved.license.as_ref(),
-        &args.dir,
-        pinit_core::ApplyOptions {
-            dry_run: false,
-            ..Default::default()
-        },
-        &mut decider,
-        report,
-    )?;
-
-    run_hooks(
-        "after_recipe",
-        &resolved.recipe_hooks.after_recipe,
-        &args.dir,
-        RunMode::Init,
-        false,
-        resolved.recipe_name.as_deref(),
-    )?;
-    run_hooks(
-        "after_all",
-        &resolved.hooks.after_all,
-        &args.dir,
-        RunMode::Init,
-        false,
-        resolved.recipe_name.as_deref(),
-    )?;
+const DEFAULT_MAX_DEPTH: usize = 32;
+const DEFAULT_MAX_FILES: usize = 10_000;
 
-    print_apply_summary(false, report);
-    Ok(())
+/// Base apply options for a run; per-template fields are filled in by `apply_template_stack`.
+fn base_apply_options(
+    dry_run: bool,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+) -> pinit_core::ApplyOptions {
+    let limit = |value: Option<usize>, default: usize| match value.unwrap_or(default) {
+        0 => None,
+        n => Some(n),
+    };
+    pinit_core::ApplyOptions {
+        dry_run,
+        max_depth: limit(max_depth, DEFAULT_MAX_DEPTH),
+        max_files: limit(max_files, DEFAULT_MAX_FILES),
+        ..Default::default()
+    }
 }
 
 fn apply_template_stack(
     resolved: &TemplateResolution,
     dest_dir: &std::path::Path,
-    dry_run: bool,
+    base_options: &pinit_core::ApplyOptions,
     decider: &mut dyn ExistingFileDecider,
 ) -> Result<pinit_core::ApplyReport, String> {
     let mut report = pinit_core::ApplyReport::default();
@@ -284,19 +644,21 @@ fn apply_template_stack(
         tracing::info!(
             template = %entry.name,
             template_dir = %entry.dir.display(),
+            dest_prefix = ?entry.dest_prefix,
             "apply template dir"
         );
         let options = pinit_core::ApplyOptions {
-            dry_run,
             template_name: Some(entry.name.clone()),
             template_index: Some(entry.index),
+            dest_prefix: entry.dest_prefix.clone(),
+            path_map: resolved.path_map.clone(),
+            transforms: resolved.transforms.clone(),
+            nested_repos: resolved.nested_repos,
+            ..base_options.clone()
         };
         let r = pinit_core::apply_template_dir(&entry.dir, dest_dir, options, decider)
             .map_err(|e| e.to_string())?;
-        report.created_files += r.created_files;
-        report.updated_files += r.updated_files;
-        report.skipped_files += r.skipped_files;
-        report.ignored_paths += r.ignored_paths;
+        report.absorb(r);
     }
     Ok(report)
 }
@@ -307,13 +669,37 @@ struct TemplateResolution {
     hooks: HookSet,
     recipe_hooks: HookSet,
     recipe_name: Option<String>,
+    /// Config file the resolution came from (`None` for a template directory).
+    config_path: Option<PathBuf>,
     license: Option<LicenseDef>,
+    remote: Option<RemoteDef>,
+    /// Composed `.gitignore` body from the recipe's fragments.
+    gitignore: Option<String>,
+    binary_action: Option<BinaryAction>,
+    license_conflict: Option<LicenseConflictAction>,
+    apply_lockfiles: bool,
+    nested_repos: NestedRepoAction,
+    dirty_dest: DirtyDestAction,
+    apply_branch: Option<String>,
+    /// Rules from each template's `pinit-policy.toml`, indexed like `templates`.
+    policies: Vec<Vec<OverrideRule>>,
+    /// Template path -> destination path renames from config `map` tables.
+    path_map: BTreeMap<PathBuf, PathBuf>,
+    /// Configured `[[transforms]]` steps.
+    transforms: pinit_core::transform::Pipeline,
+    /// "template 'x' is deprecated: ..." for the stack and each template in it.
+    deprecations: Vec<String>,
+    /// Cache directory for license texts (`None` when there is none).
+    cache_dir: Option<PathBuf>,
+    /// Time spent resolving, fetching, and checking out the stack.
+    timings: Timings,
 }
 
 fn resolve_template_stack(
     config_path: Option<&std::path::Path>,
     template: &str,
 ) -> Result<TemplateResolution, String> {
+    let start = Instant::now();
     let template_path = PathBuf::from(template);
     if template_path.is_dir() {
         let name = template_path
@@ -321,39 +707,77 @@ fn resolve_template_stack(
             .and_then(|s| s.to_str())
             .unwrap_or(template)
             .to_string();
+        let entry = ResolvedTemplate {
+            name,
+            dir: template_path,
+            index: 0,
+            dest_prefix: None,
+        };
+        pinit_core::manifest::check_requirement(&entry.dir).map_err(|e| e.to_string())?;
+        let policies = vec![template_policy(&entry, &BTreeMap::new())?];
         return Ok(TemplateResolution {
-            templates: vec![ResolvedTemplate {
-                name,
-                dir: template_path,
-                index: 0,
-            }],
+            templates: vec![entry],
             overrides: Vec::new(),
             hooks: HookSet::default(),
             recipe_hooks: HookSet::default(),
             recipe_name: None,
+            config_path: None,
             license: None,
+            remote: None,
+            gitignore: None,
+            binary_action: None,
+            license_conflict: None,
+            apply_lockfiles: false,
+            nested_repos: NestedRepoAction::default(),
+            dirty_dest: DirtyDestAction::default(),
+            apply_branch: None,
+            policies,
+            path_map: BTreeMap::new(),
+            transforms: pinit_core::transform::Pipeline::new(),
+            deprecations: Vec::new(),
+            cache_dir: template_resolver(None)
+                .ok()
+                .map(|r| r.cache_dir().to_path_buf()),
+            timings: resolve_timings(start, Timings::default()),
         });
     }
 
-    let (_path, cfg) = pinit_core::config::load_config(config_path).map_err(|e| e.to_string())?;
+    let (cfg_path, cfg) =
+        pinit_core::config::load_config(config_path).map_err(|e| e.to_string())?;
     let resolved = cfg
         .resolve_recipe(template)
         .ok_or_else(|| format!("unknown template: {template}"))?;
-    let resolver =
-        pinit_core::resolve::TemplateResolver::with_default_cache().map_err(|e| e.to_string())?;
+    let resolver = template_resolver(Some(&cfg))?;
 
     let mut templates = Vec::with_capacity(resolved.templates.len());
-    for (index, name) in resolved.templates.iter().enumerate() {
+    for (index, entry) in resolved.templates.iter().enumerate() {
         let dir = resolver
-            .resolve_template_dir(&cfg, name)
+            .resolve_template_dir(&cfg, entry.name())
             .map_err(|e| e.to_string())?;
         templates.push(ResolvedTemplate {
-            name: name.clone(),
+            name: entry.name().to_string(),
             dir,
             index,
+            dest_prefix: entry.dest_prefix().map(std::path::Path::to_path_buf),
         });
     }
 
+    for entry in &templates {
+        pinit_core::manifest::check_requirement(&entry.dir).map_err(|e| e.to_string())?;
+    }
+    let policies = templates
+        .iter()
+        .map(|entry| template_policy(entry, &resolved.map))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let gitignore = if resolved.gitignore.is_empty() {
+        None
+    } else {
+        let body = pinit_core::gitignore::compose(&resolved.gitignore, &cfg.gitignore)
+            .map_err(|e| e.to_string())?;
+        Some(body)
+    };
+
     Ok(TemplateResolution {
         templates,
         overrides: resolved.overrides.clone(),
@@ -361,10 +785,229 @@ fn resolve_template_stack(
         recipe_hooks: resolved.hooks.clone(),
         recipe_name: matches!(resolved.kind, pinit_core::config::ResolvedKind::Recipe)
             .then(|| resolved.name.clone()),
+        config_path: Some(cfg_path),
         license: cfg.license.clone(),
+        remote: cfg.remote.clone(),
+        gitignore,
+        binary_action: cfg.binary_action,
+        license_conflict: cfg.license_conflict,
+        apply_lockfiles: cfg.apply_lockfiles,
+        nested_repos: cfg.nested_repos,
+        dirty_dest: cfg.dirty_dest,
+        apply_branch: cfg.apply_branch.clone(),
+        policies,
+        path_map: resolved.map.clone(),
+        transforms: pinit_core::transform::Pipeline::from_defs(&cfg.transforms),
+        deprecations: cfg
+            .deprecations(&resolved)
+            .into_iter()
+            .map(|w| w.message)
+            .collect(),
+        cache_dir: Some(resolver.cache_dir().to_path_buf()),
+        timings: resolve_timings(start, resolver.timings()),
     })
 }
 
+/// Git timings from the resolver, plus everything else since `start` as the resolve phase.
+fn resolve_timings(start: Instant, mut timings: Timings) -> Timings {
+    let git = timings.get(Phase::Fetch) + timings.get(Phase::Checkout);
+    timings.add(Phase::Resolve, start.elapsed().saturating_sub(git));
+    timings
+}
+
+/// `--on-conflict`, with `--overwrite`, `--merge`, and `--skip` as shorthand for it.
+///
+/// Clap keeps them to one of the group, so at most one is set.
+fn on_conflict(
+    on_conflict: Option<OnConflict>,
+    overwrite: bool,
+    merge: bool,
+    skip: bool,
+) -> Option<OnConflict> {
+    on_conflict
+        .or(overwrite.then_some(OnConflict::Overwrite))
+        .or(merge.then_some(OnConflict::Merge))
+        .or(skip.then_some(OnConflict::Skip))
+}
+
+/// Reject the `--on-conflict` values that contradict `--yes` or `--only-new`, the way clap
+/// rejects conflicting flags. Clap has no per-value conflicts, so the error is built from
+/// `subcommand`'s own `Command` to get its usage line.
+fn check_on_conflict(subcommand: &str, on_conflict: Option<OnConflict>, yes: bool, only_new: bool) {
+    let clash = match on_conflict {
+        Some(OnConflict::Prompt) if yes => Some(("prompt", "--yes")),
+        Some(OnConflict::Merge) if only_new => Some(("merge", "--only-new")),
+        Some(OnConflict::Overwrite) if only_new => Some(("overwrite", "--only-new")),
+        _ => None,
+    };
+    if let Some((value, other)) = clash {
+        let mut cli = Cli::command();
+        cli.build();
+        cli.find_subcommand(subcommand)
+            .cloned()
+            .unwrap_or_else(Cli::command)
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("the argument '--on-conflict {value}' cannot be used with '{other}'"),
+            )
+            .exit();
+    }
+}
+
+/// Whether existing files are decided without asking: `--yes`, or any `--on-conflict` but `prompt`.
+fn non_interactive(yes: bool, on_conflict: Option<OnConflict>) -> bool {
+    yes || explicit_action(on_conflict)
+}
+
+/// Whether an action flag chose the action for existing files: `--on-conflict` (but `prompt`),
+/// `--skip`, `--overwrite`, or `--merge`.
+fn explicit_action(on_conflict: Option<OnConflict>) -> bool {
+    on_conflict.is_some_and(|action| action != OnConflict::Prompt)
+}
+
+/// Action for existing files: `--on-conflict` (or its shorthand flags), then
+/// `PINIT_DEFAULT_ACTION`, then merge. `prompt` only asks; its default is chosen the same way.
+fn default_action(on_conflict: Option<OnConflict>) -> Result<ExistingFileAction, String> {
+    match on_conflict {
+        Some(OnConflict::Overwrite) => return Ok(ExistingFileAction::Overwrite),
+        Some(OnConflict::Skip) => return Ok(ExistingFileAction::Skip),
+        Some(OnConflict::Merge) => return Ok(ExistingFileAction::Merge),
+        Some(OnConflict::Prompt) | None => {}
+    }
+    match std::env::var("PINIT_DEFAULT_ACTION") {
+        Ok(value) if !value.trim().is_empty() => parse_default_action(&value),
+        _ => Ok(ExistingFileAction::Merge),
+    }
+}
+
+fn parse_default_action(value: &str) -> Result<ExistingFileAction, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "overwrite" => Ok(ExistingFileAction::Overwrite),
+        "merge" => Ok(ExistingFileAction::Merge),
+        "skip" => Ok(ExistingFileAction::Skip),
+        _ => Err(format!(
+            "PINIT_DEFAULT_ACTION must be overwrite, merge, or skip (got {value:?})"
+        )),
+    }
+}
+
+/// Warnings for deprecated templates in the stack, or an error under `--strict`.
+fn check_deprecations(resolved: &TemplateResolution, strict: bool) -> Result<Vec<Warning>, String> {
+    if strict && !resolved.deprecations.is_empty() {
+        return Err(format!(
+            "{} (refusing with --strict)",
+            resolved.deprecations.join("; ")
+        ));
+    }
+    Ok(resolved
+        .deprecations
+        .iter()
+        .map(|message| Warning::new(WarningKind::Deprecated, message.as_str()))
+        .collect())
+}
+
+/// `earlier` warnings, then the apply report's, unmatched overrides, and allowed hook failures.
+fn collect_warnings(
+    mut earlier: Vec<Warning>,
+    report: &pinit_core::ApplyReport,
+    overrides: &[OverrideRule],
+    hooks: &[HookOutcome],
+) -> Vec<Warning> {
+    earlier.extend(report.warnings.iter().cloned());
+    earlier.extend(unmatched_overrides(overrides, report));
+    earlier.extend(hooks.iter().filter_map(HookOutcome::warning));
+    earlier
+}
+
+/// A warning for each override pattern that matched none of the files the run applied.
+///
+/// Catches typos like `*.yamll`, which would otherwise do nothing without a word.
+fn unmatched_overrides(rules: &[OverrideRule], report: &pinit_core::ApplyReport) -> Vec<Warning> {
+    let applied: Vec<String> = report
+        .entries
+        .iter()
+        .filter(|entry| !matches!(entry.outcome, EntryOutcome::Ignored(_)))
+        .map(|entry| rel_path_for_match(&entry.path))
+        .collect();
+    let mut seen = HashSet::new();
+    rules
+        .iter()
+        .filter(|rule| seen.insert(rule.pattern.as_str()))
+        .filter(|rule| !applied.iter().any(|rel| glob_match(&rule.pattern, rel)))
+        .map(|rule| {
+            Warning::new(
+                WarningKind::UnmatchedOverride,
+                format!("override pattern {:?} matched no files", rule.pattern),
+            )
+        })
+        .collect()
+}
+
+/// Warnings go out together at the end of a run, where they are not scrolled away.
+fn print_warnings(warnings: &[Warning]) {
+    for warning in warnings {
+        note!("warning: {warning}");
+    }
+}
+
+/// Rules from a template's `pinit-policy.toml`, with patterns moved to where the files land:
+/// through the target's `map` renames, then under the template's `dest_prefix`.
+fn template_policy(
+    entry: &ResolvedTemplate,
+    path_map: &BTreeMap<PathBuf, PathBuf>,
+) -> Result<Vec<OverrideRule>, String> {
+    let Some(policy) = pinit_core::manifest::load_policy(&entry.dir).map_err(|e| e.to_string())?
+    else {
+        return Ok(Vec::new());
+    };
+    tracing::debug!(template = %entry.name, rules = policy.overrides.len(), "template policy");
+    let prefix = entry
+        .dest_prefix
+        .as_deref()
+        .map(|p| rel_path_for_match(p).trim_end_matches('/').to_string());
+    Ok(policy
+        .overrides
+        .into_iter()
+        .map(|rule| {
+            let pattern = map_policy_pattern(&rule.pattern, path_map);
+            OverrideRule {
+                pattern: match &prefix {
+                    Some(prefix) => format!("{prefix}/{pattern}"),
+                    None => pattern,
+                },
+                action: rule.action,
+            }
+        })
+        .collect())
+}
+
+/// `pattern` with its literal leading segments renamed by `path_map`, the way
+/// [`pinit_core::transform::RemapPaths`] renames the files it matches.
+fn map_policy_pattern(pattern: &str, path_map: &BTreeMap<PathBuf, PathBuf>) -> String {
+    use pinit_core::transform::{FileTransform, RemapPaths};
+
+    let pattern = pattern.trim_start_matches('/');
+    let segments: Vec<&str> = pattern.split('/').collect();
+    let literal = segments
+        .iter()
+        .take_while(|s| !s.contains(['*', '?', '[']))
+        .count();
+    if path_map.is_empty() || literal == 0 {
+        return pattern.to_string();
+    }
+    let remap = RemapPaths {
+        map: path_map.clone(),
+        dest_prefix: None,
+    };
+    let mapped = remap.path(segments[..literal].iter().collect());
+    let mut out = rel_path_for_match(&mapped);
+    for rest in &segments[literal..] {
+        out.push('/');
+        out.push_str(rest);
+    }
+    out
+}
+
 fn combined_overrides(
     resolved: &TemplateResolution,
     patterns: &[String],
@@ -391,18 +1034,87 @@ fn override_action_from_arg(action: Option<OverrideActionArg>) -> OverrideAction
     }
 }
 
-fn print_apply_summary(dry_run: bool, report: pinit_core::ApplyReport) {
+fn print_apply_summary(
+    dry_run: bool,
+    report: &pinit_core::ApplyReport,
+    merge_unavailable: &[PathBuf],
+) {
+    if quiet() {
+        return;
+    }
+    if verbose() {
+        print_entries(report);
+    }
+    let ignored = if report.ignored_paths > 0 {
+        format!(", {}", summary::ignored_counts(report))
+    } else {
+        String::new()
+    };
+    let unmerged = summary::merge_unavailable_count(merge_unavailable);
     if dry_run {
         println!(
-            "dry-run: would create {} file(s), update {} file(s), skip {} file(s)",
+            "dry-run: would create {} file(s), update {} file(s), skip {} file(s){unmerged}{ignored}",
             report.created_files, report.updated_files, report.skipped_files
         );
     } else {
         println!(
-            "created {} file(s), updated {} file(s), skipped {} file(s)",
+            "created {} file(s), updated {} file(s), skipped {} file(s){unmerged}{ignored}",
             report.created_files, report.updated_files, report.skipped_files
         );
     }
+    let breakdown = summary::template_breakdown(report);
+    if summary::spans_templates(&breakdown) {
+        print!("{}", summary::breakdown_table(&breakdown));
+    }
+    print_merge_unavailable(merge_unavailable);
+}
+
+/// One line per path with its colored action marker, for `-v`.
+fn print_entries(report: &pinit_core::ApplyReport) {
+    for entry in &report.entries {
+        let slash = if entry.pruned.is_some() { "/" } else { "" };
+        println!(
+            "{} {}{slash}",
+            style::marker(style::Marker::for_entry(entry), style::Stream::Stdout),
+            entry.path.display()
+        );
+    }
+}
+
+/// Name the files a merge was wanted for but could not be done, and what to do about them.
+fn print_merge_unavailable(paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+    eprintln!("no merge available for (left unchanged):");
+    for path in paths {
+        eprintln!("  {}", path.display());
+    }
+    eprintln!(
+        "hint: merge these by hand, or apply again with `--override <path> --override-action overwrite`"
+    );
+}
+
+/// `--sizes`: total bytes written, then the largest files.
+fn print_sizes(show: bool, dry_run: bool, report: &pinit_core::ApplyReport) {
+    if !show || quiet() {
+        return;
+    }
+    println!("size: {}", summary::size_line(dry_run, report));
+    for entry in report.largest_files(summary::LARGEST_FILES) {
+        println!(
+            "  {:>10}  {}",
+            summary::format_size(entry.bytes.unwrap_or(0)),
+            entry.path.display()
+        );
+    }
+}
+
+/// Per-phase timings for `--timings` and `-v`, on stderr so stdout stays parseable.
+fn print_timings(show: bool, timings: &Timings) {
+    if show {
+        note!("timings: {timings}");
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -418,16 +1130,23 @@ fn run_hooks(
     mode: RunMode,
     dry_run: bool,
     recipe_name: Option<&str>,
-) -> Result<(), String> {
+) -> Result<Vec<HookOutcome>, String> {
+    let mut outcomes = Vec::new();
     for hook in hooks {
         if !hook_should_run(hook, mode) {
             continue;
         }
+        let outcome = |result| HookOutcome {
+            phase: label.to_string(),
+            command: format_command(&hook.command),
+            result,
+        };
         if dry_run {
-            eprintln!(
+            note!(
                 "dry-run: would run hook {label}: {}",
                 format_command(&hook.command)
             );
+            outcomes.push(outcome(HookResult::DryRun));
             continue;
         }
 
@@ -470,6 +1189,7 @@ fn run_hooks(
             )
         })?;
         if output.status.success() {
+            outcomes.push(outcome(HookResult::Ok));
             continue;
         }
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -479,12 +1199,12 @@ fn run_hooks(
             stderr.trim()
         );
         if hook.allow_failure {
-            eprintln!("warning: {message}");
+            outcomes.push(outcome(HookResult::AllowedFailure(message)));
         } else {
             return Err(message);
         }
     }
-    Ok(())
+    Ok(outcomes)
 }
 
 fn hook_should_run(hook: &HookDef, mode: RunMode) -> bool {
@@ -516,8 +1236,69 @@ fn format_command(command: &[String]) -> String {
     out
 }
 
+/// The template stack, then the composed `.gitignore` and the license file.
+fn apply_all(
+    resolved: &TemplateResolution,
+    dest_dir: &std::path::Path,
+    options: &pinit_core::ApplyOptions,
+    generated_options: pinit_core::ApplyOptions,
+    decider: &mut dyn ExistingFileDecider,
+) -> Result<pinit_core::ApplyReport, String> {
+    let report = apply_template_stack(resolved, dest_dir, options, decider)?;
+    let report = maybe_apply_gitignore(
+        resolved.gitignore.as_deref(),
+        dest_dir,
+        generated_options.clone(),
+        decider,
+        report,
+    )?;
+    maybe_apply_license(
+        resolved.license.as_ref(),
+        resolved.cache_dir.as_deref(),
+        dest_dir,
+        generated_options,
+        decider,
+        report,
+    )
+}
+
+fn maybe_apply_gitignore(
+    body: Option<&str>,
+    dest_dir: &std::path::Path,
+    options: pinit_core::ApplyOptions,
+    decider: &mut dyn ExistingFileDecider,
+    mut report: pinit_core::ApplyReport,
+) -> Result<pinit_core::ApplyReport, String> {
+    let Some(body) = body else {
+        return Ok(report);
+    };
+
+    let r =
+        pinit_core::merge_generated_file(dest_dir, ".gitignore", body.as_bytes(), options, decider)
+            .map_err(|e| e.to_string())?;
+    report.absorb(r);
+    Ok(report)
+}
+
+/// Offer a short list of licenses and optionally save the choice to config.
+fn prompt_for_license(config_path: Option<&std::path::Path>) -> Result<Option<LicenseDef>, String> {
+    let mut input = std::io::stdin().lock();
+    let mut output = std::io::stderr();
+    let Some(spdx) = license::choose_license(&mut input, &mut output) else {
+        return Ok(None);
+    };
+    if let Some(path) = config_path
+        && license::confirm_save(&mut input, &mut output, &spdx, path)
+    {
+        license::save_license(path, &spdx)?;
+        note!("saved license {spdx} to {}", path.display());
+    }
+    Ok(Some(LicenseDef::Spdx(spdx)))
+}
+
 fn maybe_apply_license(
     license_def: Option<&LicenseDef>,
+    cache_dir: Option<&Path>,
     dest_dir: &std::path::Path,
     options: pinit_core::ApplyOptions,
     decider: &mut dyn ExistingFileDecider,
@@ -535,23 +1316,36 @@ fn maybe_apply_license(
         ));
     }
 
-    let rendered = pinit_core::licensing::render_spdx_license(
-        license_def.spdx(),
-        &license_def.template_args(),
-    )
-    .map_err(|e| e.to_string())?;
+    // Values may reference template variables (`{{ project_name }}`), and project
+    // variables also fill SPDX placeholders the config leaves unset.
+    let mut template_args = license_def.template_args();
+    for value in template_args.values_mut() {
+        if let Some(rendered) = pinit_core::vars::render(value, &options.variables) {
+            *value = rendered;
+        }
+    }
+    for (name, value) in &options.variables {
+        template_args
+            .entry(name.clone())
+            .or_insert_with(|| value.clone());
+    }
+    let rendered = license::render(cache_dir, license_def.spdx(), &template_args)?;
 
     let mut bytes = rendered.text.into_bytes();
     if !bytes.ends_with(b"\n") {
         bytes.push(b'\n');
     }
 
-    let r = pinit_core::apply_generated_file(dest_dir, &rel_path, &bytes, options, decider)
-        .map_err(|e| e.to_string())?;
-    report.created_files += r.created_files;
-    report.updated_files += r.updated_files;
-    report.skipped_files += r.skipped_files;
-    report.ignored_paths += r.ignored_paths;
+    let r = pinit_core::apply_license_file(
+        dest_dir,
+        &rel_path,
+        &bytes,
+        license_def.spdx(),
+        options,
+        decider,
+    )
+    .map_err(|e| e.to_string())?;
+    report.absorb(r);
     Ok(report)
 }
 
@@ -611,7 +1405,22 @@ fn git_init(dir: &std::path::Path, branch: &str) -> Result<(), String> {
 struct CliDecider {
     default_action: ExistingFileAction,
     non_interactive: bool,
+    /// An action flag (`--on-conflict`, `--skip`, ...) chose `default_action`; bare `--yes` does not.
+    explicit_action: bool,
     overrides: Vec<OverrideRule>,
+    binary_action: Option<BinaryAction>,
+    license_conflict: Option<LicenseConflictAction>,
+    apply_lockfiles: bool,
+    /// Template policy rules by template index: the suggested answer when asking.
+    policies: Vec<Vec<OverrideRule>>,
+    /// `--only-new`: every existing file is skipped, before any other rule.
+    only_new: bool,
+    /// Files a merge was chosen for but none was available, so they were skipped.
+    merge_unavailable: Vec<PathBuf>,
+    /// `--review`: collect conflicts instead of asking, or use the reviewed answers.
+    review: review::Review,
+    /// `--review` conflicts that only came up after the queue; skipped rather than asked.
+    unreviewed: Vec<PathBuf>,
 }
 
 impl CliDecider {
@@ -623,11 +1432,55 @@ impl CliDecider {
         Self {
             default_action,
             non_interactive,
+            explicit_action: false,
             overrides,
+            binary_action: None,
+            license_conflict: None,
+            apply_lockfiles: false,
+            policies: Vec::new(),
+            only_new: false,
+            merge_unavailable: Vec::new(),
+            review: review::Review::Off,
+            unreviewed: Vec::new(),
         }
     }
 
-    fn prompt(&self, ctx: &ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+    fn with_binary_action(mut self, binary_action: Option<BinaryAction>) -> Self {
+        self.binary_action = binary_action;
+        self
+    }
+
+    fn with_license_conflict(mut self, license_conflict: Option<LicenseConflictAction>) -> Self {
+        self.license_conflict = license_conflict;
+        self
+    }
+
+    fn with_apply_lockfiles(mut self, apply_lockfiles: bool) -> Self {
+        self.apply_lockfiles = apply_lockfiles;
+        self
+    }
+
+    fn with_template_policies(mut self, policies: Vec<Vec<OverrideRule>>) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    fn with_explicit_action(mut self, explicit_action: bool) -> Self {
+        self.explicit_action = explicit_action;
+        self
+    }
+
+    fn with_only_new(mut self, only_new: bool) -> Self {
+        self.only_new = only_new;
+        self
+    }
+
+    /// Ask on the terminal; an empty answer picks `default`.
+    fn prompt(
+        &self,
+        ctx: &ExistingFileDecisionContext<'_>,
+        default: ExistingFileAction,
+    ) -> ExistingFileAction {
         let rel = ctx.rel_path.display();
         let merge_available = ctx.merge_bytes.is_some();
 
@@ -638,7 +1491,26 @@ impl CliDecider {
                 "merge available: {}",
                 if merge_available { "yes" } else { "no" }
             );
-            eprintln!("choose: (m)erge, (o)verwrite, (s)kip, (d)iff  [default: m]");
+            if let Some(license) = ctx.license {
+                eprintln!(
+                    "license: existing {}, template {}",
+                    license.existing_spdx.as_deref().unwrap_or("unrecognized"),
+                    license.spdx
+                );
+            }
+            if ctx.is_binary {
+                eprintln!(
+                    "binary: {} -> {} bytes ({:+})",
+                    ctx.dest_bytes.len(),
+                    ctx.src_bytes.len(),
+                    ctx.size_delta
+                );
+            }
+            eprintln!(
+                "choose: {}, (d)iff  [default: {}]",
+                style::action_choices(),
+                &default.as_str()[..1]
+            );
             eprint!("> ");
             {
                 use std::io::Write;
@@ -655,10 +1527,13 @@ impl CliDecider {
                     return ExistingFileAction::Skip;
                 }
             }
-            let choice = line.trim().to_ascii_lowercase();
+            let mut choice = line.trim().to_ascii_lowercase();
+            if choice.is_empty() {
+                choice = default.as_str()[..1].to_string();
+            }
 
             match choice.as_str() {
-                "" | "m" => {
+                "m" => {
                     if merge_available {
                         return ExistingFileAction::Merge;
                     }
@@ -666,72 +1541,184 @@ impl CliDecider {
                 }
                 "o" => return ExistingFileAction::Overwrite,
                 "s" => return ExistingFileAction::Skip,
-                "d" => {
-                    self.print_diffs(ctx);
-                }
+                "d" => print_diffs(ctx.rel_path, ctx.dest_bytes, ctx.src_bytes, ctx.merge_bytes),
                 _ => eprintln!("unknown choice: {choice}"),
             }
         }
     }
 
-    fn print_diffs(&self, ctx: &ExistingFileDecisionContext<'_>) {
-        let rel = ctx.rel_path.display();
-        eprintln!();
-        eprintln!("diffs for {rel}:");
-        eprintln!();
-
-        if let Some(merge) = ctx.merge_bytes {
-            eprintln!("--- merge");
-            print_unified_diff("dest", "merged", ctx.dest_bytes, merge);
-        } else {
-            eprintln!("--- merge (unavailable)");
+    /// Ask about a conflict now, or under `--review`, record it or use the reviewed answer.
+    ///
+    /// The template's policy, if it has a rule for the file, is the suggested answer.
+    fn ask(&mut self, ctx: &ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        let default = self.unasked_default(ctx);
+        match &mut self.review {
+            review::Review::Off => {}
+            review::Review::Collect(conflicts) => {
+                let conflict = review::Conflict::new(ctx, default);
+                let action = conflict.action();
+                conflicts.push(conflict);
+                return action;
+            }
+            review::Review::Decided(decisions) => {
+                return match decisions.get(&review::key(ctx)) {
+                    Some(action) => *action,
+                    None => {
+                        self.unreviewed.push(ctx.rel_path.to_path_buf());
+                        ExistingFileAction::Skip
+                    }
+                };
+            }
         }
+        self.prompt(ctx, default)
+    }
 
-        eprintln!();
-        eprintln!("--- overwrite");
-        print_unified_diff("dest", "template", ctx.dest_bytes, ctx.src_bytes);
-        eprintln!();
+    fn override_action(
+        &mut self,
+        ctx: &ExistingFileDecisionContext<'_>,
+    ) -> Option<ExistingFileAction> {
+        // Overrides only cover template files, not generated ones (.gitignore, LICENSE).
+        ctx.template_name?;
+        let action = rule_action(&self.overrides, ctx)?;
+        Some(self.rule_decision(action, ctx))
     }
 
-    fn override_action(&self, ctx: &ExistingFileDecisionContext<'_>) -> Option<ExistingFileAction> {
-        if ctx.template_name.is_none() || self.overrides.is_empty() {
-            return None;
-        }
-        let rel = rel_path_for_match(ctx.rel_path);
-        let mut matched = None;
-        for rule in &self.overrides {
-            if glob_match(&rule.pattern, &rel) {
-                matched = Some(rule.action);
-            }
+    /// The answer for a file nothing else decided: the action flag's, then the template's
+    /// policy, then the default action.
+    fn unasked_default(&self, ctx: &ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        if self.explicit_action {
+            return self.default_action;
         }
-        let action = matched?;
-        Some(match action {
+        self.policy_default(ctx).unwrap_or(self.default_action)
+    }
+
+    fn policy_default(&self, ctx: &ExistingFileDecisionContext<'_>) -> Option<ExistingFileAction> {
+        let rules = self.policies.get(ctx.template_index?)?;
+        Some(match rule_action(rules, ctx)? {
             OverrideAction::Overwrite => ExistingFileAction::Overwrite,
             OverrideAction::Skip => ExistingFileAction::Skip,
-            OverrideAction::Merge => {
-                if ctx.merge_bytes.is_some() {
-                    ExistingFileAction::Merge
-                } else {
-                    ExistingFileAction::Skip
-                }
-            }
+            OverrideAction::Merge => ExistingFileAction::Merge,
         })
     }
+
+    fn rule_decision(
+        &mut self,
+        action: OverrideAction,
+        ctx: &ExistingFileDecisionContext<'_>,
+    ) -> ExistingFileAction {
+        match action {
+            OverrideAction::Overwrite => ExistingFileAction::Overwrite,
+            OverrideAction::Skip => ExistingFileAction::Skip,
+            OverrideAction::Merge => self.merge_or_skip(ctx),
+        }
+    }
+
+    /// Merge when a merge is available; otherwise skip, and remember the file for the summary.
+    fn merge_or_skip(&mut self, ctx: &ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        if ctx.merge_bytes.is_some() {
+            return ExistingFileAction::Merge;
+        }
+        tracing::debug!(path = %ctx.rel_path.display(), "merge unavailable; skipping");
+        self.merge_unavailable.push(ctx.rel_path.to_path_buf());
+        ExistingFileAction::Skip
+    }
+}
+
+/// Action of the last rule in `rules` matching the file.
+fn rule_action(
+    rules: &[OverrideRule],
+    ctx: &ExistingFileDecisionContext<'_>,
+) -> Option<OverrideAction> {
+    let rel = rel_path_for_match(ctx.rel_path);
+    rules
+        .iter()
+        .rev()
+        .find(|rule| glob_match(&rule.pattern, &rel))
+        .map(|rule| rule.action)
 }
 
 impl ExistingFileDecider for CliDecider {
     fn decide(&mut self, ctx: ExistingFileDecisionContext<'_>) -> ExistingFileAction {
+        if self.only_new {
+            return ExistingFileAction::Skip;
+        }
         if let Some(action) = self.override_action(&ctx) {
             return action;
         }
+        if let Some(license) = ctx.license {
+            match self.license_conflict {
+                Some(LicenseConflictAction::Keep) => return ExistingFileAction::Skip,
+                Some(LicenseConflictAction::Overwrite) => return ExistingFileAction::Overwrite,
+                Some(LicenseConflictAction::Matching) => {
+                    return if license.existing_spdx.as_deref() == Some(license.spdx.as_str()) {
+                        ExistingFileAction::Overwrite
+                    } else {
+                        ExistingFileAction::Skip
+                    };
+                }
+                Some(LicenseConflictAction::Prompt) if std::io::stdin().is_terminal() => {
+                    return self.ask(&ctx);
+                }
+                Some(LicenseConflictAction::Prompt) | None => {}
+            }
+        }
+        // What the repo declares for git merges stands in for asking; an action flag
+        // (`--skip`, `--on-conflict`, ...) and explicit overrides still win, bare `--yes` does not.
+        match ctx.merge_attr {
+            _ if self.explicit_action => {}
+            Some(MergeAttribute::Ours) => return ExistingFileAction::Skip,
+            Some(MergeAttribute::Theirs) => return ExistingFileAction::Overwrite,
+            Some(MergeAttribute::Union) if ctx.merge_bytes.is_some() => {
+                return ExistingFileAction::Merge;
+            }
+            _ => {}
+        }
+        if ctx.is_binary || ctx.merge_attr == Some(&MergeAttribute::Binary) {
+            match self.binary_action {
+                Some(BinaryAction::Skip) => return ExistingFileAction::Skip,
+                Some(BinaryAction::Overwrite) => return ExistingFileAction::Overwrite,
+                Some(BinaryAction::Prompt) if std::io::stdin().is_terminal() => {
+                    return self.ask(&ctx);
+                }
+                Some(BinaryAction::Prompt) | None => {}
+            }
+        }
+        // Merging or overwriting a lockfile from a template is almost always wrong.
+        if !self.apply_lockfiles && pinit_core::is_lockfile(ctx.rel_path) {
+            return ExistingFileAction::Skip;
+        }
         if self.non_interactive {
-            if self.default_action == ExistingFileAction::Merge && ctx.merge_bytes.is_none() {
-                return ExistingFileAction::Skip;
+            let action = self.unasked_default(&ctx);
+            if action == ExistingFileAction::Merge {
+                return self.merge_or_skip(&ctx);
             }
-            return self.default_action;
+            return action;
         }
-        self.prompt(&ctx)
+        self.ask(&ctx)
+    }
+}
+
+/// What merging and overwriting would each do to `rel`.
+fn print_diffs(rel: &Path, dest: &[u8], src: &[u8], merged: Option<&[u8]>) {
+    eprintln!();
+    eprintln!("diffs for {}:", rel.display());
+    eprintln!();
+
+    let merge = style::marker(style::Marker::Merged, style::Stream::Stderr);
+    if let Some(merged) = merged {
+        eprintln!("--- {merge} merge");
+        print_unified_diff("dest", "merged", dest, merged);
+    } else {
+        eprintln!("--- {merge} merge (unavailable)");
     }
+
+    eprintln!();
+    eprintln!(
+        "--- {} overwrite",
+        style::marker(style::Marker::Overwritten, style::Stream::Stderr)
+    );
+    print_unified_diff("dest", "template", dest, src);
+    eprintln!();
 }
 
 fn print_unified_diff(old_label: &str, new_label: &str, old_bytes: &[u8], new_bytes: &[u8]) {
@@ -759,129 +1746,19 @@ fn print_unified_diff(old_label: &str, new_label: &str, old_bytes: &[u8], new_by
         .header(old_label, new_label)
         .to_string();
 
-    if diff.trim().is_empty() {
-        eprintln!("(no textual changes)");
-    } else {
-        eprint!("{diff}");
-    }
-}
-
-fn rel_path_for_match(path: &std::path::Path) -> String {
-    let mut s = path.to_string_lossy().replace('\\', "/");
-    while s.starts_with("./") {
-        s = s[2..].to_string();
-    }
-    s.trim_start_matches('/').to_string()
-}
-
-fn glob_match(pattern: &str, path: &str) -> bool {
-    let pattern = pattern.replace('\\', "/");
-    let pattern = pattern.trim_start_matches('/').to_string();
-    let path = path.trim_start_matches('/').to_string();
-    let pat_segments: Vec<&str> = pattern.split('/').collect();
-    let path_segments: Vec<&str> = path.split('/').collect();
-    glob_match_segments(&pat_segments, &path_segments)
-}
-
-fn glob_match_segments(patterns: &[&str], paths: &[&str]) -> bool {
-    if patterns.is_empty() {
-        return paths.is_empty();
-    }
-    if patterns[0] == "**" {
-        for idx in 0..=paths.len() {
-            if glob_match_segments(&patterns[1..], &paths[idx..]) {
-                return true;
-            }
-        }
-        return false;
-    }
-    if paths.is_empty() {
-        return false;
-    }
-    if !glob_match_segment(patterns[0], paths[0]) {
-        return false;
-    }
-    glob_match_segments(&patterns[1..], &paths[1..])
-}
-
-fn glob_match_segment(pattern: &str, text: &str) -> bool {
-    let pat = pattern.as_bytes();
-    let txt = text.as_bytes();
-    let mut p = 0usize;
-    let mut t = 0usize;
-    let mut star_idx: Option<usize> = None;
-    let mut match_idx = 0usize;
-
-    while t < txt.len() {
-        if p < pat.len() && (pat[p] == b'?' || pat[p] == txt[t]) {
-            p += 1;
-            t += 1;
-        } else if p < pat.len() && pat[p] == b'*' {
-            star_idx = Some(p);
-            match_idx = t;
-            p += 1;
-        } else if let Some(star) = star_idx {
-            p = star + 1;
-            match_idx += 1;
-            t = match_idx;
-        } else {
-            return false;
-        }
-    }
-
-    while p < pat.len() && pat[p] == b'*' {
-        p += 1;
-    }
-    p == pat.len()
-}
-
-fn cmd_list(config_path: Option<&std::path::Path>) -> Result<(), String> {
-    match pinit_core::config::load_config(config_path) {
-        Ok((path, cfg)) => {
-            tracing::debug!(config = %path.display(), "loaded config");
-            println!("config: {}", path.display());
-
-            if !cfg.templates.is_empty() {
-                println!("\ntemplates:");
-                for (name, def) in &cfg.templates {
-                    let source = def.source().unwrap_or("-");
-                    println!(
-                        "  {name} (source: {source}, path: {})",
-                        def.path().display()
-                    );
-                }
-            }
-
-            if !cfg.targets.is_empty() {
-                println!("\ntargets:");
-                for (name, stack) in &cfg.targets {
-                    println!("  {name} = {}", stack.templates().join(" + "));
-                }
-            }
-
-            if !cfg.recipes.is_empty() {
-                println!("\nrecipes:");
-                for (name, recipe) in &cfg.recipes {
-                    let tmpl = if recipe.templates.is_empty() {
-                        "-".to_string()
-                    } else {
-                        recipe.templates.join(" + ")
-                    };
-                    println!(
-                        "  {name} (templates: {tmpl}, filesets: {})",
-                        recipe.files.len()
-                    );
-                }
-            }
-
-            Ok(())
-        }
-        Err(pinit_core::config::ConfigError::NotFound) => {
-            println!("no config found");
-            Ok(())
-        }
-        Err(e) => Err(e.to_string()),
+    if diff.trim().is_empty() {
+        eprintln!("(no textual changes)");
+    } else {
+        eprint!("{diff}");
+    }
+}
+
+fn rel_path_for_match(path: &std::path::Path) -> String {
+    let mut s = path.to_string_lossy().replace('\\', "/");
+    while s.starts_with("./") {
+        s = s[2..].to_string();
     }
+    s.trim_start_matches('/').to_string()
 }
 
 #[cfg(test)]
@@ -928,8 +1805,8 @@ mod tests {
         cmd_new(
             None,
             NewArgs {
-                template: template_dir.to_string_lossy().to_string(),
-                dir: dest.clone(),
+                template: Some(template_dir.to_string_lossy().to_string()),
+                dir: Some(dest.clone()),
                 dry_run: true,
                 yes: true,
                 overwrite: false,
@@ -940,6 +1817,7 @@ mod tests {
                 git: false,
                 no_git: true,
                 branch: "main".to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -959,8 +1837,8 @@ mod tests {
         cmd_new(
             None,
             NewArgs {
-                template: template_dir.to_string_lossy().to_string(),
-                dir: dest.clone(),
+                template: Some(template_dir.to_string_lossy().to_string()),
+                dir: Some(dest.clone()),
                 dry_run: false,
                 yes: true,
                 overwrite: false,
@@ -971,6 +1849,7 @@ mod tests {
                 git: false,
                 no_git: true,
                 branch: "main".to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -995,8 +1874,8 @@ mod tests {
         cmd_new(
             None,
             NewArgs {
-                template: template_dir.to_string_lossy().to_string(),
-                dir: dest.clone(),
+                template: Some(template_dir.to_string_lossy().to_string()),
+                dir: Some(dest.clone()),
                 dry_run: false,
                 yes: true,
                 overwrite: false,
@@ -1007,6 +1886,7 @@ mod tests {
                 git: false,
                 no_git: false,
                 branch: "main".to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -1023,6 +1903,158 @@ mod tests {
         assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "main");
     }
 
+    #[test]
+    fn new_renders_project_variables_from_dir_name() {
+        let root = make_temp_root();
+        let template_dir = root.join("template");
+        let dest = root.join("My-Tool");
+
+        fs::create_dir_all(&template_dir).unwrap();
+        fs::write(
+            template_dir.join("Cargo.toml"),
+            "[package]\nname = \"{{ package_name }}\"\n# {{ crate_name }} ${{ project_name }}\n",
+        )
+        .unwrap();
+
+        cmd_new(
+            None,
+            NewArgs {
+                template: Some(template_dir.to_string_lossy().to_string()),
+                dir: Some(dest.clone()),
+                yes: true,
+                no_git: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dest.join("Cargo.toml")).unwrap(),
+            "[package]\nname = \"my-tool\"\n# my_tool ${{ project_name }}\n"
+        );
+    }
+
+    #[test]
+    fn new_name_flag_overrides_dir_name() {
+        let root = make_temp_root();
+        let template_dir = root.join("template");
+        let dest = root.join("proj");
+
+        fs::create_dir_all(&template_dir).unwrap();
+        fs::write(template_dir.join("README.md"), "# {{project_name}}\n").unwrap();
+
+        cmd_new(
+            None,
+            NewArgs {
+                template: Some(template_dir.to_string_lossy().to_string()),
+                dir: Some(dest.clone()),
+                name: Some("Widget".to_string()),
+                yes: true,
+                no_git: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dest.join("README.md")).unwrap(),
+            "# Widget\n"
+        );
+    }
+
+    #[test]
+    fn project_name_for_uses_name_then_dir() {
+        assert_eq!(
+            project_name_for(Path::new("/tmp/a/proj"), None).unwrap(),
+            "proj"
+        );
+        assert_eq!(
+            project_name_for(Path::new("proj"), Some("other")).unwrap(),
+            "other"
+        );
+        assert!(project_name_for(Path::new("proj"), Some("  ")).is_err());
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            project_name_for(Path::new("."), None).unwrap(),
+            cwd.file_name().unwrap().to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn new_create_remote_runs_command_and_sets_remote_url() {
+        let root = make_temp_root();
+        let template_dir = root.join("template");
+        let dest = root.join("My-Tool");
+        let config_path = root.join("pinit.toml");
+
+        fs::create_dir_all(&template_dir).unwrap();
+        fs::write(template_dir.join("hello.txt"), "hello\n").unwrap();
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+[templates]
+rust = "{}"
+
+[remote]
+command = ["git", "config", "pinit.remote", "{{{{ package_name }}}}"]
+url = "https://example.invalid/me/{{{{ package_name }}}}.git"
+"#,
+                template_dir.display()
+            ),
+        )
+        .unwrap();
+
+        cmd_new(
+            Some(&config_path),
+            NewArgs {
+                template: Some("rust".to_string()),
+                dir: Some(dest.clone()),
+                yes: true,
+                branch: "main".to_string(),
+                create_remote: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let git_out = |args: &[&str]| {
+            let out = ProcessCommand::new("git")
+                .args(args)
+                .current_dir(&dest)
+                .output()
+                .unwrap();
+            assert!(out.status.success());
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        assert_eq!(git_out(&["config", "pinit.remote"]), "my-tool");
+        assert_eq!(
+            git_out(&["remote", "get-url", "origin"]),
+            "https://example.invalid/me/my-tool.git"
+        );
+    }
+
+    #[test]
+    fn new_create_remote_without_config_errors() {
+        let root = make_temp_root();
+        let template_dir = root.join("template");
+        fs::create_dir_all(&template_dir).unwrap();
+
+        let err = cmd_new(
+            None,
+            NewArgs {
+                template: Some(template_dir.to_string_lossy().to_string()),
+                dir: Some(root.join("proj")),
+                dry_run: true,
+                branch: "main".to_string(),
+                create_remote: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(err.contains("--create-remote requires a [remote] section"));
+    }
+
     #[test]
     fn new_writes_license_from_config() {
         let root = make_temp_root();
@@ -1053,8 +2085,8 @@ rust = "{}"
         cmd_new(
             Some(&config_path),
             NewArgs {
-                template: "rust".to_string(),
-                dir: dest.clone(),
+                template: Some("rust".to_string()),
+                dir: Some(dest.clone()),
                 dry_run: false,
                 yes: true,
                 overwrite: false,
@@ -1065,6 +2097,7 @@ rust = "{}"
                 git: false,
                 no_git: true,
                 branch: "main".to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -1074,6 +2107,49 @@ rust = "{}"
         assert!(license.contains("Clay"));
     }
 
+    #[test]
+    fn new_renders_template_variables_in_license_args() {
+        let root = make_temp_root();
+        let template_dir = root.join("template");
+        let dest = root.join("widget");
+        let config_path = root.join("pinit.toml");
+
+        fs::create_dir_all(&template_dir).unwrap();
+        fs::write(template_dir.join("hello.txt"), "hello\n").unwrap();
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+[license]
+spdx = "BSD-3-Clause"
+year = "2025"
+args = {{ owner = "The {{{{ project_name }}}} authors ({{{{ unknown }}}})" }}
+
+[templates]
+rust = "{}"
+"#,
+                template_dir.display()
+            ),
+        )
+        .unwrap();
+
+        cmd_new(
+            Some(&config_path),
+            NewArgs {
+                template: Some("rust".to_string()),
+                dir: Some(dest.clone()),
+                yes: true,
+                no_git: true,
+                branch: "main".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let license = fs::read_to_string(dest.join("LICENSE")).unwrap();
+        assert!(license.contains("Copyright (c) 2025 The widget authors ({{ unknown }})."));
+    }
+
     #[test]
     fn new_runs_after_dir_create_hooks() {
         let root = make_temp_root();
@@ -1103,8 +2179,8 @@ rust = "{}"
         cmd_new(
             Some(&config_path),
             NewArgs {
-                template: "rust".to_string(),
-                dir: dest.clone(),
+                template: Some("rust".to_string()),
+                dir: Some(dest.clone()),
                 dry_run: false,
                 yes: true,
                 overwrite: false,
@@ -1115,6 +2191,7 @@ rust = "{}"
                 git: false,
                 no_git: true,
                 branch: "main".to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -1155,7 +2232,7 @@ run_on = ["update"]
             Some(&config_path),
             ApplyArgs {
                 template: "full".to_string(),
-                dest_dir: Some(dest.clone()),
+                dest: dest.clone().into(),
                 dry_run: false,
                 yes: true,
                 overwrite: false,
@@ -1163,6 +2240,7 @@ run_on = ["update"]
                 skip: false,
                 overrides: Vec::new(),
                 override_action: None,
+                ..Default::default()
             },
         )
         .unwrap();
@@ -1200,7 +2278,7 @@ rust = "{}"
             Some(&config_path),
             ApplyArgs {
                 template: "rust".to_string(),
-                dest_dir: Some(dest.clone()),
+                dest: dest.clone().into(),
                 dry_run: true,
                 yes: true,
                 overwrite: false,
@@ -1208,6 +2286,7 @@ rust = "{}"
                 skip: false,
                 overrides: Vec::new(),
                 override_action: None,
+                ..Default::default()
             },
         )
         .unwrap();
@@ -1232,29 +2311,256 @@ rust = "{}"
             ],
         );
 
-        let ctx_overwrite = ExistingFileDecisionContext {
-            template_name: Some("rust"),
-            template_index: Some(1),
-            rel_path: Path::new("a.txt"),
-            dest_path: Path::new("/tmp/a.txt"),
-            src_bytes: b"new",
-            dest_bytes: b"old",
-            merge_bytes: None,
+        let ctx = |rel_path: &'static str| {
+            let mut ctx = ExistingFileDecisionContext::new(
+                Path::new(rel_path),
+                Path::new("/tmp/dest"),
+                b"new",
+                b"old",
+            );
+            ctx.template_name = Some("rust");
+            ctx.template_index = Some(1);
+            ctx
+        };
+        assert_eq!(decider.decide(ctx("a.txt")), ExistingFileAction::Overwrite);
+        // The merge rule matches, but there is nothing merged to write.
+        assert_eq!(decider.decide(ctx("b.txt")), ExistingFileAction::Skip);
+    }
+
+    #[test]
+    fn binary_action_applies_only_to_binary_files() {
+        let ctx = |is_binary| {
+            let mut ctx = ExistingFileDecisionContext::new(
+                Path::new("logo.png"),
+                Path::new("/tmp/logo.png"),
+                b"\0new",
+                b"\0old!",
+            );
+            ctx.template_name = Some("assets");
+            ctx.template_index = Some(0);
+            ctx.is_binary = is_binary;
+            ctx
+        };
+
+        let mut decider = CliDecider::new(ExistingFileAction::Skip, true, Vec::new())
+            .with_binary_action(Some(BinaryAction::Overwrite));
+        assert_eq!(decider.decide(ctx(true)), ExistingFileAction::Overwrite);
+        assert_eq!(decider.decide(ctx(false)), ExistingFileAction::Skip);
+
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new())
+            .with_binary_action(Some(BinaryAction::Skip));
+        assert_eq!(decider.decide(ctx(true)), ExistingFileAction::Skip);
+        assert_eq!(decider.decide(ctx(false)), ExistingFileAction::Overwrite);
+    }
+
+    #[test]
+    fn gitattributes_merge_drivers_pick_the_action() {
+        let ctx = |merge_attr, merge_bytes| {
+            let mut ctx = ExistingFileDecisionContext::new(
+                Path::new("config.toml"),
+                Path::new("/tmp/config.toml"),
+                b"new",
+                b"old",
+            );
+            ctx.template_name = Some("rust");
+            ctx.template_index = Some(0);
+            ctx.merge_bytes = merge_bytes;
+            ctx.merge_attr = merge_attr;
+            ctx
         };
-        assert_eq!(decider.decide(ctx_overwrite), ExistingFileAction::Overwrite);
-
-        let ctx_merge_unavailable = ExistingFileDecisionContext {
-            template_name: Some("rust"),
-            template_index: Some(1),
-            rel_path: Path::new("b.txt"),
-            dest_path: Path::new("/tmp/b.txt"),
-            src_bytes: b"new",
-            dest_bytes: b"old",
-            merge_bytes: None,
+
+        // Without an action flag, the attribute answers instead of the prompt.
+        let mut decider = CliDecider::new(ExistingFileAction::Merge, false, Vec::new());
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Ours), Some(b"merged"))),
+            ExistingFileAction::Skip
+        );
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Theirs), Some(b"merged"))),
+            ExistingFileAction::Overwrite
+        );
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Union), Some(b"merged"))),
+            ExistingFileAction::Merge
+        );
+
+        // Bare `--yes` does not choose an action, so the attributes still answer.
+        let mut decider = CliDecider::new(ExistingFileAction::Merge, true, Vec::new());
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Ours), Some(b"merged"))),
+            ExistingFileAction::Skip
+        );
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Theirs), Some(b"merged"))),
+            ExistingFileAction::Overwrite
+        );
+
+        // `--skip`, `--overwrite`, and `--merge` beat the repo's attributes.
+        let mut decider =
+            CliDecider::new(ExistingFileAction::Skip, true, Vec::new()).with_explicit_action(true);
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Theirs), Some(b"merged"))),
+            ExistingFileAction::Skip
+        );
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Union), Some(b"merged"))),
+            ExistingFileAction::Skip
+        );
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new())
+            .with_explicit_action(true);
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Ours), Some(b"merged"))),
+            ExistingFileAction::Overwrite
+        );
+        let mut decider =
+            CliDecider::new(ExistingFileAction::Merge, true, Vec::new()).with_explicit_action(true);
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Ours), Some(b"merged"))),
+            ExistingFileAction::Merge
+        );
+
+        // Explicit override rules beat the repo's attributes.
+        let mut decider = CliDecider::new(
+            ExistingFileAction::Skip,
+            false,
+            vec![OverrideRule {
+                pattern: "config.toml".to_string(),
+                action: OverrideAction::Overwrite,
+            }],
+        );
+        assert_eq!(
+            decider.decide(ctx(Some(&MergeAttribute::Ours), None)),
+            ExistingFileAction::Overwrite
+        );
+    }
+
+    #[test]
+    fn existing_lockfiles_are_kept_unless_opted_in() {
+        let ctx = |rel_path| {
+            let mut ctx =
+                ExistingFileDecisionContext::new(rel_path, Path::new("/tmp/x"), b"new", b"old");
+            ctx.template_name = Some("rust");
+            ctx.template_index = Some(0);
+            ctx.merge_bytes = Some(b"merged");
+            ctx
         };
+
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new());
+        assert_eq!(
+            decider.decide(ctx(Path::new("Cargo.lock"))),
+            ExistingFileAction::Skip
+        );
         assert_eq!(
-            decider.decide(ctx_merge_unavailable),
+            decider.decide(ctx(Path::new("web/package-lock.json"))),
             ExistingFileAction::Skip
         );
+        assert_eq!(
+            decider.decide(ctx(Path::new("Cargo.toml"))),
+            ExistingFileAction::Overwrite
+        );
+
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new())
+            .with_apply_lockfiles(true);
+        assert_eq!(
+            decider.decide(ctx(Path::new("Cargo.lock"))),
+            ExistingFileAction::Overwrite
+        );
+
+        // An explicit override still wins over the default.
+        let mut decider = CliDecider::new(
+            ExistingFileAction::Skip,
+            true,
+            vec![OverrideRule {
+                pattern: "**/yarn.lock".to_string(),
+                action: OverrideAction::Merge,
+            }],
+        );
+        assert_eq!(
+            decider.decide(ctx(Path::new("yarn.lock"))),
+            ExistingFileAction::Merge
+        );
+    }
+
+    #[test]
+    fn template_policy_only_suggests_an_answer_for_its_own_template() {
+        let ctx = |template_index, rel_path| {
+            let mut ctx =
+                ExistingFileDecisionContext::new(rel_path, Path::new("/tmp/dest"), b"new", b"old");
+            ctx.template_name = Some("rust");
+            ctx.template_index = Some(template_index);
+            ctx.merge_bytes = Some(b"merged");
+            ctx
+        };
+        let policy = vec![OverrideRule {
+            pattern: "*".to_string(),
+            action: OverrideAction::Overwrite,
+        }];
+        let ci = Path::new("ci.yml");
+        let lock = Path::new("Cargo.lock");
+
+        // When asking, the policy is the suggested answer, but only for template 1.
+        let mut decider = CliDecider::new(ExistingFileAction::Merge, false, Vec::new())
+            .with_template_policies(vec![Vec::new(), policy.clone()]);
+        decider.review = review::Review::Collect(Vec::new());
+        assert_eq!(decider.decide(ctx(0, ci)), ExistingFileAction::Merge);
+        assert_eq!(decider.decide(ctx(1, ci)), ExistingFileAction::Overwrite);
+
+        // The built-in lockfile default beats it.
+        assert_eq!(decider.decide(ctx(1, lock)), ExistingFileAction::Skip);
+
+        // Under `--yes`, it answers in place of the default action.
+        let mut decider = CliDecider::new(ExistingFileAction::Merge, true, Vec::new())
+            .with_template_policies(vec![Vec::new(), policy.clone()]);
+        assert_eq!(decider.decide(ctx(0, ci)), ExistingFileAction::Merge);
+        assert_eq!(decider.decide(ctx(1, ci)), ExistingFileAction::Overwrite);
+
+        // An action flag beats it.
+        let mut decider = CliDecider::new(ExistingFileAction::Skip, true, Vec::new())
+            .with_explicit_action(true)
+            .with_template_policies(vec![policy]);
+        assert_eq!(decider.decide(ctx(0, ci)), ExistingFileAction::Skip);
+    }
+
+    #[test]
+    fn license_conflict_policy_sees_the_existing_license() {
+        let same = pinit_core::LicenseConflict {
+            spdx: "MIT".to_string(),
+            existing_spdx: Some("MIT".to_string()),
+        };
+        let other = pinit_core::LicenseConflict {
+            spdx: "MIT".to_string(),
+            existing_spdx: Some("Apache-2.0".to_string()),
+        };
+        let ctx = |license| {
+            let mut ctx = ExistingFileDecisionContext::new(
+                Path::new("LICENSE"),
+                Path::new("/tmp/LICENSE"),
+                b"new",
+                b"old",
+            );
+            ctx.license = license;
+            ctx
+        };
+
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new())
+            .with_license_conflict(Some(LicenseConflictAction::Keep));
+        assert_eq!(decider.decide(ctx(Some(&same))), ExistingFileAction::Skip);
+        // Only license files follow the license policy.
+        assert_eq!(decider.decide(ctx(None)), ExistingFileAction::Overwrite);
+
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new())
+            .with_license_conflict(Some(LicenseConflictAction::Matching));
+        assert_eq!(
+            decider.decide(ctx(Some(&same))),
+            ExistingFileAction::Overwrite
+        );
+        assert_eq!(decider.decide(ctx(Some(&other))), ExistingFileAction::Skip);
+
+        // Unset falls through to the usual flow.
+        let mut decider = CliDecider::new(ExistingFileAction::Overwrite, true, Vec::new());
+        assert_eq!(
+            decider.decide(ctx(Some(&other))),
+            ExistingFileAction::Overwrite
+        );
     }
 }
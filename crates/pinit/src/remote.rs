@@ -0,0 +1,190 @@
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+use std::process::Command as ProcessCommand;
+
+use pinit_core::config::RemoteDef;
+use pinit_core::vars::{self, Variables};
+
+use crate::format_command;
+
+/// Run the configured remote creation command and register the git remote.
+pub(crate) fn create_remote(
+    def: &RemoteDef,
+    dest_dir: &Path,
+    variables: &Variables,
+    dry_run: bool,
+) -> Result<(), String> {
+    let command: Vec<String> = def
+        .command
+        .iter()
+        .map(|arg| render_arg(arg, variables))
+        .collect();
+    let url = def.url.as_deref().map(|url| render_arg(url, variables));
+    let remote_name = def.remote_name();
+
+    if dry_run {
+        if !command.is_empty() {
+            note!("dry-run: would create remote: {}", format_command(&command));
+        }
+        if let Some(url) = &url {
+            note!("dry-run: would set git remote {remote_name} to {url}");
+        }
+        return Ok(());
+    }
+
+    if let Some(program) = command.first() {
+        tracing::info!(
+            command = %format_command(&command),
+            cwd = %dest_dir.display(),
+            "create remote"
+        );
+        let output = ProcessCommand::new(program)
+            .args(&command[1..])
+            .current_dir(dest_dir)
+            .env("PINIT_PHASE", "create_remote")
+            .env("PINIT_DEST", dest_dir)
+            .output()
+            .map_err(|e| {
+                format!(
+                    "failed to run remote command ({}): {e}",
+                    format_command(&command)
+                )
+            })?;
+        if !output.status.success() {
+            return Err(format!(
+                "remote command failed ({}): {}",
+                output.status.code().unwrap_or(1),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+
+    let existing = remote_url(dest_dir, remote_name);
+    match (url, existing) {
+        (Some(url), Some(current)) if current == url => Ok(()),
+        (Some(url), Some(_)) => git(dest_dir, &["remote", "set-url", remote_name, &url]),
+        (Some(url), None) => git(dest_dir, &["remote", "add", remote_name, &url]),
+        (None, Some(_)) => Ok(()),
+        (None, None) => {
+            note!(
+                "warning: remote command did not configure git remote {remote_name}; set remote.url to add it"
+            );
+            Ok(())
+        }
+    }
+}
+
+fn remote_url(dir: &Path, name: &str) -> Option<String> {
+    let out = ProcessCommand::new("git")
+        .args(["remote", "get-url", name])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Commit the new project, make sure the remote exists, and push `branch`.
+///
+/// Runs `remote.protect` afterwards when configured; a failing protect command
+/// only warns because the push has already happened.
+pub(crate) fn push_initial(
+    def: Option<&RemoteDef>,
+    dest_dir: &Path,
+    branch: &str,
+    variables: &Variables,
+    dry_run: bool,
+) -> Result<(), String> {
+    let remote_name = def.map_or("origin", RemoteDef::remote_name);
+    let message = def
+        .and_then(|d| d.commit_message.as_deref())
+        .unwrap_or(DEFAULT_COMMIT_MESSAGE);
+    let url = def
+        .and_then(|d| d.url.as_deref())
+        .map(|url| render_arg(url, variables));
+    let mut protect_vars = variables.clone();
+    protect_vars.insert("branch".to_string(), branch.to_string());
+    let protect: Vec<String> = def
+        .map(|d| {
+            d.protect
+                .iter()
+                .map(|arg| render_arg(arg, &protect_vars))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if dry_run {
+        note!("dry-run: would commit all files ({message:?})");
+        note!("dry-run: would push {branch} to {remote_name}");
+        if !protect.is_empty() {
+            note!("dry-run: would run protect: {}", format_command(&protect));
+        }
+        return Ok(());
+    }
+
+    // Without a remote there is nothing to push to, so do not leave a commit behind either.
+    if remote_url(dest_dir, remote_name).is_none() {
+        let Some(url) = url else {
+            return Err(format!(
+                "--push needs git remote {remote_name}; set remote.url or use --create-remote"
+            ));
+        };
+        git(dest_dir, &["remote", "add", remote_name, &url])?;
+    }
+
+    git(dest_dir, &["add", "--all"])?;
+    git(dest_dir, &["commit", "--allow-empty", "-m", message])?;
+
+    tracing::info!(remote = %remote_name, branch = %branch, "push");
+    git(dest_dir, &["push", "--set-upstream", remote_name, branch])?;
+
+    if let Some(program) = protect.first() {
+        tracing::info!(command = %format_command(&protect), "protect branch");
+        let result = ProcessCommand::new(program)
+            .args(&protect[1..])
+            .current_dir(dest_dir)
+            .env("PINIT_PHASE", "protect")
+            .env("PINIT_DEST", dest_dir)
+            .env("PINIT_BRANCH", branch)
+            .output();
+        match result {
+            Ok(out) if out.status.success() => {}
+            Ok(out) => note!(
+                "warning: protect command failed ({}): {}",
+                out.status.code().unwrap_or(1),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+            Err(e) => note!(
+                "warning: failed to run protect command ({}): {e}",
+                format_command(&protect)
+            ),
+        }
+    }
+    Ok(())
+}
+
+const DEFAULT_COMMIT_MESSAGE: &str = "Initial commit";
+
+fn render_arg(arg: &str, variables: &Variables) -> String {
+    vars::render(arg, variables).unwrap_or_else(|| arg.to_string())
+}
+
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<(), String> {
+    tracing::debug!(args = ?args, "git");
+    let out = ProcessCommand::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("failed to run git: {e}"))?;
+    if !out.status.success() {
+        return Err(format!(
+            "git {} failed ({}): {}",
+            args.join(" "),
+            out.status.code().unwrap_or(1),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(())
+}
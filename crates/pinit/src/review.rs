@@ -0,0 +1,209 @@
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use pinit_core::{ExistingFileAction, ExistingFileDecisionContext};
+
+use crate::style::{self, Marker, Stream};
+
+/// Which file a decision is for: the template index (`None` for generated files) and path.
+pub(crate) type ConflictKey = (Option<usize>, PathBuf);
+
+/// `apply --review`: conflicts are collected on a dry pass, reviewed, then applied.
+#[derive(Debug, Default)]
+pub(crate) enum Review {
+    /// Ask about each conflict as it comes up.
+    #[default]
+    Off,
+    /// Dry pass: remember each conflict instead of asking.
+    Collect(Vec<Conflict>),
+    /// Real pass: use the reviewed decisions; anything new is skipped and reported.
+    Decided(BTreeMap<ConflictKey, ExistingFileAction>),
+}
+
+/// An existing file that needs a decision, captured during the dry pass.
+#[derive(Debug)]
+pub(crate) struct Conflict {
+    key: ConflictKey,
+    template: Option<String>,
+    src: Vec<u8>,
+    dest: Vec<u8>,
+    merged: Option<Vec<u8>>,
+    action: ExistingFileAction,
+}
+
+impl Conflict {
+    /// The conflict in `ctx`, starting out at `default` (skip when a merge is unavailable).
+    pub(crate) fn new(ctx: &ExistingFileDecisionContext<'_>, default: ExistingFileAction) -> Self {
+        let action = if default == ExistingFileAction::Merge && ctx.merge_bytes.is_none() {
+            ExistingFileAction::Skip
+        } else {
+            default
+        };
+        Self {
+            key: key(ctx),
+            template: ctx.template_name.map(str::to_string),
+            src: ctx.src_bytes.to_vec(),
+            dest: ctx.dest_bytes.to_vec(),
+            merged: ctx.merge_bytes.map(<[u8]>::to_vec),
+            action,
+        }
+    }
+
+    pub(crate) fn action(&self) -> ExistingFileAction {
+        self.action
+    }
+
+    fn label(&self) -> String {
+        let path = self.key.1.display();
+        let mut label = match &self.template {
+            Some(template) => format!("{path} ({template})"),
+            None => path.to_string(),
+        };
+        if self.merged.is_none() {
+            label.push_str(" [merge unavailable]");
+        }
+        label
+    }
+
+    /// Set the action, refusing a merge that is not available.
+    fn set(&mut self, action: ExistingFileAction) -> bool {
+        if action == ExistingFileAction::Merge && self.merged.is_none() {
+            eprintln!(
+                "merge is unavailable for {}; choose overwrite or skip.",
+                self.key.1.display()
+            );
+            return false;
+        }
+        self.action = action;
+        true
+    }
+}
+
+pub(crate) fn key(ctx: &ExistingFileDecisionContext<'_>) -> ConflictKey {
+    (ctx.template_index, ctx.rel_path.to_path_buf())
+}
+
+/// Show the queue until the user applies it (the decisions) or quits (an error).
+pub(crate) fn run_queue(
+    mut conflicts: Vec<Conflict>,
+) -> Result<BTreeMap<ConflictKey, ExistingFileAction>, String> {
+    let cancelled = || "review cancelled; nothing was written".to_string();
+    loop {
+        print_queue(&conflicts);
+        eprintln!("choose: <n> review a file, <n> m|o|s set it, all m|o|s, (a)pply, (q)uit");
+        let Some(line) = read_choice() else {
+            return Err(cancelled());
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["a"] => break,
+            ["q"] => return Err(cancelled()),
+            ["all", letter] => match parse_action(letter) {
+                Some(action) => {
+                    for conflict in &mut conflicts {
+                        // Files without a merge keep their answer under `all m`.
+                        if action != ExistingFileAction::Merge || conflict.merged.is_some() {
+                            conflict.action = action;
+                        }
+                    }
+                }
+                None => eprintln!("unknown action: {letter}"),
+            },
+            [n] | [n, _] => {
+                let Some(conflict) = n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| conflicts.get_mut(i))
+                else {
+                    eprintln!("unknown choice: {line}");
+                    continue;
+                };
+                match words.get(1) {
+                    Some(letter) => match parse_action(letter) {
+                        Some(action) => {
+                            conflict.set(action);
+                        }
+                        None => eprintln!("unknown action: {letter}"),
+                    },
+                    None => review_one(conflict),
+                }
+            }
+            _ => eprintln!("unknown choice: {line}"),
+        }
+    }
+    Ok(conflicts.into_iter().map(|c| (c.key, c.action)).collect())
+}
+
+fn print_queue(conflicts: &[Conflict]) {
+    let count = |action| conflicts.iter().filter(|c| c.action == action).count();
+    eprintln!();
+    eprintln!(
+        "{} conflict(s): {} merge, {} overwrite, {} skip",
+        conflicts.len(),
+        count(ExistingFileAction::Merge),
+        count(ExistingFileAction::Overwrite),
+        count(ExistingFileAction::Skip)
+    );
+    for (i, conflict) in conflicts.iter().enumerate() {
+        eprintln!(
+            "{:>4}. {} {:<9} {}",
+            i + 1,
+            style::marker(Marker::for_action(conflict.action), Stream::Stderr),
+            conflict.action.as_str(),
+            conflict.label()
+        );
+    }
+}
+
+/// Look at one file, with diffs on request, until it has an answer or the user goes back.
+fn review_one(conflict: &mut Conflict) {
+    loop {
+        eprintln!();
+        eprintln!("file: {} [{}]", conflict.label(), conflict.action.as_str());
+        eprintln!("choose: {}, (d)iff, (b)ack", style::action_choices());
+        let Some(choice) = read_choice() else {
+            return;
+        };
+        match choice.as_str() {
+            "b" | "" => return,
+            "d" => crate::print_diffs(
+                &conflict.key.1,
+                &conflict.dest,
+                &conflict.src,
+                conflict.merged.as_deref(),
+            ),
+            letter => match parse_action(letter) {
+                Some(action) => {
+                    if conflict.set(action) {
+                        return;
+                    }
+                }
+                None => eprintln!("unknown choice: {letter}"),
+            },
+        }
+    }
+}
+
+fn parse_action(letter: &str) -> Option<ExistingFileAction> {
+    match letter {
+        "m" => Some(ExistingFileAction::Merge),
+        "o" => Some(ExistingFileAction::Overwrite),
+        "s" => Some(ExistingFileAction::Skip),
+        _ => None,
+    }
+}
+
+/// The next trimmed, lowercased line from stdin; `None` at end of input.
+fn read_choice() -> Option<String> {
+    eprint!("> ");
+    let _ = std::io::stderr().flush();
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_ascii_lowercase()),
+    }
+}
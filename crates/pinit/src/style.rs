@@ -0,0 +1,109 @@
+#![forbid(unsafe_code)]
+
+use std::io::IsTerminal;
+
+use pinit_core::{EntryOutcome, ExistingFileAction, ReportEntry};
+
+/// The one-letter markers used wherever files are listed by what happened to them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Marker {
+    Added,
+    Merged,
+    Overwritten,
+    Skipped,
+    Ignored,
+}
+
+impl Marker {
+    pub(crate) fn letter(self) -> char {
+        match self {
+            Marker::Added => 'A',
+            Marker::Merged => 'M',
+            Marker::Overwritten => 'O',
+            Marker::Skipped => 'S',
+            Marker::Ignored => 'I',
+        }
+    }
+
+    /// ANSI SGR parameters for the marker.
+    fn sgr(self) -> &'static str {
+        match self {
+            Marker::Added => "1;32",
+            Marker::Merged => "1;36",
+            Marker::Overwritten => "1;33",
+            Marker::Skipped => "1;34",
+            Marker::Ignored => "2",
+        }
+    }
+
+    pub(crate) fn for_action(action: ExistingFileAction) -> Self {
+        match action {
+            ExistingFileAction::Merge => Marker::Merged,
+            ExistingFileAction::Overwrite => Marker::Overwritten,
+            ExistingFileAction::Skip => Marker::Skipped,
+        }
+    }
+
+    /// Updated files without a recorded action count as overwritten.
+    pub(crate) fn for_entry(entry: &ReportEntry) -> Self {
+        match entry.outcome {
+            EntryOutcome::Created => Marker::Added,
+            EntryOutcome::Updated => match entry.action {
+                Some(ExistingFileAction::Merge) => Marker::Merged,
+                _ => Marker::Overwritten,
+            },
+            EntryOutcome::Skipped => Marker::Skipped,
+            EntryOutcome::Ignored(_) => Marker::Ignored,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Whether to color output on `stream`: `NO_COLOR` turns it off, `CLICOLOR_FORCE` on,
+/// and otherwise only terminals (other than `TERM=dumb`) get color.
+pub(crate) fn enabled(stream: Stream) -> bool {
+    let set = |name| std::env::var_os(name).is_some_and(|v| !v.is_empty() && v != "0");
+    if set("NO_COLOR") {
+        return false;
+    }
+    if set("CLICOLOR_FORCE") {
+        return true;
+    }
+    if std::env::var_os("TERM").is_some_and(|t| t == "dumb") {
+        return false;
+    }
+    match stream {
+        Stream::Stdout => std::io::stdout().is_terminal(),
+        Stream::Stderr => std::io::stderr().is_terminal(),
+    }
+}
+
+/// `text` in the marker's color, when `stream` gets color.
+pub(crate) fn paint(text: &str, marker: Marker, stream: Stream) -> String {
+    if enabled(stream) {
+        format!("\x1b[{}m{text}\x1b[0m", marker.sgr())
+    } else {
+        text.to_string()
+    }
+}
+
+/// The marker's letter, colored when `stream` gets color.
+pub(crate) fn marker(marker: Marker, stream: Stream) -> String {
+    paint(&marker.letter().to_string(), marker, stream)
+}
+
+/// "(m)erge, (o)verwrite, (s)kip" with each choice key in its marker's color.
+pub(crate) fn action_choices() -> String {
+    let key = |letter: &str, m: Marker| paint(letter, m, Stream::Stderr);
+    format!(
+        "({})erge, ({})verwrite, ({})kip",
+        key("m", Marker::Merged),
+        key("o", Marker::Overwritten),
+        key("s", Marker::Skipped)
+    )
+}
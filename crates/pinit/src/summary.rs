@@ -0,0 +1,641 @@
+#![forbid(unsafe_code)]
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+
+use pinit_core::resolve::ResolvedTemplate;
+use pinit_core::timing::Timings;
+use pinit_core::{
+    ApplyReport, EntryOutcome, IgnoreReason, PrunedDir, ReportEntry, Warning, WarningKind,
+};
+use serde_json::{Value, json};
+
+/// What happened when a hook ran.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum HookResult {
+    Ok,
+    /// Failed, but the hook sets `allow_failure`.
+    AllowedFailure(String),
+    /// Not run because of `--dry-run`.
+    DryRun,
+}
+
+/// One hook invocation, for the Markdown summary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct HookOutcome {
+    pub(crate) phase: String,
+    pub(crate) command: String,
+    pub(crate) result: HookResult,
+}
+
+impl HookOutcome {
+    /// The warning for a failure that `allow_failure` let through.
+    pub(crate) fn warning(&self) -> Option<Warning> {
+        match &self.result {
+            HookResult::AllowedFailure(message) => {
+                Some(Warning::new(WarningKind::HookFailed, message.as_str()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A template that was applied, with the revision it was applied at.
+pub(crate) struct AppliedTemplate {
+    name: String,
+    revision: Option<String>,
+    dest_prefix: Option<PathBuf>,
+}
+
+impl AppliedTemplate {
+    pub(crate) fn from_resolved(entry: &ResolvedTemplate) -> Self {
+        Self {
+            name: entry.name.clone(),
+            revision: short_revision(&entry.dir),
+            dest_prefix: entry.dest_prefix.clone(),
+        }
+    }
+}
+
+/// Everything the Markdown and JSON summaries report about one apply.
+pub(crate) struct ApplySummary<'a> {
+    pub(crate) template: &'a str,
+    pub(crate) dry_run: bool,
+    pub(crate) branch: Option<&'a str>,
+    pub(crate) templates: Vec<AppliedTemplate>,
+    pub(crate) report: &'a ApplyReport,
+    pub(crate) hooks: &'a [HookOutcome],
+    /// Skipped files that were to be merged, but had no merge available.
+    pub(crate) merge_unavailable: &'a [PathBuf],
+    pub(crate) warnings: &'a [Warning],
+    pub(crate) timings: &'a Timings,
+    /// Include the bytes written and the largest files (`--sizes`).
+    pub(crate) sizes: bool,
+}
+
+impl ApplySummary<'_> {
+    /// Render a Markdown summary suitable for a pull request description.
+    pub(crate) fn markdown(&self) -> String {
+        let mut out = String::new();
+        let suffix = if self.dry_run { " (dry run)" } else { "" };
+        let _ = writeln!(out, "## pinit apply `{}`{suffix}", self.template);
+        out.push('\n');
+        if let Some(branch) = self.branch {
+            let _ = writeln!(out, "Branch: `{branch}`\n");
+        }
+
+        if !self.templates.is_empty() {
+            out.push_str("| Template | Revision | Destination |\n");
+            out.push_str("|----------|----------|-------------|\n");
+            for t in &self.templates {
+                let revision = t
+                    .revision
+                    .as_deref()
+                    .map_or_else(|| "-".to_string(), |r| format!("`{r}`"));
+                let dest = t
+                    .dest_prefix
+                    .as_deref()
+                    .map_or_else(|| ".".to_string(), |p| format!("{}/", display(p)));
+                let _ = writeln!(out, "| {} | {revision} | `{dest}` |", t.name);
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(
+            out,
+            "{} created, {} updated, {} skipped{}, {}.\n",
+            self.report.created_files,
+            self.report.updated_files,
+            self.report.skipped_files,
+            merge_unavailable_count(self.merge_unavailable),
+            ignored_counts(self.report)
+        );
+
+        let breakdown = template_breakdown(self.report);
+        if spans_templates(&breakdown) {
+            out.push_str("| Template | Created | Updated | Skipped | Ignored |\n");
+            out.push_str("|----------|--------:|--------:|--------:|--------:|\n");
+            for row in &breakdown {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | {} |",
+                    row.name(),
+                    row.created,
+                    row.updated,
+                    row.skipped,
+                    row.ignored
+                );
+            }
+            out.push('\n');
+        }
+
+        let paths = |wanted: fn(&EntryOutcome) -> bool| -> Vec<String> {
+            self.report
+                .entries
+                .iter()
+                .filter(|e| wanted(&e.outcome))
+                .map(|e| display(&e.path))
+                .collect()
+        };
+        write_section(&mut out, "Created", &paths(|o| *o == EntryOutcome::Created));
+        write_section(&mut out, "Updated", &paths(|o| *o == EntryOutcome::Updated));
+        let skipped = paths(|o| *o == EntryOutcome::Skipped);
+        if !skipped.is_empty() {
+            let _ = writeln!(
+                out,
+                "<details><summary>Skipped ({})</summary>\n",
+                skipped.len()
+            );
+            for path in &skipped {
+                let _ = writeln!(out, "- `{path}`");
+            }
+            out.push_str("\n</details>\n\n");
+        }
+        let ignored: Vec<&ReportEntry> = self
+            .report
+            .entries
+            .iter()
+            .filter(|e| matches!(e.outcome, EntryOutcome::Ignored(_)))
+            .collect();
+        if !ignored.is_empty() {
+            let _ = writeln!(
+                out,
+                "<details><summary>Ignored ({})</summary>\n",
+                ignored.len()
+            );
+            for entry in ignored {
+                let _ = writeln!(out, "- {}", ignored_line(entry));
+            }
+            out.push_str("\n</details>\n\n");
+        }
+
+        if self.sizes {
+            let _ = writeln!(
+                out,
+                "### Size\n\n{}\n",
+                size_line(self.dry_run, self.report)
+            );
+            let largest = self.report.largest_files(LARGEST_FILES);
+            if !largest.is_empty() {
+                out.push_str("| File | Size |\n");
+                out.push_str("|------|-----:|\n");
+                for entry in largest {
+                    let bytes = format_size(entry.bytes.unwrap_or(0));
+                    let _ = writeln!(out, "| `{}` | {bytes} |", display(&entry.path));
+                }
+                out.push('\n');
+            }
+        }
+
+        if !self.hooks.is_empty() {
+            out.push_str("### Hooks\n\n");
+            out.push_str("| Phase | Command | Result |\n");
+            out.push_str("|-------|---------|--------|\n");
+            for hook in self.hooks {
+                let result = match &hook.result {
+                    HookResult::Ok => "ok".to_string(),
+                    HookResult::AllowedFailure(message) => {
+                        format!("failed (allowed): {}", message.replace('\n', " "))
+                    }
+                    HookResult::DryRun => "not run (dry run)".to_string(),
+                };
+                let _ = writeln!(out, "| {} | `{}` | {result} |", hook.phase, hook.command);
+            }
+            out.push('\n');
+        }
+
+        while out.ends_with("\n\n") {
+            out.pop();
+        }
+        out
+    }
+
+    /// Render the counts as one line of `key=value` pairs, for `--summary line`.
+    pub(crate) fn line(&self) -> String {
+        format!(
+            "created={} updated={} skipped={} ignored={} merge_unavailable={} warnings={} dry_run={}",
+            self.report.created_files,
+            self.report.updated_files,
+            self.report.skipped_files,
+            self.report.ignored_paths,
+            self.merge_unavailable.len(),
+            self.warnings.len(),
+            self.dry_run
+        )
+    }
+
+    /// Render the summary as JSON for other tooling; times are in milliseconds.
+    pub(crate) fn json(&self) -> Value {
+        let templates: Vec<Value> = self
+            .templates
+            .iter()
+            .map(|t| {
+                json!({
+                    "name": t.name,
+                    "revision": t.revision,
+                    "dest_prefix": t.dest_prefix.as_deref().map(display),
+                })
+            })
+            .collect();
+        let files: Vec<Value> = self
+            .report
+            .entries
+            .iter()
+            .map(|e| {
+                let outcome = match e.outcome {
+                    EntryOutcome::Created => "created",
+                    EntryOutcome::Updated => "updated",
+                    EntryOutcome::Skipped => "skipped",
+                    EntryOutcome::Ignored(_) => "ignored",
+                };
+                json!({ "path": display(&e.path), "outcome": outcome })
+            })
+            .collect();
+        let per_template: Vec<Value> = template_breakdown(self.report)
+            .iter()
+            .map(|row| {
+                json!({
+                    "template": row.template,
+                    "created": row.created,
+                    "updated": row.updated,
+                    "skipped": row.skipped,
+                    "ignored": row.ignored,
+                })
+            })
+            .collect();
+        let hooks: Vec<Value> = self
+            .hooks
+            .iter()
+            .map(|hook| {
+                let (result, message) = match &hook.result {
+                    HookResult::Ok => ("ok", None),
+                    HookResult::AllowedFailure(message) => ("allowed_failure", Some(message)),
+                    HookResult::DryRun => ("dry_run", None),
+                };
+                json!({
+                    "phase": hook.phase,
+                    "command": hook.command,
+                    "result": result,
+                    "message": message,
+                })
+            })
+            .collect();
+        let warnings: Vec<Value> = self
+            .warnings
+            .iter()
+            .map(|w| {
+                json!({
+                    "kind": w.kind.as_str(),
+                    "path": w.path.as_deref().map(display),
+                    "message": w.message,
+                })
+            })
+            .collect();
+        let millis = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        let mut timings: serde_json::Map<String, Value> = self
+            .timings
+            .iter()
+            .map(|(phase, elapsed)| (phase.as_str().to_string(), json!(millis(elapsed))))
+            .collect();
+        timings.insert("total".to_string(), json!(millis(self.timings.total())));
+        let sizes = self.sizes.then(|| {
+            let largest: Vec<Value> = self
+                .report
+                .largest_files(LARGEST_FILES)
+                .into_iter()
+                .map(|e| json!({ "path": display(&e.path), "bytes": e.bytes }))
+                .collect();
+            json!({ "bytes_written": self.report.bytes_written(), "largest": largest })
+        });
+        json!({
+            "template": self.template,
+            "dry_run": self.dry_run,
+            "branch": self.branch,
+            "templates": templates,
+            "created": self.report.created_files,
+            "updated": self.report.updated_files,
+            "skipped": self.report.skipped_files,
+            "ignored": self.report.ignored_paths,
+            "merge_unavailable": self.merge_unavailable.iter().map(|p| display(p)).collect::<Vec<_>>(),
+            "per_template": per_template,
+            "files": files,
+            "hooks": hooks,
+            "warnings": warnings,
+            "timings_ms": timings,
+            "sizes": sizes,
+        })
+    }
+}
+
+/// Per-template counts, for showing which member of a stack produced the changes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct TemplateCounts<'a> {
+    /// `None` for generated files (`.gitignore`, `LICENSE`).
+    pub(crate) template: Option<&'a str>,
+    pub(crate) created: usize,
+    pub(crate) updated: usize,
+    pub(crate) skipped: usize,
+    pub(crate) ignored: usize,
+}
+
+impl TemplateCounts<'_> {
+    pub(crate) fn name(&self) -> &str {
+        self.template.unwrap_or("(generated)")
+    }
+}
+
+/// Counts per template, in the order the templates were applied.
+pub(crate) fn template_breakdown(report: &ApplyReport) -> Vec<TemplateCounts<'_>> {
+    let mut rows: Vec<TemplateCounts<'_>> = Vec::new();
+    for entry in &report.entries {
+        let template = entry.template.as_deref();
+        let i = match rows.iter().position(|r| r.template == template) {
+            Some(i) => i,
+            None => {
+                rows.push(TemplateCounts {
+                    template,
+                    ..Default::default()
+                });
+                rows.len() - 1
+            }
+        };
+        let row = &mut rows[i];
+        match entry.outcome {
+            EntryOutcome::Created => row.created += 1,
+            EntryOutcome::Updated => row.updated += 1,
+            EntryOutcome::Skipped => row.skipped += 1,
+            EntryOutcome::Ignored(_) => row.ignored += 1,
+        }
+    }
+    rows
+}
+
+/// Whether the breakdown is worth showing: more than one template produced entries.
+pub(crate) fn spans_templates(rows: &[TemplateCounts<'_>]) -> bool {
+    rows.iter().filter(|r| r.template.is_some()).count() > 1
+}
+
+/// The breakdown as an aligned plain-text table, one line per template.
+pub(crate) fn breakdown_table(rows: &[TemplateCounts<'_>]) -> String {
+    let width = rows
+        .iter()
+        .map(|r| r.name().len())
+        .chain(["template".len()])
+        .max()
+        .unwrap_or(0);
+    let mut out = format!(
+        "{:<width$}  {:>7}  {:>7}  {:>7}  {:>7}\n",
+        "template", "created", "updated", "skipped", "ignored"
+    );
+    for row in rows {
+        let _ = writeln!(
+            out,
+            "{:<width$}  {:>7}  {:>7}  {:>7}  {:>7}",
+            row.name(),
+            row.created,
+            row.updated,
+            row.skipped,
+            row.ignored
+        );
+    }
+    out
+}
+
+/// How many of the largest files `--sizes` lists.
+pub(crate) const LARGEST_FILES: usize = 10;
+
+/// "1.5 MiB written to 12 file(s)" (or "would be written" in a dry run).
+pub(crate) fn size_line(dry_run: bool, report: &ApplyReport) -> String {
+    let files = report.entries.iter().filter(|e| e.bytes.is_some()).count();
+    let verb = if dry_run {
+        "would be written"
+    } else {
+        "written"
+    };
+    format!(
+        "{} {verb} to {files} file(s)",
+        format_size(report.bytes_written())
+    )
+}
+
+/// Binary units with one decimal: "512 B", "1.5 KiB", "20.0 MiB".
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// " (2 with no merge available)" after the skipped count, or nothing.
+pub(crate) fn merge_unavailable_count(paths: &[PathBuf]) -> String {
+    if paths.is_empty() {
+        return String::new();
+    }
+    format!(" ({} with no merge available)", paths.len())
+}
+
+/// "3 ignored (2 files, 1 directory with 40 files)" style counts.
+pub(crate) fn ignored_counts(report: &ApplyReport) -> String {
+    if report.pruned_dirs == 0 {
+        return format!("{} ignored", report.ignored_files);
+    }
+    let inside = if report.pruned_dir_files > 0 {
+        format!(" with {} file(s)", report.pruned_dir_files)
+    } else {
+        String::new()
+    };
+    format!(
+        "{} ignored ({} file(s), {} director{}{inside})",
+        report.ignored_paths,
+        report.ignored_files,
+        report.pruned_dirs,
+        if report.pruned_dirs == 1 { "y" } else { "ies" }
+    )
+}
+
+fn ignored_line(entry: &ReportEntry) -> String {
+    let EntryOutcome::Ignored(reason) = &entry.outcome else {
+        return format!("`{}`", display(&entry.path));
+    };
+    let reason = match reason {
+        IgnoreReason::Always => "always ignored".to_string(),
+        IgnoreReason::Git(rule) => format!("`{rule}`"),
+        IgnoreReason::NestedRepo(repo) => format!("nested repo `{}`", display(repo)),
+    };
+    match entry.pruned {
+        Some(PrunedDir { files: Some(files) }) => {
+            format!("`{}/` ({files} file(s)): {reason}", display(&entry.path))
+        }
+        Some(PrunedDir { files: None }) => format!("`{}/`: {reason}", display(&entry.path)),
+        None => format!("`{}`: {reason}", display(&entry.path)),
+    }
+}
+
+fn write_section(out: &mut String, title: &str, paths: &[String]) {
+    if paths.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "### {title} ({})\n", paths.len());
+    for path in paths {
+        let _ = writeln!(out, "- `{path}`");
+    }
+    out.push('\n');
+}
+
+fn display(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Short commit of the checkout `dir` lives in, if it is in a git repository.
+fn short_revision(dir: &Path) -> Option<String> {
+    let out = ProcessCommand::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let rev = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (!rev.is_empty()).then_some(rev)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_groups_files_by_action() {
+        let mut report = ApplyReport::default();
+        for (path, outcome) in [
+            ("README.md", EntryOutcome::Created),
+            ("backend/Cargo.toml", EntryOutcome::Updated),
+            ("LICENSE", EntryOutcome::Skipped),
+        ] {
+            report.absorb(ApplyReport {
+                created_files: usize::from(outcome == EntryOutcome::Created),
+                updated_files: usize::from(outcome == EntryOutcome::Updated),
+                skipped_files: usize::from(outcome == EntryOutcome::Skipped),
+                entries: vec![pinit_core::ReportEntry::new(path, outcome)],
+                ..Default::default()
+            });
+        }
+        let hooks = [HookOutcome {
+            phase: "after_all".to_string(),
+            command: "cargo fmt".to_string(),
+            result: HookResult::Ok,
+        }];
+        let summary = ApplySummary {
+            template: "rust",
+            dry_run: false,
+            branch: Some("pinit/update-2025-06"),
+            templates: vec![
+                AppliedTemplate {
+                    name: "common".to_string(),
+                    revision: Some("abc1234".to_string()),
+                    dest_prefix: None,
+                },
+                AppliedTemplate {
+                    name: "service".to_string(),
+                    revision: None,
+                    dest_prefix: Some(PathBuf::from("backend")),
+                },
+            ],
+            report: &report,
+            hooks: &hooks,
+            merge_unavailable: &[],
+            warnings: &[],
+            timings: &Timings::default(),
+            sizes: false,
+        };
+
+        let md = summary.markdown();
+        assert!(md.starts_with("## pinit apply `rust`\n\nBranch: `pinit/update-2025-06`\n"));
+        assert!(md.contains("| common | `abc1234` | `.` |\n"));
+        assert!(md.contains("| service | - | `backend/` |\n"));
+        assert!(md.contains("1 created, 1 updated, 1 skipped, 0 ignored."));
+        assert!(!md.contains("Ignored ("));
+        assert!(md.contains("### Created (1)\n\n- `README.md`\n"));
+        assert!(md.contains("### Updated (1)\n\n- `backend/Cargo.toml`\n"));
+        assert!(md.contains("<details><summary>Skipped (1)</summary>\n\n- `LICENSE`\n"));
+        assert!(md.contains("| after_all | `cargo fmt` | ok |"));
+        assert!(md.ends_with("|\n"));
+    }
+
+    #[test]
+    fn breakdown_groups_entries_by_template() {
+        let entry = |path: &str, outcome, template: Option<&str>| {
+            let mut entry = ReportEntry::new(path, outcome);
+            entry.template = template.map(str::to_string);
+            entry
+        };
+        let report = ApplyReport {
+            entries: vec![
+                entry("README.md", EntryOutcome::Created, Some("base")),
+                entry(".github/ci.yml", EntryOutcome::Updated, Some("ci")),
+                entry("Cargo.toml", EntryOutcome::Skipped, Some("base")),
+                entry("LICENSE", EntryOutcome::Created, None),
+            ],
+            ..Default::default()
+        };
+        let rows = template_breakdown(&report);
+        assert!(spans_templates(&rows));
+        assert_eq!(
+            breakdown_table(&rows),
+            "template     created  updated  skipped  ignored\n\
+             base               1        0        1        0\n\
+             ci                 0        1        0        0\n\
+             (generated)        1        0        0        0\n"
+        );
+        assert!(!spans_templates(&rows[1..]));
+    }
+
+    #[test]
+    fn format_size_uses_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(20 * 1024 * 1024), "20.0 MiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn ignored_counts_separate_files_and_pruned_dirs() {
+        let mut report = ApplyReport {
+            ignored_paths: 2,
+            ignored_files: 2,
+            ..Default::default()
+        };
+        assert_eq!(ignored_counts(&report), "2 ignored");
+
+        report.ignored_paths = 4;
+        report.pruned_dirs = 2;
+        assert_eq!(
+            ignored_counts(&report),
+            "4 ignored (2 file(s), 2 directories)"
+        );
+        report.pruned_dirs = 1;
+        report.ignored_paths = 3;
+        report.pruned_dir_files = 40;
+        assert_eq!(
+            ignored_counts(&report),
+            "3 ignored (2 file(s), 1 directory with 40 file(s))"
+        );
+
+        let mut entry =
+            ReportEntry::new("node_modules", EntryOutcome::Ignored(IgnoreReason::Always));
+        entry.pruned = Some(PrunedDir { files: Some(40) });
+        assert_eq!(
+            ignored_line(&entry),
+            "`node_modules/` (40 file(s)): always ignored"
+        );
+    }
+}
@@ -0,0 +1,452 @@
+#![forbid(unsafe_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pinit::{TemplateCommand, TemplateDiffArgs, TemplateSnapshotArgs, TemplateTestArgs};
+use pinit_core::ExistingFileAction;
+use pinit_core::manifest::{self, TemplateManifest, TemplateTest};
+use pinit_core::vars::{self, Variables};
+use regex::RegexBuilder;
+use similar::TextDiff;
+
+use crate::{
+    CliDecider, TemplateResolution, apply_template_stack, base_apply_options, format_command,
+    maybe_apply_gitignore, resolve_template_stack,
+};
+
+/// Project name used when neither the manifest nor `--var` sets one.
+const DEFAULT_TEST_PROJECT: &str = "example";
+
+pub(crate) fn cmd_template(
+    config_path: Option<&Path>,
+    command: TemplateCommand,
+) -> Result<(), String> {
+    match command {
+        TemplateCommand::Test(args) => cmd_template_test(config_path, &args),
+        TemplateCommand::Snapshot(args) => cmd_template_snapshot(config_path, &args),
+        TemplateCommand::Diff(args) => cmd_template_diff(config_path, &args),
+    }
+}
+
+fn load_manifests(
+    resolved: &TemplateResolution,
+) -> Result<Vec<(String, TemplateManifest)>, String> {
+    let mut manifests = Vec::new();
+    for entry in &resolved.templates {
+        if let Some(m) = manifest::load_manifest(&entry.dir).map_err(|e| e.to_string())? {
+            manifests.push((entry.name.clone(), m));
+        }
+    }
+    Ok(manifests)
+}
+
+/// Apply the whole stack (and its composed `.gitignore`) non-interactively,
+/// merging wherever files overlap.
+fn render_into(
+    resolved: &TemplateResolution,
+    variables: &Variables,
+    dest_dir: &Path,
+) -> Result<(), String> {
+    let mut decider = CliDecider::new(ExistingFileAction::Merge, true, resolved.overrides.clone());
+    let options = pinit_core::ApplyOptions {
+        variables: variables.clone(),
+        ..base_apply_options(false, None, None)
+    };
+    let report = apply_template_stack(resolved, dest_dir, &options, &mut decider)?;
+    maybe_apply_gitignore(
+        resolved.gitignore.as_deref(),
+        dest_dir,
+        pinit_core::ApplyOptions::default(),
+        &mut decider,
+        report,
+    )?;
+    Ok(())
+}
+
+fn cmd_template_test(config_path: Option<&Path>, args: &TemplateTestArgs) -> Result<(), String> {
+    let resolved = resolve_template_stack(config_path, &args.template)?;
+    let manifests = load_manifests(&resolved)?;
+    let variables = test_variables(&manifests, &args.vars)?;
+
+    let work_dir = temp_work_dir();
+    let project_name = variables
+        .get("project_name")
+        .map_or(DEFAULT_TEST_PROJECT, String::as_str);
+    let dest_dir = work_dir.join(vars::project_variables(project_name)["package_name"].as_str());
+    fs::create_dir_all(&dest_dir).map_err(|e| format!("{}: {e}", dest_dir.display()))?;
+
+    let result = run_test(&resolved, &manifests, &variables, &dest_dir);
+
+    if args.keep {
+        println!("kept {}", dest_dir.display());
+    } else {
+        let _ = fs::remove_dir_all(&work_dir);
+    }
+    result
+}
+
+fn run_test(
+    resolved: &TemplateResolution,
+    manifests: &[(String, TemplateManifest)],
+    variables: &Variables,
+    dest_dir: &Path,
+) -> Result<(), String> {
+    render_into(resolved, variables, dest_dir)?;
+
+    let mut passed = 0usize;
+    let mut failed = 0usize;
+    for (name, manifest) in manifests {
+        let Some(test) = &manifest.test else {
+            continue;
+        };
+        for outcome in check_assertions(test, variables, dest_dir) {
+            match &outcome.failure {
+                None => {
+                    passed += 1;
+                    println!("ok    {name}: {}", outcome.label);
+                }
+                Some(reason) => {
+                    failed += 1;
+                    println!("FAIL  {name}: {} ({reason})", outcome.label);
+                }
+            }
+        }
+    }
+
+    if passed + failed == 0 {
+        println!("template test: no assertions declared");
+        return Ok(());
+    }
+    println!("template test: {passed} passed, {failed} failed");
+    if failed > 0 {
+        return Err(format!("{failed} template assertion(s) failed"));
+    }
+    Ok(())
+}
+
+/// Merge manifest defaults, manifest test values, and `--var` overrides (in
+/// that order), then fill in the derived project variables.
+fn test_variables(
+    manifests: &[(String, TemplateManifest)],
+    overrides: &[String],
+) -> Result<Variables, String> {
+    let mut variables = Variables::new();
+    for (_, m) in manifests {
+        for (name, def) in &m.variables {
+            if let Some(default) = &def.default {
+                variables.insert(name.clone(), default.clone());
+            }
+        }
+    }
+    for (_, m) in manifests {
+        if let Some(test) = &m.test {
+            variables.extend(test.variables.clone());
+        }
+    }
+    for raw in overrides {
+        let (key, value) = raw
+            .split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+            .ok_or_else(|| format!("--var expects KEY=VALUE, got {raw:?}"))?;
+        variables.insert(key.trim().to_string(), value.to_string());
+    }
+
+    let project_name = variables
+        .get("project_name")
+        .cloned()
+        .unwrap_or_else(|| DEFAULT_TEST_PROJECT.to_string());
+    for (key, value) in vars::project_variables(&project_name) {
+        variables.entry(key).or_insert(value);
+    }
+
+    for (template, m) in manifests {
+        for (name, def) in &m.variables {
+            if def.required && !variables.contains_key(name) {
+                return Err(format!(
+                    "template {template} requires variable {name}; set it in [test.variables] or pass --var {name}=..."
+                ));
+            }
+        }
+    }
+    Ok(variables)
+}
+
+struct Outcome {
+    label: String,
+    failure: Option<String>,
+}
+
+fn check_assertions(test: &TemplateTest, variables: &Variables, dest_dir: &Path) -> Vec<Outcome> {
+    let mut out = Vec::new();
+
+    for rel in &test.exists {
+        let failure = (!dest_dir.join(rel).exists()).then(|| "missing".to_string());
+        out.push(Outcome {
+            label: format!("exists {}", rel.display()),
+            failure,
+        });
+    }
+
+    for m in &test.matches {
+        let label = format!("{} matches /{}/", m.path.display(), m.regex);
+        let failure = match RegexBuilder::new(&m.regex).multi_line(true).build() {
+            Err(e) => Some(format!("invalid regex: {e}")),
+            Ok(re) => match fs::read_to_string(dest_dir.join(&m.path)) {
+                Err(e) => Some(e.to_string()),
+                Ok(text) if re.is_match(&text) => None,
+                Ok(_) => Some("no match".to_string()),
+            },
+        };
+        out.push(Outcome { label, failure });
+    }
+
+    if !test.command.is_empty() {
+        let command: Vec<String> = test
+            .command
+            .iter()
+            .map(|arg| vars::render(arg, variables).unwrap_or_else(|| arg.clone()))
+            .collect();
+        let label = format!("command {}", format_command(&command));
+        let failure = match ProcessCommand::new(&command[0])
+            .args(&command[1..])
+            .current_dir(dest_dir)
+            .env("PINIT_PHASE", "template_test")
+            .env("PINIT_DEST", dest_dir)
+            .output()
+        {
+            Err(e) => Some(e.to_string()),
+            Ok(output) if output.status.success() => None,
+            Ok(output) => {
+                let code = output.status.code().unwrap_or(1);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Some(match stderr.trim().lines().last() {
+                    Some(last) => format!("exit {code}: {last}"),
+                    None => format!("exit {code}"),
+                })
+            }
+        };
+        out.push(Outcome { label, failure });
+    }
+
+    out
+}
+
+/// Render the template over `<fixture>/input` and compare with `<fixture>/expected`.
+fn cmd_template_snapshot(
+    config_path: Option<&Path>,
+    args: &TemplateSnapshotArgs,
+) -> Result<(), String> {
+    let input_dir = args.against.join("input");
+    let expected_dir = args.against.join("expected");
+    if !args.against.is_dir() {
+        return Err(format!(
+            "fixture directory not found: {}",
+            args.against.display()
+        ));
+    }
+    if !args.update && !expected_dir.is_dir() {
+        return Err(format!(
+            "{} does not exist; run with --update to create it",
+            expected_dir.display()
+        ));
+    }
+
+    let resolved = resolve_template_stack(config_path, &args.template)?;
+    let manifests = load_manifests(&resolved)?;
+    let variables = test_variables(&manifests, &args.vars)?;
+
+    let work_dir = temp_work_dir();
+    let result = snapshot_in(
+        &resolved,
+        &variables,
+        &input_dir,
+        &work_dir.join("output"),
+        &expected_dir,
+        args.update,
+    );
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+fn snapshot_in(
+    resolved: &TemplateResolution,
+    variables: &Variables,
+    input_dir: &Path,
+    output_dir: &Path,
+    expected_dir: &Path,
+    update: bool,
+) -> Result<(), String> {
+    fs::create_dir_all(output_dir).map_err(|e| format!("{}: {e}", output_dir.display()))?;
+    if input_dir.is_dir() {
+        copy_tree(input_dir, output_dir)?;
+    }
+    render_into(resolved, variables, output_dir)?;
+    compare_snapshot(output_dir, expected_dir, update)
+}
+
+fn compare_snapshot(output_dir: &Path, expected_dir: &Path, update: bool) -> Result<(), String> {
+    let actual = list_files(output_dir)?;
+    let expected = if expected_dir.is_dir() {
+        list_files(expected_dir)?
+    } else {
+        Vec::new()
+    };
+
+    let mut differing = 0usize;
+    for rel in &expected {
+        if actual.binary_search(rel).is_err() {
+            differing += 1;
+            println!("missing: {}", rel.display());
+        }
+    }
+    for rel in &actual {
+        let new = fs::read(output_dir.join(rel)).map_err(|e| format!("{}: {e}", rel.display()))?;
+        if expected.binary_search(rel).is_err() {
+            differing += 1;
+            println!("extra: {}", rel.display());
+            continue;
+        }
+        let old =
+            fs::read(expected_dir.join(rel)).map_err(|e| format!("{}: {e}", rel.display()))?;
+        if old != new {
+            differing += 1;
+            println!("changed: {}", rel.display());
+            if !update {
+                print_file_diff(rel, &old, &new);
+            }
+        }
+    }
+
+    if update {
+        if differing == 0 {
+            println!("snapshot: {} is up to date", expected_dir.display());
+            return Ok(());
+        }
+        if expected_dir.exists() {
+            fs::remove_dir_all(expected_dir)
+                .map_err(|e| format!("{}: {e}", expected_dir.display()))?;
+        }
+        copy_tree(output_dir, expected_dir)?;
+        println!(
+            "snapshot: updated {} ({differing} path(s))",
+            expected_dir.display()
+        );
+        return Ok(());
+    }
+
+    if differing > 0 {
+        return Err(format!(
+            "snapshot mismatch: {differing} path(s) differ; rerun with --update to accept"
+        ));
+    }
+    println!("snapshot: matches {}", expected_dir.display());
+    Ok(())
+}
+
+fn cmd_template_diff(config_path: Option<&Path>, args: &TemplateDiffArgs) -> Result<(), String> {
+    let (_path, cfg) = pinit_core::config::load_config(config_path).map_err(|e| e.to_string())?;
+    let resolver = crate::template_resolver(Some(&cfg))?;
+    let name = cfg.canonical_name(&args.name);
+    let old_dir = resolver
+        .resolve_template_dir_at(&cfg, name, &args.old_ref)
+        .map_err(|e| e.to_string())?;
+    let new_dir = resolver
+        .resolve_template_dir_at(&cfg, name, &args.new_ref)
+        .map_err(|e| e.to_string())?;
+
+    let old_files = list_files(&old_dir)?;
+    let new_files = list_files(&new_dir)?;
+    let (mut added, mut removed, mut changed) = (0usize, 0usize, 0usize);
+    for rel in &old_files {
+        if new_files.binary_search(rel).is_err() {
+            removed += 1;
+            println!("removed: {}", rel.display());
+        }
+    }
+    for rel in &new_files {
+        let new = fs::read(new_dir.join(rel)).map_err(|e| format!("{}: {e}", rel.display()))?;
+        if old_files.binary_search(rel).is_err() {
+            added += 1;
+            println!("added: {}", rel.display());
+            print_file_diff(rel, b"", &new);
+            continue;
+        }
+        let old = fs::read(old_dir.join(rel)).map_err(|e| format!("{}: {e}", rel.display()))?;
+        if old != new {
+            changed += 1;
+            println!("changed: {}", rel.display());
+            print_file_diff(rel, &old, &new);
+        }
+    }
+
+    println!(
+        "template diff {} {}..{}: {added} added, {removed} removed, {changed} changed",
+        args.name, args.old_ref, args.new_ref
+    );
+    Ok(())
+}
+
+/// Print a unified diff of `old` → `new` on stdout, labelled with `rel`.
+fn print_file_diff(rel: &Path, old: &[u8], new: &[u8]) {
+    match (std::str::from_utf8(old), std::str::from_utf8(new)) {
+        (Ok(old), Ok(new)) => {
+            let label = rel.display().to_string();
+            let diff = TextDiff::from_lines(old, new)
+                .unified_diff()
+                .header(&format!("a/{label}"), &format!("b/{label}"))
+                .to_string();
+            print!("{diff}");
+        }
+        _ => println!("  (binary: {} → {} bytes)", old.len(), new.len()),
+    }
+}
+
+/// Relative paths of every file under `root`, sorted. `.git` is skipped.
+fn list_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), String> {
+        let entries = fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("{}: {e}", dir.display()))?;
+            let path = entry.path();
+            if entry.file_name() == ".git" {
+                continue;
+            }
+            if path.is_dir() {
+                walk(root, &path, out)?;
+            } else if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut out = Vec::new();
+    walk(root, root, &mut out)?;
+    out.sort();
+    Ok(out)
+}
+
+fn copy_tree(src: &Path, dest: &Path) -> Result<(), String> {
+    for rel in list_files(src)? {
+        let to = dest.join(&rel);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+        }
+        fs::copy(src.join(&rel), &to).map_err(|e| format!("{}: {e}", to.display()))?;
+    }
+    Ok(())
+}
+
+fn temp_work_dir() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!(
+        "pinit-template-test-{}-{nanos}",
+        std::process::id()
+    ))
+}
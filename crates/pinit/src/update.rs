@@ -0,0 +1,265 @@
+#![forbid(unsafe_code)]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command as ProcessCommand, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_REPO: &str = env!("CARGO_PKG_REPOSITORY");
+const CACHE_FILE: &str = "update-check.json";
+const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
+/// Set on the detached worker process: the cache file it should write.
+const WORKER_CACHE_ENV: &str = "PINIT_UPDATE_CHECK_WORKER_CACHE";
+/// Set on the detached worker process: the repo whose releases it looks up.
+const WORKER_REPO_ENV: &str = "PINIT_UPDATE_CHECK_WORKER_REPO";
+
+/// An update check started alongside a command.
+pub(crate) enum UpdateCheck {
+    /// The cache was fresh; `None` means the last check found no release.
+    Cached(Option<String>),
+    /// A detached worker is looking up the latest release; its answer is
+    /// cached for the next run.
+    Started,
+}
+
+/// Start the update check if it is enabled via `PINIT_UPDATE_CHECK` or config.
+///
+/// A fresh cache entry (less than a day old, same repo) is used as-is;
+/// otherwise the latest release is looked up by a detached `pinit` process,
+/// which writes the cache even after this one has exited.
+pub(crate) fn start(config_path: Option<&Path>) -> Option<UpdateCheck> {
+    let def = pinit_core::config::load_config(config_path)
+        .ok()
+        .and_then(|(_, cfg)| cfg.update_check);
+    let enabled = env_override().unwrap_or_else(|| def.as_ref().is_some_and(|d| d.enabled));
+    if !enabled {
+        return None;
+    }
+
+    let repo = def
+        .and_then(|d| d.repo)
+        .unwrap_or_else(|| DEFAULT_REPO.to_string());
+    let cache_path = pinit_core::config::state_dir()?.join(CACHE_FILE);
+
+    if let Some((checked_at, latest)) = read_cache(&cache_path, &repo)
+        && now_secs().saturating_sub(checked_at) < CHECK_INTERVAL_SECS
+    {
+        tracing::debug!(latest = ?latest, "update check cached");
+        return Some(UpdateCheck::Cached(latest));
+    }
+
+    let spawned = std::env::current_exe().and_then(|exe| {
+        ProcessCommand::new(exe)
+            .env(WORKER_CACHE_ENV, &cache_path)
+            .env(WORKER_REPO_ENV, &repo)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    });
+    match spawned {
+        Ok(_) => tracing::debug!(repo = %repo, "update check started"),
+        Err(e) => tracing::debug!(error = %e, "update check could not start"),
+    }
+    Some(UpdateCheck::Started)
+}
+
+/// In the detached worker started by [`start`], look up the release, write the
+/// cache, and exit; otherwise return so the command runs as usual.
+pub(crate) fn run_worker_if_requested() {
+    let (Some(cache_path), Some(repo)) = (
+        std::env::var_os(WORKER_CACHE_ENV),
+        std::env::var(WORKER_REPO_ENV).ok(),
+    ) else {
+        return;
+    };
+    run_worker(&repo, Path::new(&cache_path), now_secs(), latest_release);
+    std::process::exit(0);
+}
+
+/// Look up the latest release of `repo` with `fetch` and cache the answer.
+fn run_worker(repo: &str, cache_path: &Path, now: u64, fetch: impl FnOnce(&str) -> Option<String>) {
+    let latest = fetch(repo);
+    write_cache(cache_path, repo, now, latest.as_deref());
+}
+
+impl UpdateCheck {
+    /// Print a one-line notice when a newer release is known. Never waits:
+    /// a check that is still running reports on a later run.
+    pub(crate) fn finish(self) {
+        if let UpdateCheck::Cached(Some(latest)) = self
+            && is_newer(&latest, CURRENT_VERSION)
+        {
+            eprintln!("notice: pinit {latest} is available (installed: {CURRENT_VERSION})");
+        }
+    }
+}
+
+fn env_override() -> Option<bool> {
+    let value = std::env::var("PINIT_UPDATE_CHECK").ok()?;
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn read_cache(path: &Path, repo: &str) -> Option<(u64, Option<String>)> {
+    let text = fs::read_to_string(path).ok()?;
+    let value: Value = serde_json::from_str(&text).ok()?;
+    if value.get("repo")?.as_str()? != repo {
+        return None;
+    }
+    let checked_at = value.get("checked_at")?.as_u64()?;
+    let latest = value
+        .get("latest")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Some((checked_at, latest))
+}
+
+fn write_cache(path: &Path, repo: &str, checked_at: u64, latest: Option<&str>) {
+    let body = json!({ "repo": repo, "checked_at": checked_at, "latest": latest });
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(path, format!("{body:#}\n")));
+    if let Err(e) = result {
+        tracing::debug!(error = %e, path = %path.display(), "update check cache write failed");
+    }
+}
+
+/// Highest `vX.Y.Z` tag in `repo`, without the `v`. Pre-release tags are ignored.
+fn latest_release(repo: &str) -> Option<String> {
+    let out = ProcessCommand::new("git")
+        .args(["ls-remote", "--tags", "--refs", repo])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        tracing::debug!(
+            repo,
+            stderr = %String::from_utf8_lossy(&out.stderr).trim(),
+            "update check failed"
+        );
+        return None;
+    }
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| line.split_once("refs/tags/").map(|(_, tag)| tag.trim()))
+        .filter_map(|tag| parse_version(tag).map(|v| (v, tag.trim_start_matches('v').to_string())))
+        .max_by_key(|(v, _)| *v)
+        .map(|(_, tag)| tag)
+}
+
+fn parse_version(tag: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = tag.strip_prefix('v').unwrap_or(tag).split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_release_tags_and_ignores_prereleases() {
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("0.10.0"), Some((0, 10, 0)));
+        assert_eq!(parse_version("v1.2.3-rc.1"), None);
+        assert_eq!(parse_version("v1.2"), None);
+        assert_eq!(parse_version("v1.2.3.4"), None);
+    }
+
+    #[test]
+    fn worker_caches_its_answer() {
+        let dir = std::env::temp_dir().join(format!("pinit-update-worker-{}", std::process::id()));
+        let path = dir.join(CACHE_FILE);
+        run_worker("repo", &path, 42, |repo| {
+            assert_eq!(repo, "repo");
+            Some("99.0.0".to_string())
+        });
+        assert_eq!(
+            read_cache(&path, "repo"),
+            Some((42, Some("99.0.0".to_string())))
+        );
+        run_worker("repo", &path, 43, |_| None);
+        assert_eq!(read_cache(&path, "repo"), Some((43, None)));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cache_is_keyed_by_repo() {
+        let dir = std::env::temp_dir().join(format!("pinit-update-cache-{}", std::process::id()));
+        let path = dir.join(CACHE_FILE);
+        write_cache(&path, "repo", 42, Some("99.0.0"));
+        assert_eq!(
+            read_cache(&path, "repo"),
+            Some((42, Some("99.0.0".to_string())))
+        );
+        assert_eq!(read_cache(&path, "other"), None);
+        write_cache(&path, "repo", 43, None);
+        assert_eq!(read_cache(&path, "repo"), Some((43, None)));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn latest_release_reads_version_tags() {
+        let dir = std::env::temp_dir().join(format!("pinit-update-repo-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            let status = ProcessCommand::new("git")
+                .args([
+                    "-c",
+                    "user.name=pinit",
+                    "-c",
+                    "user.email=pinit@example.invalid",
+                ])
+                .args(args)
+                .current_dir(&dir)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "release"]);
+        for tag in ["v1.2.0", "v99.0.0", "v100.0.0-rc.1", "nightly"] {
+            git(&["tag", tag]);
+        }
+        assert_eq!(
+            latest_release(dir.to_str().unwrap()).as_deref(),
+            Some("99.0.0")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compares_numerically() {
+        assert!(is_newer("0.10.0", "0.9.9"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("garbage", "0.1.0"));
+    }
+}
@@ -0,0 +1,113 @@
+#![forbid(unsafe_code)]
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use pinit::VersionArgs;
+use pinit_core::config::ConfigError;
+use serde_json::json;
+
+/// What `pinit version` reports: enough to answer "which pinit, built how, using what".
+struct BuildInfo {
+    commit: Option<&'static str>,
+    build_date: String,
+    target: &'static str,
+    profile: &'static str,
+    features: Vec<&'static str>,
+    merge_drivers: Vec<&'static str>,
+    config: Result<Option<PathBuf>, String>,
+    cache_dir: Result<PathBuf, String>,
+    state_dir: Option<PathBuf>,
+}
+
+pub(crate) fn cmd_version(config_path: Option<&Path>, args: &VersionArgs) -> Result<(), String> {
+    let info = BuildInfo::collect(config_path);
+    if args.json {
+        println!("{:#}", info.json());
+    } else {
+        print!("{}", info.text());
+    }
+    Ok(())
+}
+
+impl BuildInfo {
+    fn collect(config_path: Option<&Path>) -> Self {
+        let (config, cfg) = match pinit_core::config::load_config(config_path) {
+            Ok((path, cfg)) => (Ok(Some(path)), Some(cfg)),
+            Err(ConfigError::NotFound) => (Ok(None), None),
+            Err(e) => (Err(e.to_string()), None),
+        };
+        let cache_dir = crate::template_resolver(cfg.as_ref()).map(|r| r.cache_dir().to_path_buf());
+        let epoch: u64 = env!("PINIT_BUILD_EPOCH").parse().unwrap_or(0);
+        let (year, month, day) = pinit_core::time::civil_from_days((epoch / 86_400) as i64);
+        Self {
+            commit: Some(env!("PINIT_GIT_COMMIT")).filter(|c| !c.is_empty()),
+            build_date: format!("{year:04}-{month:02}-{day:02}"),
+            target: env!("PINIT_TARGET"),
+            profile: env!("PINIT_PROFILE"),
+            features: env!("PINIT_FEATURES")
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .collect(),
+            merge_drivers: pinit_core::merge_driver_names(),
+            config,
+            cache_dir,
+            state_dir: pinit_core::config::state_dir(),
+        }
+    }
+
+    fn text(&self) -> String {
+        let list = |items: &[&str]| {
+            if items.is_empty() {
+                "none".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        let config = match &self.config {
+            Ok(Some(path)) => path.display().to_string(),
+            Ok(None) => "none found".to_string(),
+            Err(e) => format!("error: {e}"),
+        };
+        let cache = match &self.cache_dir {
+            Ok(dir) => dir.display().to_string(),
+            Err(e) => format!("unavailable: {e}"),
+        };
+        let state = self
+            .state_dir
+            .as_deref()
+            .map_or_else(|| "unavailable".to_string(), |d| d.display().to_string());
+        let rows = [
+            ("commit", self.commit.unwrap_or("unknown").to_string()),
+            ("built", self.build_date.clone()),
+            ("target", format!("{} ({})", self.target, self.profile)),
+            ("features", list(&self.features)),
+            ("merge drivers", list(&self.merge_drivers)),
+            ("config", config),
+            ("cache", cache),
+            ("state", state),
+        ];
+        let mut out = format!("pinit {}\n", env!("CARGO_PKG_VERSION"));
+        for (label, value) in rows {
+            let _ = writeln!(out, "{:<15}{value}", format!("{label}:"));
+        }
+        out
+    }
+
+    fn json(&self) -> serde_json::Value {
+        let path = |p: &Path| p.display().to_string();
+        json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "commit": self.commit,
+            "build_date": self.build_date,
+            "target": self.target,
+            "profile": self.profile,
+            "features": self.features,
+            "merge_drivers": self.merge_drivers,
+            "config": self.config.as_ref().ok().and_then(|c| c.as_deref()).map(path),
+            "config_error": self.config.as_ref().err(),
+            "cache_dir": self.cache_dir.as_deref().ok().map(path),
+            "state_dir": self.state_dir.as_deref().map(path),
+        })
+    }
+}
@@ -0,0 +1,190 @@
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+use std::process::Command as ProcessCommand;
+use std::time::SystemTime;
+
+use pinit_core::config::DirtyDestAction;
+use pinit_core::time::utc_date;
+use pinit_core::vars::{self, Variables};
+use pinit_core::{Warning, WarningKind};
+
+use crate::remote::git;
+
+/// Branch name used by `--branch-per-apply` when config sets no `apply_branch`.
+pub(crate) const DEFAULT_APPLY_BRANCH: &str = "pinit/update-{{ year }}-{{ month }}";
+
+/// Apply `action` when `dest_dir` has uncommitted changes; `warn` returns the warning.
+///
+/// Only the destination subtree is considered, so applying into one package of
+/// a monorepo ignores work in progress elsewhere. Destinations that are not in
+/// a git worktree (or a missing `git`) are never dirty.
+pub(crate) fn check_dirty(
+    dest_dir: &Path,
+    action: DirtyDestAction,
+    template: &str,
+) -> Result<Option<Warning>, String> {
+    let Some(changes) = uncommitted_changes(dest_dir) else {
+        return Ok(None);
+    };
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    tracing::debug!(dest_dir = %dest_dir.display(), changes = ?changes, action = ?action, "destination is dirty");
+
+    let count = changes.len();
+    match action {
+        DirtyDestAction::Warn => Ok(Some(Warning::new(
+            WarningKind::DirtyDest,
+            format!(
+                "{} has uncommitted changes ({count} path(s)); template changes will mix with them",
+                dest_dir.display()
+            ),
+        ))),
+        DirtyDestAction::Abort => Err(format!(
+            "{} has uncommitted changes ({count} path(s)); commit or stash them first (dirty_dest = \"abort\")",
+            dest_dir.display()
+        )),
+        DirtyDestAction::Stash => {
+            let message = format!("pinit: before applying {template}");
+            git(
+                dest_dir,
+                &[
+                    "stash",
+                    "push",
+                    "--include-untracked",
+                    "-m",
+                    &message,
+                    "--",
+                    ".",
+                ],
+            )?;
+            note!(
+                "stashed uncommitted changes ({count} path(s)); restore them with `git stash pop`"
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Create and check out a fresh branch in `dest_dir` for this apply.
+///
+/// `name_template` may use `{{ template }}`, `{{ date }}` (UTC, `YYYY-MM-DD`),
+/// `{{ year }}`, and `{{ month }}`. If the branch already exists, `-2`, `-3`, ...
+/// is appended. Returns the branch name.
+pub(crate) fn create_apply_branch(
+    dest_dir: &Path,
+    name_template: &str,
+    template: &str,
+    dry_run: bool,
+) -> Result<String, String> {
+    if uncommitted_changes(dest_dir).is_none() {
+        return Err(format!(
+            "--branch-per-apply needs a git worktree destination ({} is not one)",
+            dest_dir.display()
+        ));
+    }
+
+    let base = branch_name(name_template, template, today());
+    if !git_ok(dest_dir, &["check-ref-format", "--branch", &base]) {
+        return Err(format!("invalid apply branch name: {base:?}"));
+    }
+    let mut name = base.clone();
+    let mut n = 2;
+    while git_ok(
+        dest_dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{name}"),
+        ],
+    ) {
+        name = format!("{base}-{n}");
+        n += 1;
+    }
+
+    if dry_run {
+        note!("dry-run: would create branch {name}");
+        return Ok(name);
+    }
+    git(dest_dir, &["checkout", "-q", "-b", &name])?;
+    note!("created branch {name}");
+    Ok(name)
+}
+
+fn branch_name(name_template: &str, template: &str, (year, month, day): (i32, u32, u32)) -> String {
+    let mut vars = Variables::new();
+    // Paths make poor branch names; keep only the last component.
+    let template = Path::new(template)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(template);
+    vars.insert("template".to_string(), template.to_string());
+    vars.insert("date".to_string(), format!("{year:04}-{month:02}-{day:02}"));
+    vars.insert("year".to_string(), format!("{year:04}"));
+    vars.insert("month".to_string(), format!("{month:02}"));
+    vars::render(name_template, &vars).unwrap_or_else(|| name_template.to_string())
+}
+
+/// Today's UTC date as (year, month, day).
+fn today() -> (i32, u32, u32) {
+    utc_date(SystemTime::now())
+}
+
+/// `git status --porcelain` lines for `dir`, or `None` outside a git worktree.
+fn uncommitted_changes(dir: &Path) -> Option<Vec<String>> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let out = ProcessCommand::new("git")
+        .args(["status", "--porcelain", "--", "."])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        tracing::debug!(
+            dir = %dir.display(),
+            stderr = %String::from_utf8_lossy(&out.stderr).trim(),
+            "git status failed; not checking for uncommitted changes"
+        );
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+fn git_ok(dir: &Path, args: &[&str]) -> bool {
+    ProcessCommand::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_branch_name_templates() {
+        let date = (2025, 6, 15);
+        assert_eq!(
+            branch_name(DEFAULT_APPLY_BRANCH, "rust", date),
+            "pinit/update-2025-06"
+        );
+        assert_eq!(
+            branch_name(
+                "chore/{{ template }}-{{ date }}",
+                "/tmp/templates/base",
+                date
+            ),
+            "chore/base-2025-06-15"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+#![forbid(unsafe_code)]
+
+//! Shell completion scripts rendered from the clap definitions.
+//!
+//! The scripts walk the words typed so far down the subcommand tree (`pinit
+//! template test`, `pinit cache verify`, ...) to find the active command, then
+//! offer its flags or its own subcommands. Template names (and their aliases)
+//! come from the user's config at completion time, via the hidden
+//! `pinit list --names`.
+
+use clap::{Arg, Command};
+
+/// Shells we ship completion scripts for, with their conventional file names.
+pub(crate) const SHELLS: &[(&str, &str)] = &[
+    ("bash", "pinit.bash"),
+    ("zsh", "_pinit"),
+    ("fish", "pinit.fish"),
+];
+
+pub(crate) fn render(shell: &str, cmd: &Command) -> Option<String> {
+    match shell {
+        "bash" => Some(bash(cmd)),
+        "zsh" => Some(zsh(cmd)),
+        "fish" => Some(fish(cmd)),
+        _ => None,
+    }
+}
+
+fn visible_subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands().filter(|s| !s.is_hide_set())
+}
+
+fn visible_flags(cmd: &Command) -> impl Iterator<Item = &Arg> {
+    cmd.get_arguments()
+        .filter(|a| !a.is_hide_set() && !a.is_positional())
+}
+
+/// Every visible subcommand below `cmd`, depth first, with the names leading to it.
+fn subcommand_paths(cmd: &Command) -> Vec<(Vec<&str>, &Command)> {
+    let mut out = Vec::new();
+    for sub in visible_subcommands(cmd) {
+        out.push((vec![sub.get_name()], sub));
+        for (mut path, nested) in subcommand_paths(sub) {
+            path.insert(0, sub.get_name());
+            out.push((path, nested));
+        }
+    }
+    out
+}
+
+/// Whether the first positional argument is a template/target/recipe name.
+fn takes_template_name(cmd: &Command) -> bool {
+    cmd.get_positionals()
+        .next()
+        .is_some_and(|a| a.get_id() == "template")
+}
+
+fn subcommand_names(cmd: &Command) -> Vec<&str> {
+    visible_subcommands(cmd).map(Command::get_name).collect()
+}
+
+fn flag_words(cmd: &Command) -> Vec<String> {
+    let mut words = Vec::new();
+    for arg in visible_flags(cmd) {
+        if let Some(long) = arg.get_long() {
+            words.push(format!("--{long}"));
+        }
+        if let Some(short) = arg.get_short() {
+            words.push(format!("-{short}"));
+        }
+    }
+    words
+}
+
+fn bash(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let paths = subcommand_paths(cmd);
+
+    let mut out = String::new();
+    out.push_str(&format!("_{name}() {{\n"));
+    out.push_str("    local cur cmd opts children at i\n");
+    out.push_str("    COMPREPLY=()\n");
+    out.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    out.push_str("    cmd=\"\"\n");
+    out.push_str("    at=0\n");
+    out.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+    out.push_str("        case \"$cmd/${COMP_WORDS[i]}\" in\n");
+    let steps: Vec<String> = paths
+        .iter()
+        .map(|(path, _)| {
+            let (last, parents) = path.split_last().expect("paths are never empty");
+            format!("{}/{last}", parents.join("/"))
+        })
+        .collect();
+    out.push_str(&format!(
+        "            {}) cmd=\"${{cmd:+$cmd/}}${{COMP_WORDS[i]}}\"; at=$i ;;\n",
+        steps.join("|")
+    ));
+    out.push_str("        esac\n");
+    out.push_str("    done\n\n");
+    out.push_str("    case \"$cmd\" in\n");
+    for (path, sub) in &paths {
+        out.push_str(&format!(
+            "        {}) opts=\"{}\"; children=\"{}\" ;;\n",
+            path.join("/"),
+            flag_words(sub).join(" "),
+            subcommand_names(sub).join(" ")
+        ));
+    }
+    out.push_str(&format!(
+        "        *) opts=\"{}\"; children=\"{}\" ;;\n",
+        flag_words(cmd).join(" "),
+        subcommand_names(cmd).join(" ")
+    ));
+    out.push_str("    esac\n\n");
+    let named: Vec<String> = paths
+        .iter()
+        .filter(|(_, sub)| takes_template_name(sub))
+        .map(|(path, _)| path.join("/"))
+        .collect();
+    if !named.is_empty() {
+        out.push_str("    case \"$cmd\" in\n");
+        out.push_str(&format!("        {})\n", named.join("|")));
+        out.push_str("            local npos=0 j\n");
+        out.push_str("            for ((j = at + 1; j < COMP_CWORD; j++)); do\n");
+        out.push_str("                [[ \"${COMP_WORDS[j]}\" != -* ]] && npos=$((npos + 1))\n");
+        out.push_str("            done\n");
+        out.push_str("            if [[ $npos -eq 0 && \"$cur\" != -* ]]; then\n");
+        out.push_str(&format!(
+            "                COMPREPLY=($(compgen -W \"$({name} list --names 2>/dev/null)\" -- \"$cur\"))\n"
+        ));
+        out.push_str("                return\n");
+        out.push_str("            fi\n");
+        out.push_str("            ;;\n");
+        out.push_str("    esac\n\n");
+    }
+    out.push_str("    if [[ \"$cur\" == -* ]]; then\n");
+    out.push_str("        COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))\n");
+    out.push_str("    elif [[ -n \"$children\" ]]; then\n");
+    out.push_str("        COMPREPLY=($(compgen -W \"$children\" -- \"$cur\"))\n");
+    out.push_str("    fi\n");
+    out.push_str("}\n\n");
+    out.push_str(&format!("complete -o default -F _{name} {name}\n"));
+    out
+}
+
+fn zsh(cmd: &Command) -> String {
+    // Reuse the bash function through zsh's compatibility layer rather than
+    // maintaining a second hand-written grammar.
+    format!(
+        "#compdef {name}\n\nautoload -U +X bashcompinit && bashcompinit\n\n{}",
+        bash(cmd),
+        name = cmd.get_name()
+    )
+}
+
+/// Fish condition for "`path` is the active command": every name on the path has been
+/// seen, and none of the command's own subcommands has.
+fn fish_condition(path: &[&str], cmd: &Command) -> String {
+    if path.is_empty() {
+        return "__fish_use_subcommand".to_string();
+    }
+    let mut parts: Vec<String> = path
+        .iter()
+        .map(|name| format!("__fish_seen_subcommand_from {name}"))
+        .collect();
+    let children = subcommand_names(cmd);
+    if !children.is_empty() {
+        parts.push(format!(
+            "not __fish_seen_subcommand_from {}",
+            children.join(" ")
+        ));
+    }
+    parts.join("; and ")
+}
+
+fn fish(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let mut commands = vec![(Vec::new(), cmd)];
+    commands.extend(subcommand_paths(cmd));
+
+    let mut out = String::new();
+    for (path, command) in &commands {
+        let condition = fish_condition(path, command);
+        for sub in visible_subcommands(command) {
+            let mut line = format!(
+                "complete -c {name} -n \"{condition}\" -f -a {}",
+                sub.get_name()
+            );
+            if let Some(about) = sub.get_about() {
+                line.push_str(&format!(" -d {}", fish_quote(&about.to_string())));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+        if takes_template_name(command) {
+            out.push_str(&format!(
+                "complete -c {name} -n \"{condition}\" -a \"({name} list --names 2>/dev/null)\"\n"
+            ));
+        }
+        for arg in visible_flags(command) {
+            out.push_str(&fish_arg(name, &condition, arg));
+        }
+    }
+    out
+}
+
+fn fish_arg(name: &str, condition: &str, arg: &Arg) -> String {
+    let mut line = format!("complete -c {name} -n \"{condition}\"");
+    if let Some(short) = arg.get_short() {
+        line.push_str(&format!(" -s {short}"));
+    }
+    if let Some(long) = arg.get_long() {
+        line.push_str(&format!(" -l {long}"));
+    }
+    if arg.get_action().takes_values() {
+        let possible: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        if possible.is_empty() {
+            line.push_str(" -r");
+        } else {
+            line.push_str(&format!(" -x -a {}", fish_quote(&possible.join(" "))));
+        }
+    }
+    if let Some(help) = arg.get_help() {
+        line.push_str(&format!(" -d {}", fish_quote(&help.to_string())));
+    }
+    line.push('\n');
+    line
+}
+
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -6,6 +6,8 @@ use std::process::Command;
 
 use clap::{Parser, Subcommand};
 
+mod completions;
+
 #[derive(Parser, Debug)]
 #[command(name = "xtask")]
 #[command(about = "Project maintenance tasks")]
@@ -16,11 +18,26 @@ struct Xtask {
 
 #[derive(Subcommand, Debug)]
 enum Task {
-    /// Generate a manpage for the pinit CLI.
+    /// Generate man pages (one per subcommand) and the markdown CLI reference.
     Man {
         /// Output directory (default: target/man)
         #[arg(long = "out-dir", default_value = "target/man")]
         out_dir: PathBuf,
+
+        /// Markdown CLI reference path (default: docs/cli.md)
+        #[arg(long = "markdown", default_value = "docs/cli.md")]
+        markdown: PathBuf,
+
+        /// Fail if the markdown reference is out of date instead of writing it
+        #[arg(long = "check")]
+        check: bool,
+    },
+
+    /// Write the bash, zsh, and fish completion scripts.
+    Completions {
+        /// Output directory (default: target/completions)
+        #[arg(long = "out-dir", default_value = "target/completions")]
+        out_dir: PathBuf,
     },
 
     /// Build and install the pinit CLI into ~/.bin for local testing.
@@ -33,13 +50,45 @@ enum Task {
         #[arg(long = "profile", default_value = "release")]
         profile: String,
     },
+
+    /// Build release archives (binary, man pages, completions) with checksums.
+    Dist {
+        /// Target triple to build (repeatable; default: the host target)
+        #[arg(long = "target")]
+        targets: Vec<String>,
+
+        /// Output directory for archives (default: target/distrib)
+        #[arg(long = "out-dir", default_value = "target/distrib")]
+        out_dir: PathBuf,
+
+        /// Cargo profile to build (default: dist)
+        #[arg(long = "profile", default_value = "dist")]
+        profile: String,
+    },
 }
 
 fn main() -> Result<(), String> {
     let task = Xtask::parse();
     match task.command {
-        Task::Man { out_dir } => generate_manpage(&out_dir),
+        Task::Man {
+            out_dir,
+            markdown,
+            check,
+        } => {
+            if !check {
+                generate_manpages(&out_dir)?;
+            }
+            generate_cli_markdown(&markdown, check)
+        }
+        Task::Completions { out_dir } => {
+            write_completions(&built_command(), &workspace_root().join(out_dir))
+        }
         Task::Install { bin_dir, profile } => install_cli(&bin_dir, &profile),
+        Task::Dist {
+            targets,
+            out_dir,
+            profile,
+        } => dist(&targets, &out_dir, &profile),
     }
 }
 
@@ -52,22 +101,168 @@ fn workspace_root() -> PathBuf {
         .to_path_buf()
 }
 
-fn generate_manpage(out_dir: &Path) -> Result<(), String> {
+fn generate_manpages(out_dir: &Path) -> Result<(), String> {
     let out_dir = workspace_root().join(out_dir);
     fs::create_dir_all(&out_dir).map_err(|e| format!("{}: {e}", out_dir.display()))?;
+    write_manpages(&built_command(), &out_dir)
+}
 
-    let cmd = pinit::command();
-    let man = clap_mangen::Man::new(cmd);
-    let mut buffer: Vec<u8> = Vec::new();
-    man.render(&mut buffer)
-        .map_err(|e| format!("render manpage: {e}"))?;
+/// The pinit command tree, built so subcommand display names (and therefore
+/// man page names such as `pinit-apply.1`) are filled in.
+fn built_command() -> clap::Command {
+    let mut cmd = pinit::command().disable_help_subcommand(true);
+    cmd.build();
+    cmd
+}
+
+fn write_completions(cmd: &clap::Command, out_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(out_dir).map_err(|e| format!("{}: {e}", out_dir.display()))?;
+    for (shell, file_name) in completions::SHELLS {
+        let script = completions::render(shell, cmd).unwrap_or_default();
+        let path = out_dir.join(file_name);
+        fs::write(&path, script).map_err(|e| format!("{}: {e}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
 
-    let man_path = out_dir.join("pinit.1");
-    fs::write(&man_path, buffer).map_err(|e| format!("{}: {e}", man_path.display()))?;
+fn write_manpages(cmd: &clap::Command, out_dir: &Path) -> Result<(), String> {
+    let man = clap_mangen::Man::new(cmd.clone());
+    let man_path = man
+        .generate_to(out_dir)
+        .map_err(|e| format!("render manpage: {e}"))?;
     println!("wrote {}", man_path.display());
+
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        write_manpages(sub, out_dir)?;
+    }
     Ok(())
 }
 
+fn generate_cli_markdown(path: &Path, check: bool) -> Result<(), String> {
+    let path = workspace_root().join(path);
+    let rendered = cli_markdown(&built_command());
+
+    if check {
+        let current = fs::read_to_string(&path).unwrap_or_default();
+        if current != rendered {
+            return Err(format!(
+                "{} is out of date; run `cargo xtask man`",
+                path.display()
+            ));
+        }
+        println!("{} is up to date", path.display());
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+    }
+    fs::write(&path, rendered).map_err(|e| format!("{}: {e}", path.display()))?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+fn cli_markdown(cmd: &clap::Command) -> String {
+    let mut out = String::new();
+    out.push_str("# pinit command-line reference\n\n");
+    out.push_str(
+        "<!-- Generated by `cargo xtask man` from the clap definitions. Do not edit. -->\n",
+    );
+    write_command_markdown(&mut out, cmd);
+    out
+}
+
+fn write_command_markdown(out: &mut String, cmd: &clap::Command) {
+    let name = cmd.get_display_name().unwrap_or(cmd.get_name());
+    out.push_str(&format!("\n## `{}`\n\n", name.replace('-', " ")));
+    if let Some(about) = cmd.get_long_about().or(cmd.get_about()) {
+        out.push_str(&format!("{about}\n\n"));
+    }
+    let usage = cmd.clone().render_usage().to_string();
+    let usage = usage.trim_start_matches("Usage: ");
+    out.push_str(&format!("```text\n{usage}\n```\n"));
+
+    let visible =
+        |a: &&clap::Arg| !a.is_hide_set() && !matches!(a.get_id().as_str(), "help" | "version");
+    let positionals: Vec<_> = cmd
+        .get_arguments()
+        .filter(visible)
+        .filter(|a| a.is_positional())
+        .collect();
+    let options: Vec<_> = cmd
+        .get_arguments()
+        .filter(visible)
+        .filter(|a| !a.is_positional())
+        .collect();
+
+    if !positionals.is_empty() {
+        out.push_str("\nArguments:\n\n");
+        for arg in positionals {
+            let value = arg
+                .get_value_names()
+                .and_then(|v| v.first())
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| arg.get_id().to_string().to_uppercase());
+            let value = if arg.is_required_set() {
+                format!("<{value}>")
+            } else {
+                format!("[{value}]")
+            };
+            out.push_str(&format!("- `{value}`{}\n", arg_help(arg)));
+        }
+    }
+
+    if !options.is_empty() {
+        out.push_str("\nOptions:\n\n");
+        for arg in options {
+            let mut flags = Vec::new();
+            if let Some(short) = arg.get_short() {
+                flags.push(format!("-{short}"));
+            }
+            if let Some(long) = arg.get_long() {
+                flags.push(format!("--{long}"));
+            }
+            let mut flag = flags.join(", ");
+            if arg.get_action().takes_values() {
+                let value = arg
+                    .get_value_names()
+                    .and_then(|v| v.first())
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| arg.get_id().to_string().to_uppercase());
+                flag.push_str(&format!(" <{value}>"));
+            }
+            out.push_str(&format!("- `{flag}`{}\n", arg_help(arg)));
+        }
+    }
+
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        write_command_markdown(out, sub);
+    }
+}
+
+fn arg_help(arg: &clap::Arg) -> String {
+    let mut help = arg.get_help().map(|h| format!(": {h}")).unwrap_or_default();
+    let possible: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect();
+    if !possible.is_empty() {
+        help.push_str(&format!(" [possible values: {}]", possible.join(", ")));
+    }
+    if arg.get_action().takes_values() && !arg.get_default_values().is_empty() {
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|v| v.to_string_lossy().into_owned())
+            .collect();
+        help.push_str(&format!(" [default: {}]", defaults.join(", ")));
+    }
+    help
+}
+
 fn install_cli(bin_dir: &str, profile: &str) -> Result<(), String> {
     let bin_dir = expand_tilde(bin_dir)?;
     fs::create_dir_all(&bin_dir).map_err(|e| format!("{}: {e}", bin_dir.display()))?;
@@ -93,6 +288,173 @@ fn install_cli(bin_dir: &str, profile: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Files copied from the repo root into every archive.
+const DIST_DOCS: &[&str] = &["README.md", "CHANGELOG.md", "LICENSE-MIT", "LICENSE-APACHE"];
+
+fn dist(targets: &[String], out_dir: &Path, profile: &str) -> Result<(), String> {
+    let root = workspace_root();
+    let out_dir = root.join(out_dir);
+    fs::create_dir_all(&out_dir).map_err(|e| format!("{}: {e}", out_dir.display()))?;
+
+    let targets = if targets.is_empty() {
+        vec![host_target()?]
+    } else {
+        targets.to_vec()
+    };
+    let version = env!("CARGO_PKG_VERSION");
+    let cmd = built_command();
+
+    let mut checksums = Vec::new();
+    for target in &targets {
+        let status = Command::new("cargo")
+            .args(["build", "-p", "pinit", "--locked", "--target", target])
+            .args(profile_args(profile))
+            .current_dir(&root)
+            .status()
+            .map_err(|e| format!("failed to run cargo: {e}"))?;
+        if !status.success() {
+            return Err(format!(
+                "cargo build --target {target} failed with status {}",
+                status.code().unwrap_or(1)
+            ));
+        }
+
+        let windows = target.contains("windows");
+        let exe = if windows { "pinit.exe" } else { "pinit" };
+        let bin_path = root
+            .join("target")
+            .join(target)
+            .join(profile_dir(profile))
+            .join(exe);
+        if !bin_path.exists() {
+            return Err(format!("built binary not found at {}", bin_path.display()));
+        }
+
+        // Names follow the `package.metadata.binstall` layout in crates/pinit/Cargo.toml.
+        let stem = format!("pinit-v{version}-{target}");
+        let stage = out_dir.join(&stem);
+        if stage.exists() {
+            fs::remove_dir_all(&stage).map_err(|e| format!("{}: {e}", stage.display()))?;
+        }
+        fs::create_dir_all(stage.join("man")).map_err(|e| format!("{}: {e}", stage.display()))?;
+
+        copy_file(&bin_path, &stage.join(exe))?;
+        set_executable(&stage.join(exe))?;
+        for doc in DIST_DOCS {
+            copy_file(&root.join(doc), &stage.join(doc))?;
+        }
+        write_manpages(&cmd, &stage.join("man"))?;
+        write_completions(&cmd, &stage.join("completions"))?;
+
+        let archive_name = if windows {
+            format!("{stem}.zip")
+        } else {
+            format!("{stem}.tar.xz")
+        };
+        let archive_path = out_dir.join(&archive_name);
+        if archive_path.exists() {
+            fs::remove_file(&archive_path)
+                .map_err(|e| format!("{}: {e}", archive_path.display()))?;
+        }
+        // Zip archives hold the files at the top level; tarballs wrap them in `{stem}/`.
+        let mut archive = if windows {
+            let mut zip = Command::new("zip");
+            zip.arg("-q")
+                .arg("-r")
+                .arg("-X")
+                .arg(&archive_path)
+                .arg(".")
+                .current_dir(&stage);
+            zip
+        } else {
+            let mut tar = Command::new("tar");
+            tar.args(["-cJf", &archive_name, &stem])
+                .current_dir(&out_dir);
+            tar
+        };
+        run_tool(&mut archive)?;
+
+        let digest = sha256_hex(&archive_path)?;
+        let line = format!("{digest}  {archive_name}\n");
+        let sum_path = out_dir.join(format!("{archive_name}.sha256"));
+        fs::write(&sum_path, &line).map_err(|e| format!("{}: {e}", sum_path.display()))?;
+        checksums.push(line);
+        println!("wrote {}", archive_path.display());
+    }
+
+    let sums_path = out_dir.join("SHA256SUMS");
+    fs::write(&sums_path, checksums.concat())
+        .map_err(|e| format!("{}: {e}", sums_path.display()))?;
+    println!("wrote {}", sums_path.display());
+    Ok(())
+}
+
+fn host_target() -> Result<String, String> {
+    let out = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .map_err(|e| format!("failed to run rustc: {e}"))?;
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|host| host.trim().to_string())
+        .ok_or_else(|| "could not determine host target from rustc -vV".to_string())
+}
+
+/// SHA-256 via the platform tool (`sha256sum` on Linux, `shasum` on macOS).
+fn sha256_hex(path: &Path) -> Result<String, String> {
+    let attempts: [(&str, &[&str]); 2] = [("sha256sum", &[]), ("shasum", &["-a", "256"])];
+    for (tool, args) in attempts {
+        let Ok(out) = Command::new(tool).args(args).arg(path).output() else {
+            continue;
+        };
+        if out.status.success()
+            && let Some(digest) = String::from_utf8_lossy(&out.stdout)
+                .split_whitespace()
+                .next()
+        {
+            return Ok(digest.to_string());
+        }
+    }
+    Err(format!(
+        "could not checksum {}: need sha256sum or shasum",
+        path.display()
+    ))
+}
+
+fn run_tool(cmd: &mut Command) -> Result<(), String> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let status = cmd
+        .status()
+        .map_err(|e| format!("failed to run {program}: {e}"))?;
+    if !status.success() {
+        return Err(format!(
+            "{program} failed with status {}",
+            status.code().unwrap_or(1)
+        ));
+    }
+    Ok(())
+}
+
+fn copy_file(src: &Path, dest: &Path) -> Result<(), String> {
+    fs::copy(src, dest)
+        .map(|_| ())
+        .map_err(|e| format!("{} -> {}: {e}", src.display(), dest.display()))
+}
+
+fn profile_args(profile: &str) -> Vec<String> {
+    if profile == "release" {
+        vec!["--release".to_string()]
+    } else {
+        vec!["--profile".to_string(), profile.to_string()]
+    }
+}
+
+/// Cargo's output directory name for a profile (`dev` builds land in `debug`).
+fn profile_dir(profile: &str) -> &str {
+    if profile == "dev" { "debug" } else { profile }
+}
+
 fn build_cli(root: &Path, profile: &str) -> Result<std::process::ExitStatus, String> {
     let mut cmd = Command::new("cargo");
     cmd.arg("build").arg("-p").arg("pinit").current_dir(root);